@@ -46,6 +46,7 @@ await contract.get_owners_by_store_id(store_id);
 /**
  * Retrieves an item from a particular store.
  *
+ * @deprecated Use `get_item_metadata`, which takes the store ID explicitly.
  * @function get_item_by_store_id
  * @param {string} item_id - The item ID.
  * @returns {ItemMetadata} An object representing the item from the specified store.
@@ -53,17 +54,20 @@ await contract.get_owners_by_store_id(store_id);
 await contract.get_item_by_store_id(item_id);
 
 /**
- * Adds a new item to a store. This is an action that changes the contract state.
+ * Adds a new item to a store. Only callable by a store owner or manager. This is an action that changes the contract state.
  *
  * @function add_store_item
  * @param {string} item_id - The item ID.
  * @param {string} store_id - The store ID.
- * @param {string} item_name - The name of the item.
+ * @param {string} item_name - The name of the item, unique within the store once normalized (see `get_item_id_by_name`).
  * @param {U128} item_price - The price of the item.
  * @param {string} item_img_url - The image URL of the item.
+ * @param {ItemCondition|null} item_condition - The grade of a second-hand item, optional.
+ * @param {string|null} item_upc - The item's 12-digit UPC or 13-digit EAN barcode, optional.
+ * @param {string|null} item_video_url - An `https://` URL to a video of the item, optional.
  * @returns {void}
  */
-await contract.add_store_item(item_id, store_id, item_name, item_price, item_img_url);
+await contract.add_store_item(item_id, store_id, item_name, item_price, item_img_url, item_condition, item_upc, item_video_url);
 
 /**
  * Allows a user to buy an item from a store. This is an action that changes the contract state.
@@ -71,21 +75,20 @@ await contract.add_store_item(item_id, store_id, item_name, item_price, item_img
  * @function buy
  * @param {string} item_id - The item ID.
  * @param {string} store_id - The store ID.
- * @returns {string} A JSON string containing a success message and the transaction ID.
+ * @returns {BuyReceipt} The purchase receipt, including the transaction ID.
  */
 await contract.buy(item_id, store_id);
 
 /**
- * Adds a log entry to the contract state. This is an action that changes the contract state.
+ * Adds an administrative annotation to the audit logs, recorded with the overseer as actor. Only callable by the overseer. This is an action that changes the contract state.
  *
- * @function add_log
+ * @function admin_add_log
  * @param {string} action - The action that occurred.
- * @param {string} actor - The account ID that performed the action.
  * @param {string} entity - The entity on which the action was performed.
  * @param {string} extra - Any extra information about the log event.
  * @returns {string} The id of the log confirming the log was added.
  */
-await contract.add_log(action, actor, entity, extra);
+await contract.admin_add_log(action, entity, extra);
 
 
 /**
@@ -113,4 +116,2322 @@ await contract.get_log(log_id);
  * @param {string} ft_account_id - The account ID of the FT.
  * @returns {boolean} A boolean indicating whether the specified FT is approved.
  */
-await contract.is_ft_approved(ft_account_id);
\ No newline at end of file
+await contract.is_ft_approved(ft_account_id);
+
+/**
+ * Overrides an item's price in an emergency. Only callable by the overseer. This is an action that changes the contract state.
+ *
+ * @function overseer_override_item_price
+ * @param {string} item_id - The item ID.
+ * @param {string} store_id - The store ID.
+ * @param {U128} new_price - The corrected price of the item.
+ * @returns {void}
+ */
+await contract.overseer_override_item_price(item_id, store_id, new_price);
+
+/**
+ * Buys an item on behalf of another account (e.g. as a gift). The caller pays and the recipient becomes the owner. This is an action that changes the contract state.
+ *
+ * @function proxy_buy
+ * @param {string} item_id - The item ID.
+ * @param {string} store_id - The store ID.
+ * @param {string} recipient - The account ID that receives the item.
+ * @returns {BuyReceipt} The purchase receipt, including the transaction ID.
+ */
+await contract.proxy_buy(item_id, store_id, recipient);
+
+/**
+ * Retrieves the items an account has paid for.
+ *
+ * @function get_buyer_purchases
+ * @param {string} account_id - The buyer's account ID.
+ * @param {number} [from_index] - The index to start from.
+ * @param {number} [limit] - The maximum number of entries to return.
+ * @returns {Array<string>} An array of `store_id.item_id` keys.
+ */
+await contract.get_buyer_purchases(account_id, from_index, limit);
+
+/**
+ * Sets the minimum deposit a store accepts for any purchase. Only callable by a store owner. This is an action that changes the contract state.
+ *
+ * @function set_store_minimum_deposit
+ * @param {string} store_id - The store ID.
+ * @param {U128} min_deposit - The minimum deposit in yoctoNEAR.
+ * @returns {void}
+ */
+await contract.set_store_minimum_deposit(store_id, min_deposit);
+
+/**
+ * Retrieves the minimum deposit a store accepts.
+ *
+ * @function get_store_minimum_deposit
+ * @param {string} store_id - The store ID.
+ * @returns {U128} The minimum deposit in yoctoNEAR, `0` if none is set.
+ */
+await contract.get_store_minimum_deposit(store_id);
+
+/**
+ * Retrieves a store's best selling items.
+ *
+ * @function get_store_items_by_sales
+ * @param {string} store_id - The store ID.
+ * @param {number} limit - The maximum number of items to return.
+ * @returns {Array<[string, number]>} `[item_id, purchase_count]` pairs sorted by purchase count descending.
+ */
+await contract.get_store_items_by_sales(store_id, limit);
+
+/**
+ * Retrieves the rank (1-indexed) of an item among its store's items by purchase count.
+ *
+ * @function get_item_sales_rank
+ * @param {string} item_id - The item ID.
+ * @param {string} store_id - The store ID.
+ * @returns {number} The item's sales rank.
+ */
+await contract.get_item_sales_rank(item_id, store_id);
+
+/**
+ * Buys an item through an affiliate code. The affiliate's commission is deducted from the seller's proceeds. This is an action that changes the contract state.
+ *
+ * @function buy_with_affiliate
+ * @param {string} item_id - The item ID.
+ * @param {string} store_id - The store ID.
+ * @param {string} affiliate_code - The affiliate code.
+ * @returns {BuyReceipt} The purchase receipt, including the transaction ID.
+ */
+await contract.buy_with_affiliate(item_id, store_id, affiliate_code);
+
+/**
+ * Creates an affiliate code for a store. Only callable by a store owner. This is an action that changes the contract state.
+ *
+ * @function create_affiliate_code
+ * @param {string} code - The affiliate code.
+ * @param {string} store_id - The store ID.
+ * @param {string} affiliate - The account ID earning the commission.
+ * @param {number} commission_bps - The commission in basis points of the item price.
+ * @returns {void}
+ */
+await contract.create_affiliate_code(code, store_id, affiliate, commission_bps);
+
+/**
+ * Deactivates an affiliate code. Only callable by an owner of the code's store. This is an action that changes the contract state.
+ *
+ * @function deactivate_affiliate_code
+ * @param {string} code - The affiliate code.
+ * @returns {void}
+ */
+await contract.deactivate_affiliate_code(code);
+
+/**
+ * Retrieves the total commission earned by an affiliate.
+ *
+ * @function get_affiliate_earnings
+ * @param {string} account_id - The affiliate's account ID.
+ * @returns {U128} The total commission in yoctoNEAR.
+ */
+await contract.get_affiliate_earnings(account_id);
+
+/**
+ * Retrieves the stores created by any account.
+ *
+ * @function get_stores_for_account
+ * @param {string} account_id - The account ID.
+ * @param {number} [from_index] - The index to start from.
+ * @param {number} [limit] - The maximum number of stores to return.
+ * @returns {Array<string>} An array of store IDs.
+ */
+await contract.get_stores_for_account(account_id, from_index, limit);
+
+/**
+ * Retrieves the number of stores created by an account.
+ *
+ * @function get_store_count_for_account
+ * @param {string} account_id - The account ID.
+ * @returns {number} The number of stores.
+ */
+await contract.get_store_count_for_account(account_id);
+
+/**
+ * Retrieves the logs recorded for a given action on a store or its items.
+ *
+ * @function get_logs_by_store_and_action
+ * @param {string} store_id - The store ID.
+ * @param {string} action - The action, e.g. `buy`.
+ * @param {number} [from_index] - The index to start from.
+ * @param {number} [limit] - The maximum number of logs to return.
+ * @returns {Array<Log>} The matching logs.
+ */
+await contract.get_logs_by_store_and_action(store_id, action, from_index, limit);
+
+/**
+ * Retrieves the number of logs recorded for a given action on a store or its items.
+ *
+ * @function get_logs_count_by_store_and_action
+ * @param {string} store_id - The store ID.
+ * @param {string} action - The action, e.g. `buy`.
+ * @returns {number} The number of matching logs.
+ */
+await contract.get_logs_count_by_store_and_action(store_id, action);
+
+/**
+ * Schedules an item price change for a future timestamp. Only callable by a store owner. This is an action that changes the contract state.
+ *
+ * @function schedule_price_change
+ * @param {string} item_id - The item ID.
+ * @param {string} store_id - The store ID.
+ * @param {U128} new_price - The price to apply.
+ * @param {number} execute_at - The block timestamp (in nanoseconds) from which the change can be executed.
+ * @returns {string} The schedule ID.
+ */
+await contract.schedule_price_change(item_id, store_id, new_price, execute_at);
+
+/**
+ * Applies a scheduled price change once it is due. Callable by anyone. This is an action that changes the contract state.
+ *
+ * @function execute_scheduled_price_change
+ * @param {string} schedule_id - The schedule ID.
+ * @returns {void}
+ */
+await contract.execute_scheduled_price_change(schedule_id);
+
+/**
+ * Cancels a pending scheduled price change. Only callable by a store owner. This is an action that changes the contract state.
+ *
+ * @function cancel_scheduled_price_change
+ * @param {string} schedule_id - The schedule ID.
+ * @returns {void}
+ */
+await contract.cancel_scheduled_price_change(schedule_id);
+
+/**
+ * Retrieves the pending price changes of a store's items.
+ *
+ * @function get_pending_price_changes_by_store
+ * @param {string} store_id - The store ID.
+ * @returns {Array<[string, ScheduledPriceChange]>} `[schedule_id, change]` pairs.
+ */
+await contract.get_pending_price_changes_by_store(store_id);
+
+/**
+ * Opens a group buy on a store item. The target count can't exceed the item's remaining supply. Only callable by a store owner. This is an action that changes the contract state.
+ *
+ * @function create_group_buy
+ * @param {string} item_id - The item ID.
+ * @param {string} store_id - The store ID.
+ * @param {number} target_count - The number of participants needed to unlock the discount.
+ * @param {U128} discount_price - The price each participant pays.
+ * @param {number} deadline - The block timestamp (in nanoseconds) by which the target must be reached.
+ * @returns {string} The group buy ID.
+ */
+await contract.create_group_buy(item_id, store_id, target_count, discount_price, deadline);
+
+/**
+ * Joins a group buy by attaching its discount price. Any amount above the discount price is refunded. Fails once participants would exceed the item's remaining supply. This is an action that changes the contract state.
+ *
+ * @function join_group_buy
+ * @param {string} group_buy_id - The group buy ID.
+ * @returns {void}
+ */
+await contract.join_group_buy(group_buy_id);
+
+/**
+ * Settles a group buy that reached its target, crediting the pooled deposits to the store's balance. If the store no longer owns the item or its remaining supply can't cover every participant, all participants are refunded instead. Callable by anyone. This is an action that changes the contract state.
+ *
+ * @function execute_group_buy
+ * @param {string} group_buy_id - The group buy ID.
+ * @returns {void}
+ */
+await contract.execute_group_buy(group_buy_id);
+
+/**
+ * Cancels a group buy whose deadline passed without reaching its target, refunding all participants. Callable by anyone. This is an action that changes the contract state.
+ *
+ * @function cancel_group_buy
+ * @param {string} group_buy_id - The group buy ID.
+ * @returns {void}
+ */
+await contract.cancel_group_buy(group_buy_id);
+
+/**
+ * Retrieves a group buy.
+ *
+ * @function get_group_buy
+ * @param {string} group_buy_id - The group buy ID.
+ * @returns {GroupBuy|null} The group buy, if it exists.
+ */
+await contract.get_group_buy(group_buy_id);
+
+/**
+ * Adds a new item to a store under a generated ID. Only callable by a store owner or manager. This is an action that changes the contract state.
+ *
+ * @function add_store_item_auto
+ * @param {string} store_id - The store ID.
+ * @param {string} item_name - The name of the item.
+ * @param {U128} item_price - The price of the item.
+ * @param {string} item_img_url - The image URL of the item.
+ * @returns {string} The generated item ID.
+ */
+await contract.add_store_item_auto(store_id, item_name, item_price, item_img_url);
+
+/**
+ * Reprices up to 100 items of a store at once, logging one entry per repriced item. Only callable by a store owner. This is an action that changes the contract state.
+ *
+ * @function update_item_price_batch
+ * @param {string} store_id - The store ID.
+ * @param {Array<[string, U128]>} updates - `[item_id, new_price]` pairs.
+ * @param {boolean} abort_on_error - Whether a missing item aborts the whole batch.
+ * @returns {Array<[string, boolean]>} `[item_id, updated]` pairs in request order.
+ */
+await contract.update_item_price_batch(store_id, updates, abort_on_error);
+
+/**
+ * Retrieves an item's metadata given both its ID and its store's ID.
+ *
+ * @function get_item_metadata
+ * @param {string} item_id - The item ID.
+ * @param {string} store_id - The store ID.
+ * @returns {ItemMetadata|null} The item's metadata, if it exists in that store.
+ */
+await contract.get_item_metadata(item_id, store_id);
+
+/**
+ * Migrates a v1 contract state to the current layout after a code upgrade. Store and item listings are rebuilt from the v1 items, which count as listed at the migration. Stores without items can't be recovered from v1 state and aren't listed. Only callable by the overseer, once. This is an action that changes the contract state.
+ *
+ * @function migrate_from_v1
+ * @returns {Contract} The migrated Contract instance.
+ */
+await contract.migrate_from_v1();
+
+/**
+ * Retrieves the total amount paid for a store's items.
+ *
+ * @function get_store_total_revenue
+ * @param {string} store_id - The store ID.
+ * @returns {U128} The total revenue in yoctoNEAR.
+ */
+await contract.get_store_total_revenue(store_id);
+
+/**
+ * Retrieves the stores with the highest revenue. Scans every store, so an off-chain indexer is recommended for large marketplaces.
+ *
+ * @function get_top_stores_by_revenue
+ * @param {number} limit - The maximum number of stores to return, capped at 50.
+ * @returns {Array<[string, U128]>} `[store_id, revenue]` pairs sorted by revenue descending.
+ */
+await contract.get_top_stores_by_revenue(limit);
+
+/**
+ * Retrieves the stores listing the most items. Scans every store, so an off-chain indexer is recommended for large marketplaces.
+ *
+ * @function get_top_stores_by_item_count
+ * @param {number} limit - The maximum number of stores to return, capped at 50.
+ * @returns {Array<[string, number]>} `[store_id, item_count]` pairs sorted by item count descending.
+ */
+await contract.get_top_stores_by_item_count(limit);
+
+/**
+ * Retrieves the funds held by the contract on behalf of a store.
+ *
+ * @function get_store_balance
+ * @param {string} store_id - The store ID.
+ * @returns {U128} The store's balance in yoctoNEAR.
+ */
+await contract.get_store_balance(store_id);
+
+/**
+ * Withdraws funds held on behalf of a store to the calling owner. Only callable by a store owner. This is an action that changes the contract state.
+ *
+ * @function withdraw_store_balance
+ * @param {string} store_id - The store ID.
+ * @param {U128} amount - The amount to withdraw in yoctoNEAR.
+ * @returns {void}
+ */
+await contract.withdraw_store_balance(store_id, amount);
+
+/**
+ * Sets the protocol fee kept by the treasury on every sale, capped at 1000 basis points. Only callable by the overseer. This is an action that changes the contract state.
+ *
+ * @function set_protocol_fee
+ * @param {number} fee_bps - The fee in basis points.
+ * @returns {void}
+ */
+await contract.set_protocol_fee(fee_bps);
+
+/**
+ * Retrieves the protocol fee charged on every sale.
+ *
+ * @function get_protocol_fee
+ * @returns {number} The fee in basis points.
+ */
+await contract.get_protocol_fee();
+
+/**
+ * Retrieves the protocol fees accumulated by the treasury.
+ *
+ * @function get_treasury_balance
+ * @returns {U128} The treasury balance in yoctoNEAR.
+ */
+await contract.get_treasury_balance();
+
+/**
+ * Withdraws accumulated protocol fees to the overseer. Only callable by the overseer, and only until an overseer multisig is set up. This is an action that changes the contract state.
+ *
+ * @function withdraw_treasury
+ * @param {U128} amount - The amount to withdraw in yoctoNEAR.
+ * @returns {void}
+ */
+await contract.withdraw_treasury(amount);
+
+/**
+ * Sets the currency a store prices its items in. Only allowed before the store lists any item, so all of its items share one currency. Only callable by a store owner. This is an action that changes the contract state.
+ *
+ * @function set_store_currency
+ * @param {string} store_id - The store ID.
+ * @param {string|null} ft_account_id - An approved fungible token contract, or `null` for NEAR.
+ * @returns {void}
+ */
+await contract.set_store_currency(store_id, ft_account_id);
+
+/**
+ * Retrieves the currency a store prices its items in.
+ *
+ * @function get_store_currency
+ * @param {string} store_id - The store ID.
+ * @returns {string|null} The fungible token contract, or `null` for NEAR.
+ */
+await contract.get_store_currency(store_id);
+
+/**
+ * Creates a giveaway of a store-owned item. Only callable by a store owner. This is an action that changes the contract state.
+ *
+ * @function create_giveaway
+ * @param {string} item_id - The item ID.
+ * @param {string} store_id - The store ID.
+ * @param {number} max_entries - The maximum number of entrants.
+ * @param {number} end_time - The block timestamp, in nanoseconds, at which entries close.
+ * @returns {string} The giveaway ID.
+ */
+await contract.create_giveaway(item_id, store_id, max_entries, end_time);
+
+/**
+ * Enters the signer into a giveaway, once per account and before it ends. This is an action that changes the contract state.
+ *
+ * @function enter_giveaway
+ * @param {string} giveaway_id - The giveaway ID.
+ * @returns {void}
+ */
+await contract.enter_giveaway(giveaway_id);
+
+/**
+ * Draws a random winner once a giveaway has ended and transfers them the item. If the store no longer owns the item, e.g. because it was sold, the giveaway closes without a winner. Only callable by a store owner. This is an action that changes the contract state.
+ *
+ * @function execute_giveaway
+ * @param {string} giveaway_id - The giveaway ID.
+ * @returns {string|null} The winner, or `null` if nobody entered or the item is no longer available.
+ */
+await contract.execute_giveaway(giveaway_id);
+
+/**
+ * Retrieves the entrants of a giveaway. This is a view method.
+ *
+ * @function get_giveaway_entries
+ * @param {string} giveaway_id - The giveaway ID.
+ * @returns {Array<string>} The entrants' account IDs.
+ */
+await contract.get_giveaway_entries(giveaway_id);
+
+/**
+ * Retrieves the winner of an executed giveaway.
+ *
+ * @function get_giveaway_result
+ * @param {string} giveaway_id - The giveaway ID.
+ * @returns {string|null} The winner, or `null` if nobody entered.
+ */
+await contract.get_giveaway_result(giveaway_id);
+
+/**
+ * Proposes an overseer action to be voted on until `end_time`, at least 3 days away. Only `set_overseer`, taking the new overseer's account ID as calldata, is supported. Only callable by the overseer, or by a multisig member once the multisig is set up. This is an action that changes the contract state.
+ *
+ * @function create_proposal
+ * @param {string} description - What the proposal is about.
+ * @param {string} target_fn - The action to execute, `set_overseer`.
+ * @param {string} calldata - The action's argument.
+ * @param {number} end_time - The block timestamp, in nanoseconds, at which voting closes.
+ * @returns {string} The proposal ID.
+ */
+await contract.create_proposal(description, target_fn, calldata, end_time);
+
+/**
+ * Casts the signer's vote on a proposal, once per account and before voting closes. This is an action that changes the contract state.
+ *
+ * @function vote_on_proposal
+ * @param {string} proposal_id - The proposal ID.
+ * @param {boolean} approve - Whether the vote is in favour.
+ * @returns {void}
+ */
+await contract.vote_on_proposal(proposal_id, approve);
+
+/**
+ * Applies a proposal whose voting ended with at least 3 votes cast and more votes for than against. Only callable by the overseer, or by a multisig member once the multisig is set up. With a multisig, the overseer change becomes a pending `set_overseer` admin action that needs the multisig's threshold of approvals. This is an action that changes the contract state.
+ *
+ * @function execute_proposal
+ * @param {string} proposal_id - The proposal ID.
+ * @returns {void}
+ */
+await contract.execute_proposal(proposal_id);
+
+/**
+ * Rates a store the signer bought from, replacing any previous rating by the signer. This is an action that changes the contract state.
+ *
+ * @function rate_store
+ * @param {string} store_id - The store ID.
+ * @param {number} score - The score, from 1 to 5.
+ * @param {string} comment - The review.
+ * @returns {void}
+ */
+await contract.rate_store(store_id, score, comment);
+
+/**
+ * Retrieves a store's ratings.
+ *
+ * @function get_store_ratings
+ * @param {string} store_id - The store ID.
+ * @param {number|null} from_index - The index to start from.
+ * @param {number|null} limit - The maximum number of ratings to return.
+ * @returns {Array<StoreRating>} The store's ratings.
+ */
+await contract.get_store_ratings(store_id, from_index, limit);
+
+/**
+ * Retrieves a store's average rating.
+ *
+ * @function get_store_average_rating
+ * @param {string} store_id - The store ID.
+ * @returns {number|null} The average score, or `null` if the store has no ratings.
+ */
+await contract.get_store_average_rating(store_id);
+
+/**
+ * Rates an item the signer bought, replacing any previous rating by the signer. Fails if a moderator removed the signer's review of the item. This is an action that changes the contract state.
+ *
+ * @function rate_item
+ * @param {string} item_id - The item ID.
+ * @param {string} store_id - The store ID.
+ * @param {number} score - The score, from 1 to 5.
+ * @param {string} review - The review.
+ * @param {ReviewMedia[]|null} media - Up to 5 `https://` photos or videos, optional.
+ * @returns {void}
+ */
+await contract.rate_item(item_id, store_id, score, review, media);
+
+/**
+ * Retrieves an item's ratings.
+ *
+ * @function get_item_ratings
+ * @param {string} item_id - The item ID.
+ * @param {string} store_id - The store ID.
+ * @param {number|null} from_index - The index to start from.
+ * @param {number|null} limit - The maximum number of ratings to return.
+ * @returns {Array<ItemRating>} The item's ratings.
+ */
+await contract.get_item_ratings(item_id, store_id, from_index, limit);
+
+/**
+ * Retrieves an item's average rating, rounded to the nearest score.
+ *
+ * @function get_item_average_rating
+ * @param {string} item_id - The item ID.
+ * @param {string} store_id - The store ID.
+ * @returns {number|null} The average score, or `null` if the item has no ratings.
+ */
+await contract.get_item_average_rating(item_id, store_id);
+
+/**
+ * Sets the royalty a store earns when its items are resold, capped at 5000 basis points. Only callable by a store owner. This is an action that changes the contract state.
+ *
+ * @function set_store_royalty
+ * @param {string} store_id - The store ID.
+ * @param {number} royalty_bps - The royalty in basis points.
+ * @returns {void}
+ */
+await contract.set_store_royalty(store_id, royalty_bps);
+
+/**
+ * Retrieves a store's resale royalty.
+ *
+ * @function get_store_royalty
+ * @param {string} store_id - The store ID.
+ * @returns {number} The royalty in basis points.
+ */
+await contract.get_store_royalty(store_id);
+
+/**
+ * Retrieves the total royalties a store earned from resales.
+ *
+ * @function get_total_royalties_earned_by_store
+ * @param {string} store_id - The store ID.
+ * @returns {U128} The royalties in yoctoNEAR.
+ */
+await contract.get_total_royalties_earned_by_store(store_id);
+
+/**
+ * Retrieves the royalties a store earned from an item's resales.
+ *
+ * @function get_item_royalties_earned
+ * @param {string} item_id - The item ID.
+ * @param {string} store_id - The store ID.
+ * @returns {U128} The royalties in yoctoNEAR.
+ */
+await contract.get_item_royalties_earned(item_id, store_id);
+
+/**
+ * Retrieves the `royalty_transfer` logs of a store's items.
+ *
+ * @function get_royalty_history_by_store
+ * @param {string} store_id - The store ID.
+ * @param {number|null} from_index - The index to start from.
+ * @param {number|null} limit - The maximum number of logs to return.
+ * @returns {Array<Log>} The matching logs.
+ */
+await contract.get_royalty_history_by_store(store_id, from_index, limit);
+
+/**
+ * Adds up to 20 co-owners to a store in one call. Only callable by a store owner. This is an action that changes the contract state.
+ *
+ * @function add_store_owners_batch
+ * @param {string} store_id - The store ID.
+ * @param {Array<string>} new_owners - The account IDs of the new owners.
+ * @returns {void}
+ */
+await contract.add_store_owners_batch(store_id, new_owners);
+
+/**
+ * Removes an unsold item from a store. Only callable by a store owner or manager, or the overseer. Owners can't take a store below the platform's minimum item count. This is an action that changes the contract state.
+ *
+ * @function remove_store_item
+ * @param {string} item_id - The item ID.
+ * @param {string} store_id - The store ID.
+ * @returns {void}
+ */
+await contract.remove_store_item(item_id, store_id);
+
+/**
+ * Retrieves the stores listing an item.
+ *
+ * @function get_stores_by_item
+ * @param {string} item_id - The item ID.
+ * @returns {Array<string>} The store IDs.
+ */
+await contract.get_stores_by_item(item_id);
+
+/**
+ * Retrieves the number of stores listing an item.
+ *
+ * @function get_item_store_count
+ * @param {string} item_id - The item ID.
+ * @returns {number} The number of stores.
+ */
+await contract.get_item_store_count(item_id);
+
+/**
+ * Retrieves items across all stores, along with their metadata.
+ *
+ * @function get_all_items
+ * @param {number|null} from_index - The index to start from.
+ * @param {number|null} limit - The maximum number of items to return.
+ * @returns {Array<[string, ItemMetadata]>} `[store_and_item_id, metadata]` pairs.
+ */
+await contract.get_all_items(from_index, limit);
+
+/**
+ * Retrieves the number of items across all stores.
+ *
+ * @function get_total_item_count
+ * @returns {number} The number of items.
+ */
+await contract.get_total_item_count();
+
+/**
+ * Caps how many times a single account can buy an item. A `max` of 0 lifts the cap. Only callable by a store owner. This is an action that changes the contract state.
+ *
+ * @function set_item_purchase_limit
+ * @param {string} item_id - The item ID.
+ * @param {string} store_id - The store ID.
+ * @param {number} max - The maximum number of purchases per account.
+ * @returns {void}
+ */
+await contract.set_item_purchase_limit(item_id, store_id, max);
+
+/**
+ * Retrieves how many times a single account can buy an item.
+ *
+ * @function get_item_purchase_limit
+ * @param {string} item_id - The item ID.
+ * @param {string} store_id - The store ID.
+ * @returns {number|null} The cap, or `null` if uncapped.
+ */
+await contract.get_item_purchase_limit(item_id, store_id);
+
+/**
+ * Retrieves how many times an account bought an item.
+ *
+ * @function get_buyer_item_purchase_count
+ * @param {string} account_id - The buyer's account ID.
+ * @param {string} item_id - The item ID.
+ * @param {string} store_id - The store ID.
+ * @returns {number} The number of purchases.
+ */
+await contract.get_buyer_item_purchase_count(account_id, item_id, store_id);
+
+/**
+ * Buys an item from a geo-restricted store. The region is self-attested by the buyer, so the restriction relies on front-end enforcement. This is an action that changes the contract state.
+ *
+ * @function buy_with_region
+ * @param {string} item_id - The item ID.
+ * @param {string} store_id - The store ID.
+ * @param {string} buyer_region - The buyer's ISO 3166-1 alpha-2 region code.
+ * @returns {BuyReceipt} The purchase receipt.
+ */
+await contract.buy_with_region(item_id, store_id, buyer_region);
+
+/**
+ * Restricts the regions a store accepts purchases from. Purchases from a restricted store must go through `buy_with_region`. Only callable by a store owner. This is an action that changes the contract state.
+ *
+ * @function set_store_geo_restriction
+ * @param {string} store_id - The store ID.
+ * @param {StoreGeoPolicy|null} policy - The policy, or `null` to lift the restriction.
+ * @returns {void}
+ */
+await contract.set_store_geo_restriction(store_id, policy);
+
+/**
+ * Retrieves the regions a store accepts purchases from.
+ *
+ * @function get_store_geo_restriction
+ * @param {string} store_id - The store ID.
+ * @returns {StoreGeoPolicy|null} The policy, or `null` if unrestricted.
+ */
+await contract.get_store_geo_restriction(store_id);
+
+/**
+ * Retrieves an item's price in every store listing it, cheapest first, capped at 20 stores.
+ *
+ * @function get_cross_store_prices_for_item
+ * @param {string} item_id - The item ID.
+ * @returns {Array<[string, U128]>} `[store_id, price]` pairs sorted by price ascending.
+ */
+await contract.get_cross_store_prices_for_item(item_id);
+
+/**
+ * Retrieves the store listing an item at the lowest price.
+ *
+ * @function get_cheapest_store_for_item
+ * @param {string} item_id - The item ID.
+ * @returns {[string, U128]|null} The `[store_id, price]` pair, or `null` if no store lists the item.
+ */
+await contract.get_cheapest_store_for_item(item_id);
+
+/**
+ * Replays the audit log hash chain, checking every log links to the one recorded before it.
+ *
+ * @function verify_log_chain
+ * @returns {boolean} Whether the chain is intact.
+ */
+await contract.verify_log_chain();
+
+/**
+ * Requires buyers of an item to hold a minimum balance of an approved fungible token. Gated items can only be bought through `buy_with_ft_gate`. An item can't be both token and NFT gated. Only callable by a store owner. This is an action that changes the contract state.
+ *
+ * @function set_item_ft_requirement
+ * @param {string} item_id - The item ID.
+ * @param {string} store_id - The store ID.
+ * @param {string} ft_contract - The approved fungible token contract.
+ * @param {U128} min_balance - The minimum balance the buyer must hold.
+ * @returns {void}
+ */
+await contract.set_item_ft_requirement(item_id, store_id, ft_contract, min_balance);
+
+/**
+ * Retrieves the token and minimum balance required to buy an item.
+ *
+ * @function get_item_ft_requirement
+ * @param {string} item_id - The item ID.
+ * @param {string} store_id - The store ID.
+ * @returns {[string, U128]|null} The `[ft_contract, min_balance]` pair, or `null` if the item isn't gated.
+ */
+await contract.get_item_ft_requirement(item_id, store_id);
+
+/**
+ * Buys a token-gated item. The purchase is checked before the signer's token balance is looked up, and fails right away if it can't go through. If the balance is insufficient, or the purchase can no longer go through once it is known, the deposit is refunded and no purchase is made. This is an action that changes the contract state.
+ *
+ * @function buy_with_ft_gate
+ * @param {string} item_id - The item ID.
+ * @param {string} store_id - The store ID.
+ * @param {string|null} buyer_region - The buyer's ISO 3166-1 alpha-2 region, required by geo-restricted stores.
+ * @returns {BuyReceipt|null} The purchase receipt, or `null` if the deposit was refunded.
+ */
+await contract.buy_with_ft_gate(item_id, store_id, buyer_region);
+
+/**
+ * Sets how an item is shipped. Buyers pay the flat shipping fee on top of the price, unless the price reaches the free shipping threshold. The fee goes to the seller. Only callable by a store owner. This is an action that changes the contract state.
+ *
+ * @function set_item_shipping_info
+ * @param {string} item_id - The item ID.
+ * @param {string} store_id - The store ID.
+ * @param {ShippingInfo} shipping_info - The shipping configuration.
+ * @returns {void}
+ */
+await contract.set_item_shipping_info(item_id, store_id, shipping_info);
+
+/**
+ * Retrieves how an item is shipped.
+ *
+ * @function get_item_shipping_info
+ * @param {string} item_id - The item ID.
+ * @param {string} store_id - The store ID.
+ * @returns {ShippingInfo|null} The shipping configuration, or `null` if none is set.
+ */
+await contract.get_item_shipping_info(item_id, store_id);
+
+/**
+ * Retrieves the metadata of up to 100 items at once.
+ *
+ * @function batch_get_items
+ * @param {Array<[string, string]>} ids - `[item_id, store_id]` pairs.
+ * @returns {Array<ItemMetadata|null>} The metadata in the order of `ids`, `null` for missing items.
+ */
+await contract.batch_get_items(ids);
+
+/**
+ * Retrieves a store's items graded with a given condition.
+ *
+ * @function get_items_by_condition
+ * @param {string} store_id - The store ID.
+ * @param {ItemCondition} condition - The condition to filter by.
+ * @param {number|null} from_index - The index to start from.
+ * @param {number|null} limit - The maximum number of items to return.
+ * @returns {Array<[string, ItemMetadata]>} `[item_id, metadata]` pairs.
+ */
+await contract.get_items_by_condition(store_id, condition, from_index, limit);
+
+/**
+ * Locks an item while it is being auctioned, so it can't be bought directly. Only callable by a store owner. This is an action that changes the contract state.
+ *
+ * @function lock_item_for_auction
+ * @param {string} item_id - The item ID.
+ * @param {string} store_id - The store ID.
+ * @returns {void}
+ */
+await contract.lock_item_for_auction(item_id, store_id);
+
+/**
+ * Releases an item locked for auction. Only callable by a store owner. This is an action that changes the contract state.
+ *
+ * @function unlock_item
+ * @param {string} item_id - The item ID.
+ * @param {string} store_id - The store ID.
+ * @returns {void}
+ */
+await contract.unlock_item(item_id, store_id);
+
+/**
+ * Checks whether an item is locked for auction.
+ *
+ * @function is_item_locked
+ * @param {string} item_id - The item ID.
+ * @param {string} store_id - The store ID.
+ * @returns {boolean} Whether the item is locked.
+ */
+await contract.is_item_locked(item_id, store_id);
+
+/**
+ * Creates a recurring payment plan for an item. Only callable by a store owner. This is an action that changes the contract state.
+ *
+ * @function create_subscription_plan
+ * @param {string} item_id - The item ID.
+ * @param {string} store_id - The store ID.
+ * @param {U128} price_per_period - The price of each period in yoctoNEAR.
+ * @param {number} period_seconds - The length of a period in seconds, at most 365 days.
+ * @param {number|null} max_periods - The maximum number of periods a subscriber can pay for, optional.
+ * @returns {string} The plan ID.
+ */
+await contract.create_subscription_plan(item_id, store_id, price_per_period, period_seconds, max_periods);
+
+/**
+ * Subscribes the signer to a plan, paying for the first period. Payments are credited to the store's balance. This is an action that changes the contract state.
+ *
+ * @function subscribe
+ * @param {string} plan_id - The plan ID.
+ * @returns {void}
+ */
+await contract.subscribe(plan_id);
+
+/**
+ * Pays for the signer's next subscription period. This is an action that changes the contract state.
+ *
+ * @function renew_subscription
+ * @param {string} plan_id - The plan ID.
+ * @returns {void}
+ */
+await contract.renew_subscription(plan_id);
+
+/**
+ * Cancels the signer's subscription. Periods already paid for are not refunded. This is an action that changes the contract state.
+ *
+ * @function cancel_subscription
+ * @param {string} plan_id - The plan ID.
+ * @returns {void}
+ */
+await contract.cancel_subscription(plan_id);
+
+/**
+ * Retrieves the subscriptions to a store's plans.
+ *
+ * @function get_subscriptions_by_store
+ * @param {string} store_id - The store ID.
+ * @param {number|null} from_index - The index to start from.
+ * @param {number|null} limit - The maximum number of subscriptions to return.
+ * @returns {Array<Subscription>} The subscriptions.
+ */
+await contract.get_subscriptions_by_store(store_id, from_index, limit);
+
+/**
+ * Checks whether an account's subscription is active and paid up.
+ *
+ * @function is_subscription_active
+ * @param {string} plan_id - The plan ID.
+ * @param {string} subscriber - The subscriber's account ID.
+ * @returns {boolean} Whether the subscription is active.
+ */
+await contract.is_subscription_active(plan_id, subscriber);
+
+/**
+ * Sets the number of items a store needs to be considered active. Only callable by the overseer. This is an action that changes the contract state.
+ *
+ * @function set_platform_min_items
+ * @param {number|null} min_items - The minimum item count, or `null` to disable it.
+ * @returns {void}
+ */
+await contract.set_platform_min_items(min_items);
+
+/**
+ * Retrieves the stores listing fewer items than the platform minimum.
+ *
+ * @function get_inactive_stores
+ * @param {number|null} from_index - The index to start from.
+ * @param {number|null} limit - The maximum number of stores to return.
+ * @returns {Array<string>} The store IDs.
+ */
+await contract.get_inactive_stores(from_index, limit);
+
+/**
+ * Sets the tax rate applied to a store's invoices. Only callable by the overseer. This is an action that changes the contract state.
+ *
+ * @function set_tax_rate
+ * @param {string} store_id - The store ID.
+ * @param {number} tax_rate_bps - The tax rate in basis points.
+ * @returns {void}
+ */
+await contract.set_tax_rate(store_id, tax_rate_bps);
+
+/**
+ * Retrieves the tax rate applied to a store's invoices.
+ *
+ * @function get_tax_rate
+ * @param {string} store_id - The store ID.
+ * @returns {number} The tax rate in basis points.
+ */
+await contract.get_tax_rate(store_id);
+
+/**
+ * Assembles the invoice of a purchase from its `buy` log. The amount paid is treated as tax-inclusive, and the tax rate and item name are the ones in effect at the time of sale.
+ *
+ * @function generate_invoice
+ * @param {string} log_id - The purchase's transaction ID.
+ * @returns {Invoice} The invoice.
+ */
+await contract.generate_invoice(log_id);
+
+/**
+ * Runs a store-wide sale that discounts the store's own listings between `start_time` and `end_time`. Resales keep their price. Only callable by a store owner. This is an action that changes the contract state.
+ *
+ * @function set_store_sale_period
+ * @param {string} store_id - The store ID.
+ * @param {number} discount_bps - The discount in basis points.
+ * @param {number} start_time - The block timestamp, in nanoseconds, at which the sale starts.
+ * @param {number} end_time - The block timestamp, in nanoseconds, at which the sale ends.
+ * @returns {void}
+ */
+await contract.set_store_sale_period(store_id, discount_bps, start_time, end_time);
+
+/**
+ * Cancels a store's sale. Only callable by a store owner. This is an action that changes the contract state.
+ *
+ * @function cancel_store_sale
+ * @param {string} store_id - The store ID.
+ * @returns {void}
+ */
+await contract.cancel_store_sale(store_id);
+
+/**
+ * Retrieves a store's sale.
+ *
+ * @function get_store_sale_period
+ * @param {string} store_id - The store ID.
+ * @returns {StoreSalePeriod|null} The sale, or `null` if none is scheduled.
+ */
+await contract.get_store_sale_period(store_id);
+
+/**
+ * Checks whether a store's sale is running.
+ *
+ * @function is_store_on_sale
+ * @param {string} store_id - The store ID.
+ * @returns {boolean} Whether the store is on sale.
+ */
+await contract.is_store_on_sale(store_id);
+
+/**
+ * Grants an account a `Manager` or `Viewer` role in a store. Managers can add and remove items. Viewers can read the store's private data. Owners are added through `add_store_owners`. Only callable by a store owner. This is an action that changes the contract state.
+ *
+ * @function set_store_role
+ * @param {string} store_id - The store ID.
+ * @param {string} account_id - The account ID.
+ * @param {"Manager"|"Viewer"} role - The role to grant.
+ * @returns {void}
+ */
+await contract.set_store_role(store_id, account_id, role);
+
+/**
+ * Retrieves an account's role in a store.
+ *
+ * @function get_store_role
+ * @param {string} store_id - The store ID.
+ * @param {string} account_id - The account ID.
+ * @returns {"Owner"|"Manager"|"Viewer"|null} The role, or `null` if the account has none.
+ */
+await contract.get_store_role(store_id, account_id);
+
+/**
+ * Revokes an account's manager or viewer role in a store. Only callable by a store owner. This is an action that changes the contract state.
+ *
+ * @function remove_store_role
+ * @param {string} store_id - The store ID.
+ * @param {string} account_id - The account ID.
+ * @returns {void}
+ */
+await contract.remove_store_role(store_id, account_id);
+
+/**
+ * Updates an item's descriptive metadata. The previous version is kept in the item's history. Only callable by a store owner or manager. This is an action that changes the contract state.
+ *
+ * @function update_item_metadata
+ * @param {string} item_id - The item ID.
+ * @param {string} store_id - The store ID.
+ * @param {string|null} item_name - The new name, or `null` to keep the current one.
+ * @param {string|null} item_img_url - The new image URL, or `null` to keep the current one.
+ * @param {ItemCondition|null} item_condition - The new condition, or `null` to keep the current one.
+ * @param {string|null} item_upc - The new barcode, or `null` to keep the current one.
+ * @param {string|null} item_video_url - The new `https://` video URL, or `null` to keep the current one.
+ * @returns {void}
+ */
+await contract.update_item_metadata(item_id, store_id, item_name, item_img_url, item_condition, item_upc, item_video_url);
+
+/**
+ * Retrieves up to the last 20 metadata versions of an item, oldest first, each with the time it was replaced.
+ *
+ * @function get_item_metadata_history
+ * @param {string} item_id - The item ID.
+ * @param {string} store_id - The store ID.
+ * @param {number|null} from_index - The index to start from.
+ * @param {number|null} limit - The maximum number of versions to return.
+ * @returns {Array<[ItemMetadata, number]>} `[metadata, replaced_at]` pairs.
+ */
+await contract.get_item_metadata_history(item_id, store_id, from_index, limit);
+
+/**
+ * Retrieves the number of previous metadata versions kept for an item.
+ *
+ * @function get_item_metadata_version_count
+ * @param {string} item_id - The item ID.
+ * @param {string} store_id - The store ID.
+ * @returns {number} The number of versions.
+ */
+await contract.get_item_metadata_version_count(item_id, store_id);
+
+/**
+ * Buys an item on behalf of `buyer` from an approved contract, forwarding the attached deposit. The buyer becomes the item's owner. Only callable by approved caller contracts. This is an action that changes the contract state.
+ *
+ * @function cross_contract_buy
+ * @param {string} item_id - The item ID.
+ * @param {string} store_id - The store ID.
+ * @param {string} buyer - The account ID receiving the item.
+ * @param {string|null} ft_contract - Must be `null`; fungible token purchases are not supported yet.
+ * @returns {BuyReceipt} The purchase receipt, resolved through a callback.
+ */
+await contract.cross_contract_buy(item_id, store_id, buyer, ft_contract);
+
+/**
+ * Allows a contract to buy items on behalf of its users. Only callable by the overseer. This is an action that changes the contract state.
+ *
+ * @function approve_caller_contract
+ * @param {string} contract_id - The contract's account ID.
+ * @returns {void}
+ */
+await contract.approve_caller_contract(contract_id);
+
+/**
+ * Stops a contract from buying items on behalf of its users. Only callable by the overseer. This is an action that changes the contract state.
+ *
+ * @function revoke_caller_contract
+ * @param {string} contract_id - The contract's account ID.
+ * @returns {void}
+ */
+await contract.revoke_caller_contract(contract_id);
+
+/**
+ * Sets how a store presents itself in the marketplace. Only callable by a store owner. This is an action that changes the contract state.
+ *
+ * @function set_store_metadata
+ * @param {string} store_id - The store ID.
+ * @param {string} name - The store's display name; can't be empty.
+ * @param {string} description - A short description of the store.
+ * @param {string} logo_url - The store's logo URL.
+ * @param {string} website_url - The store's website URL.
+ * @returns {void}
+ */
+await contract.set_store_metadata(store_id, name, description, logo_url, website_url);
+
+/**
+ * Retrieves how a store presents itself in the marketplace. This is a view method.
+ *
+ * @function get_store_metadata
+ * @param {string} store_id - The store ID.
+ * @returns {StoreMetadata|null} The store's metadata, or `null` if it isn't described.
+ */
+await contract.get_store_metadata(store_id);
+
+/**
+ * Creates a new store and sets its metadata in a single transaction. The caller becomes the store's owner. This is an action that changes the contract state.
+ *
+ * @function create_store_with_metadata
+ * @param {string} store_id - The store ID.
+ * @param {string} name - The store's display name; can't be empty.
+ * @param {string} description - A short description of the store.
+ * @param {string} logo_url - The store's logo URL.
+ * @param {string} website_url - The store's website URL.
+ * @returns {string} The created store's ID.
+ */
+await contract.create_store_with_metadata(store_id, name, description, logo_url, website_url);
+
+/**
+ * Retrieves stores alongside their metadata in a single call, for rendering store directories. This is a view method.
+ *
+ * @function get_stores_with_metadata
+ * @param {number|null} from_index - The index to start from.
+ * @param {number|null} limit - The maximum number of stores to return.
+ * @returns {Array<[string, StoreMetadata|null]>} The store IDs paired with their metadata, `null` where a store isn't described.
+ */
+await contract.get_stores_with_metadata(from_index, limit);
+
+/**
+ * Requires new items to be approved before they're listed. Only callable by the overseer. This is an action that changes the contract state.
+ *
+ * @function set_require_item_approval
+ * @param {boolean} require_item_approval - Whether new items need approval.
+ * @returns {void}
+ */
+await contract.set_require_item_approval(require_item_approval);
+
+/**
+ * Checks whether new items need approval before they're listed. This is a view method.
+ *
+ * @function get_require_item_approval
+ * @returns {boolean} `true` if new items need approval.
+ */
+await contract.get_require_item_approval();
+
+/**
+ * Lists an item awaiting approval. Only callable by the overseer. This is an action that changes the contract state.
+ *
+ * @function approve_item
+ * @param {string} item_id - The item ID.
+ * @param {string} store_id - The store ID.
+ * @returns {void}
+ */
+await contract.approve_item(item_id, store_id);
+
+/**
+ * Deletes an item awaiting approval. Only callable by the overseer. This is an action that changes the contract state.
+ *
+ * @function reject_item
+ * @param {string} item_id - The item ID.
+ * @param {string} store_id - The store ID.
+ * @returns {void}
+ */
+await contract.reject_item(item_id, store_id);
+
+/**
+ * Retrieves the items awaiting approval. This is a view method.
+ *
+ * @function get_pending_approval_items
+ * @param {number|null} from_index - The index to start from.
+ * @param {number|null} limit - The maximum number of items to return.
+ * @returns {string[]} The items' `STOREID + DELIMITER + ITEM_ID` keys.
+ */
+await contract.get_pending_approval_items(from_index, limit);
+
+/**
+ * Checks whether an item is listed, i.e. not awaiting approval. Unlisted items can't be bought. This is a view method.
+ *
+ * @function is_item_listed
+ * @param {string} item_id - The item ID.
+ * @param {string} store_id - The store ID.
+ * @returns {boolean} `true` if the item is listed.
+ */
+await contract.is_item_listed(item_id, store_id);
+
+/**
+ * Stops a store from accepting new items. Existing items remain purchasable. Only callable by a store owner. This is an action that changes the contract state.
+ *
+ * @function close_store_for_listing
+ * @param {string} store_id - The store ID.
+ * @returns {void}
+ */
+await contract.close_store_for_listing(store_id);
+
+/**
+ * Lets a store accept new items again. Only callable by a store owner. This is an action that changes the contract state.
+ *
+ * @function open_store_for_listing
+ * @param {string} store_id - The store ID.
+ * @returns {void}
+ */
+await contract.open_store_for_listing(store_id);
+
+/**
+ * Checks whether a store accepts new items. This is a view method.
+ *
+ * @function is_store_open_for_listing
+ * @param {string} store_id - The store ID.
+ * @returns {boolean} `true` if the store accepts new items.
+ */
+await contract.is_store_open_for_listing(store_id);
+
+/**
+ * Previews the deposit needed to buy an item, with any active store sale and shipping fee applied. This is a view method.
+ *
+ * @function get_item_effective_price
+ * @param {string} item_id - The item ID.
+ * @param {string} store_id - The store ID.
+ * @param {number} quantity - Must be `1`; items are single units.
+ * @param {string|null} discount_code - Must be `null`; discount codes are not supported yet.
+ * @returns {string} The total deposit needed, as a U128 string.
+ */
+await contract.get_item_effective_price(item_id, store_id, quantity, discount_code);
+
+/**
+ * Adds items to a store from a JSON array of catalog items, at most 50 per call. Invalid items are skipped and reported instead of aborting the import. Only callable by a store owner or manager. This is an action that changes the contract state.
+ *
+ * @function import_store_from_json
+ * @param {string} store_id - The store ID.
+ * @param {string} json_payload - A JSON array of `{ id, name, price, img_url }` objects, with `price` as a U128 string.
+ * @returns {ImportResult} The number of imported and failed items, with an error message per failed item.
+ */
+await contract.import_store_from_json(store_id, json_payload);
+
+/**
+ * Exports a store's items in the `import_store_from_json` format, for backups or migrations. Items awaiting approval are included, as they are public chain state. This is a view method.
+ *
+ * @function export_store_items
+ * @param {string} store_id - The store ID.
+ * @param {number|null} from_index - The index to start from.
+ * @param {number|null} limit - The maximum number of items to return.
+ * @returns {string} A JSON string of `{ items: CatalogItem[], total_count: number }`.
+ */
+await contract.export_store_items(store_id, from_index, limit);
+
+/**
+ * Retrieves how much a buyer spent at each store, highest first. This is a view method.
+ *
+ * @function get_buyer_store_spending
+ * @param {string} account_id - The buyer's account ID.
+ * @returns {Array<[string, string]>} The store IDs paired with the amount spent, as U128 strings.
+ */
+await contract.get_buyer_store_spending(account_id);
+
+/**
+ * Buys an item with the discount of the store's referral program, rewarding the referrer out of the sale. Only applies to items the store still owns. This is an action that changes the contract state.
+ *
+ * @function buy_with_referral
+ * @param {string} item_id - The item ID.
+ * @param {string} store_id - The store ID.
+ * @param {string} referrer - The account ID that referred the buyer.
+ * @returns {BuyReceipt} The purchase receipt.
+ */
+await contract.buy_with_referral(item_id, store_id, referrer);
+
+/**
+ * Starts or updates a store's referral program. Only callable by a store owner. This is an action that changes the contract state.
+ *
+ * @function create_referral_program
+ * @param {string} store_id - The store ID.
+ * @param {number} referral_reward_bps - The referrer's reward, in basis points of the price.
+ * @param {number} referred_discount_bps - The referred buyer's discount, in basis points of the price.
+ * @returns {void}
+ */
+await contract.create_referral_program(store_id, referral_reward_bps, referred_discount_bps);
+
+/**
+ * Ends a store's referral program. Only callable by a store owner. This is an action that changes the contract state.
+ *
+ * @function end_referral_program
+ * @param {string} store_id - The store ID.
+ * @returns {void}
+ */
+await contract.end_referral_program(store_id);
+
+/**
+ * Retrieves a store's referral program. This is a view method.
+ *
+ * @function get_referral_program
+ * @param {string} store_id - The store ID.
+ * @returns {ReferralProgram|null} The referral program, or `null` if the store has none.
+ */
+await contract.get_referral_program(store_id);
+
+/**
+ * Retrieves the total rewards an account earned from referrals. This is a view method.
+ *
+ * @function get_referral_earnings
+ * @param {string} account_id - The referrer's account ID.
+ * @returns {string} The total rewards, as a U128 string.
+ */
+await contract.get_referral_earnings(account_id);
+
+/**
+ * Sets how long an account must wait before buying an item again, to discourage resell-flipping. Only callable by a store owner. This is an action that changes the contract state.
+ *
+ * @function set_item_purchase_cooldown
+ * @param {string} item_id - The item ID.
+ * @param {string} store_id - The store ID.
+ * @param {number} cooldown - The cooldown in nanoseconds, `0` lifting it.
+ * @returns {void}
+ */
+await contract.set_item_purchase_cooldown(item_id, store_id, cooldown);
+
+/**
+ * Retrieves how long an account must wait before buying an item again. This is a view method.
+ *
+ * @function get_item_cooldown_remaining
+ * @param {string} account_id - The buyer's account ID.
+ * @param {string} item_id - The item ID.
+ * @param {string} store_id - The store ID.
+ * @returns {number} The remaining cooldown in nanoseconds, `0` if the account can buy now.
+ */
+await contract.get_item_cooldown_remaining(account_id, item_id, store_id);
+
+/**
+ * Retrieves a store's activity feed, merging its logs, new items and sales, most recent first. Sales are read from the store's `buy` logs. This is a view method.
+ *
+ * @function get_store_activity_feed
+ * @param {string} store_id - The store ID.
+ * @param {number|null} from_index - The index to start from.
+ * @param {number|null} limit - The maximum number of events to return.
+ * @returns {ActivityEvent[]} The store's activity events.
+ */
+await contract.get_store_activity_feed(store_id, from_index, limit);
+
+/**
+ * Sets the terms buyers must acknowledge before buying from a store, e.g. a URL or a hash of the text. Only callable by a store owner. This is an action that changes the contract state.
+ *
+ * @function set_store_acceptance_terms
+ * @param {string} store_id - The store ID.
+ * @param {string|null} terms - The terms, or `null` to remove them.
+ * @returns {void}
+ */
+await contract.set_store_acceptance_terms(store_id, terms);
+
+/**
+ * Retrieves the terms buyers must acknowledge before buying from a store. This is a view method.
+ *
+ * @function get_store_acceptance_terms
+ * @param {string} store_id - The store ID.
+ * @returns {string|null} The terms, or `null` if the store has none.
+ */
+await contract.get_store_acceptance_terms(store_id);
+
+/**
+ * Acknowledges a store's terms, allowing the caller to buy from it. This is an action that changes the contract state.
+ *
+ * @function acknowledge_store_terms
+ * @param {string} store_id - The store ID.
+ * @returns {void}
+ */
+await contract.acknowledge_store_terms(store_id);
+
+/**
+ * Checks whether an account acknowledged a store's terms. This is a view method.
+ *
+ * @function has_accepted_terms
+ * @param {string} store_id - The store ID.
+ * @param {string} account_id - The account ID.
+ * @returns {boolean} `true` if the account acknowledged the terms.
+ */
+await contract.has_accepted_terms(store_id, account_id);
+
+/**
+ * Restricts an item to buyers who attested to being at least 18. Only callable by a store owner. This is an action that changes the contract state.
+ *
+ * @function set_item_age_restricted
+ * @param {string} item_id - The item ID.
+ * @param {string} store_id - The store ID.
+ * @param {boolean} restricted - Whether the item is age-restricted.
+ * @returns {void}
+ */
+await contract.set_item_age_restricted(item_id, store_id, restricted);
+
+/**
+ * Checks whether an item is age-restricted. This is a view method.
+ *
+ * @function is_item_age_restricted
+ * @param {string} item_id - The item ID.
+ * @param {string} store_id - The store ID.
+ * @returns {boolean} `true` if the item is age-restricted.
+ */
+await contract.is_item_age_restricted(item_id, store_id);
+
+/**
+ * Attests that the caller is at least a given age. The attestation is self-declared and isn't verified. This is an action that changes the contract state.
+ *
+ * @function attest_age
+ * @param {number} minimum_age - The age the caller attests to.
+ * @returns {void}
+ */
+await contract.attest_age(minimum_age);
+
+/**
+ * Checks whether an account attested to being at least a given age. This is a view method.
+ *
+ * @function has_attested_age
+ * @param {string} account_id - The account ID.
+ * @param {number} required_age - The required age.
+ * @returns {boolean} `true` if the account attested to the required age or more.
+ */
+await contract.has_attested_age(account_id, required_age);
+
+/**
+ * Raises an item's price after each purchase, for scarcity-based pricing. The price is bumped by `price_increment_bps` and capped at `max_price`. Only callable by a store owner. This is an action that changes the contract state.
+ *
+ * @function set_dynamic_pricing
+ * @param {string} item_id - The item ID.
+ * @param {string} store_id - The store ID.
+ * @param {number} price_increment_bps - The price increase per purchase, in basis points.
+ * @param {string|null} max_price - The highest price the item can reach, as a U128 string, or `null` for no cap.
+ * @returns {void}
+ */
+await contract.set_dynamic_pricing(item_id, store_id, price_increment_bps, max_price);
+
+/**
+ * Stops raising an item's price after each purchase. Only callable by a store owner. This is an action that changes the contract state.
+ *
+ * @function remove_dynamic_pricing
+ * @param {string} item_id - The item ID.
+ * @param {string} store_id - The store ID.
+ * @returns {void}
+ */
+await contract.remove_dynamic_pricing(item_id, store_id);
+
+/**
+ * Retrieves how an item's price rises after each purchase. This is a view method.
+ *
+ * @function get_dynamic_pricing
+ * @param {string} item_id - The item ID.
+ * @param {string} store_id - The store ID.
+ * @returns {DynamicPricingConfig|null} The dynamic pricing config, or `null` if the item has none.
+ */
+await contract.get_dynamic_pricing(item_id, store_id);
+
+/**
+ * Posts a notice to every front-end, e.g. planned downtime or policy changes. At most 10 announcements are kept; expired ones are dropped to make room. Only callable by the overseer. This is an action that changes the contract state.
+ *
+ * @function post_announcement
+ * @param {string} title - The announcement's title.
+ * @param {string} body - The announcement's text.
+ * @param {number|null} expires_at - The timestamp the announcement expires at, or `null` to keep it until deleted.
+ * @returns {string} The announcement ID.
+ */
+await contract.post_announcement(title, body, expires_at);
+
+/**
+ * Deletes a platform announcement. Only callable by the overseer. This is an action that changes the contract state.
+ *
+ * @function delete_announcement
+ * @param {string} id - The announcement ID.
+ * @returns {void}
+ */
+await contract.delete_announcement(id);
+
+/**
+ * Retrieves the platform announcements that haven't expired. This is a view method.
+ *
+ * @function get_active_announcements
+ * @returns {PlatformAnnouncement[]} The active announcements.
+ */
+await contract.get_active_announcements();
+
+/**
+ * Stakes the attached deposit to vouch for a store. A store's total stake serves as a trust signal. This is an action that changes the contract state.
+ *
+ * @function stake_for_store
+ * @param {string} store_id - The store ID.
+ * @returns {void}
+ */
+await contract.stake_for_store(store_id);
+
+/**
+ * Withdraws part of the caller's stake on a store, returning it to the caller. This is an action that changes the contract state.
+ *
+ * @function unstake_from_store
+ * @param {string} store_id - The store ID.
+ * @param {string} amount - The amount to withdraw, as a U128 string.
+ * @returns {void}
+ */
+await contract.unstake_from_store(store_id, amount);
+
+/**
+ * Retrieves the total staked on a store. This is a view method.
+ *
+ * @function get_store_total_stake
+ * @param {string} store_id - The store ID.
+ * @returns {string} The total stake, as a U128 string.
+ */
+await contract.get_store_total_stake(store_id);
+
+/**
+ * Retrieves an account's stake on a store. This is a view method.
+ *
+ * @function get_staker_stake
+ * @param {string} store_id - The store ID.
+ * @param {string} account_id - The staker's account ID.
+ * @returns {string} The account's stake, as a U128 string.
+ */
+await contract.get_staker_stake(store_id, account_id);
+
+/**
+ * Joins the waitlist of an item the store already sold. This is an action that changes the contract state.
+ *
+ * @function join_item_waitlist
+ * @param {string} item_id - The item ID.
+ * @param {string} store_id - The store ID.
+ * @returns {void}
+ */
+await contract.join_item_waitlist(item_id, store_id);
+
+/**
+ * Leaves the waitlist of an item. This is an action that changes the contract state.
+ *
+ * @function leave_item_waitlist
+ * @param {string} item_id - The item ID.
+ * @param {string} store_id - The store ID.
+ * @returns {void}
+ */
+await contract.leave_item_waitlist(item_id, store_id);
+
+/**
+ * Retrieves the accounts waiting for an item, in signup order. This is a view method.
+ *
+ * @function get_item_waitlist
+ * @param {string} item_id - The item ID.
+ * @param {string} store_id - The store ID.
+ * @returns {string[]} The waiting account IDs.
+ */
+await contract.get_item_waitlist(item_id, store_id);
+
+/**
+ * Retrieves an account's position on an item's waitlist, starting at 1. This is a view method.
+ *
+ * @function get_waitlist_position
+ * @param {string} item_id - The item ID.
+ * @param {string} store_id - The store ID.
+ * @param {string} account_id - The account ID.
+ * @returns {number|null} The account's position, or `null` if it isn't on the waitlist.
+ */
+await contract.get_waitlist_position(item_id, store_id, account_id);
+
+/**
+ * Allows an account to certify items. Only callable by the overseer. This is an action that changes the contract state.
+ *
+ * @function add_certified_issuer
+ * @param {string} account_id - The issuer's account ID.
+ * @returns {void}
+ */
+await contract.add_certified_issuer(account_id);
+
+/**
+ * Stops an account from certifying items. Certificates it already issued are kept. Only callable by the overseer. This is an action that changes the contract state.
+ *
+ * @function remove_certified_issuer
+ * @param {string} account_id - The issuer's account ID.
+ * @returns {void}
+ */
+await contract.remove_certified_issuer(account_id);
+
+/**
+ * Attaches an authenticity certificate to an item. Only callable by the overseer or a certified issuer. This is an action that changes the contract state.
+ *
+ * @function add_item_certificate
+ * @param {string} item_id - The item ID.
+ * @param {string} store_id - The store ID.
+ * @param {string} certificate_type - The kind of certificate, e.g. `authenticity`.
+ * @param {string} certificate_id - The certificate ID, unique per item.
+ * @param {number|null} expires_at - The timestamp the certificate expires at, or `null` if it doesn't expire.
+ * @param {string} metadata_url - A URL to the certificate's documentation.
+ * @returns {void}
+ */
+await contract.add_item_certificate(item_id, store_id, certificate_type, certificate_id, expires_at, metadata_url);
+
+/**
+ * Revokes an item's certificate. Only callable by the certificate's issuer or the overseer. This is an action that changes the contract state.
+ *
+ * @function revoke_item_certificate
+ * @param {string} item_id - The item ID.
+ * @param {string} store_id - The store ID.
+ * @param {string} certificate_id - The certificate ID.
+ * @returns {void}
+ */
+await contract.revoke_item_certificate(item_id, store_id, certificate_id);
+
+/**
+ * Retrieves an item's certificates, including expired ones. This is a view method.
+ *
+ * @function get_item_certificates
+ * @param {string} item_id - The item ID.
+ * @param {string} store_id - The store ID.
+ * @returns {ItemCertificate[]} The item's certificates.
+ */
+await contract.get_item_certificates(item_id, store_id);
+
+/**
+ * Checks whether an item holds a certificate that hasn't expired. This is a view method.
+ *
+ * @function is_item_certified
+ * @param {string} item_id - The item ID.
+ * @param {string} store_id - The store ID.
+ * @returns {boolean} `true` if the item is certified.
+ */
+await contract.is_item_certified(item_id, store_id);
+
+/**
+ * Adds a platform loyalty tier, reached once an account's lifetime spending meets its threshold. Only callable by the overseer. This is an action that changes the contract state.
+ *
+ * @function create_loyalty_tier
+ * @param {string} name - The tier's name, unique across tiers.
+ * @param {string} min_lifetime_spend - The lifetime spending threshold, as a U128 string.
+ * @param {string} benefits - A description of the benefits the tier unlocks.
+ * @returns {void}
+ */
+await contract.create_loyalty_tier(name, min_lifetime_spend, benefits);
+
+/**
+ * Retrieves the highest loyalty tier an account's lifetime spending qualifies for. This is a view method.
+ *
+ * @function get_loyalty_tier_for_account
+ * @param {string} account_id - The account ID.
+ * @returns {LoyaltyTier|null} The account's tier, or `null` if it doesn't qualify for any.
+ */
+await contract.get_loyalty_tier_for_account(account_id);
+
+/**
+ * Retrieves the benefits a loyalty tier unlocks. This is a view method.
+ *
+ * @function get_loyalty_tier_benefits
+ * @param {string} name - The tier's name.
+ * @returns {string|null} The tier's benefits, or `null` if the tier doesn't exist.
+ */
+await contract.get_loyalty_tier_benefits(name);
+
+/**
+ * Proposes a partnership between two stores. Once accepted, buyers who purchased from either store get `cross_discount_bps` off the other store's listings. Only callable by an owner of `store_a`. This is an action that changes the contract state.
+ *
+ * @function propose_partnership
+ * @param {string} store_a - The proposing store's ID.
+ * @param {string} store_b - The partner store's ID.
+ * @param {number} cross_discount_bps - The discount for buyers of the partner store, in basis points.
+ * @returns {string} The partnership ID.
+ */
+await contract.propose_partnership(store_a, store_b, cross_discount_bps);
+
+/**
+ * Accepts a proposed partnership. Only callable by an owner of `store_b`. This is an action that changes the contract state.
+ *
+ * @function accept_partnership
+ * @param {string} partnership_id - The partnership ID.
+ * @returns {void}
+ */
+await contract.accept_partnership(partnership_id);
+
+/**
+ * Ends a partnership, or withdraws a proposal. Only callable by an owner of either store. This is an action that changes the contract state.
+ *
+ * @function dissolve_partnership
+ * @param {string} partnership_id - The partnership ID.
+ * @returns {void}
+ */
+await contract.dissolve_partnership(partnership_id);
+
+/**
+ * Retrieves a store's partnerships, including pending proposals. This is a view method.
+ *
+ * @function get_store_partnerships
+ * @param {string} store_id - The store ID.
+ * @returns {Partnership[]} The store's partnerships.
+ */
+await contract.get_store_partnerships(store_id);
+
+/**
+ * Retrieves a store's item by its barcode, for point-of-sale scanners. This is a view method.
+ *
+ * @function get_item_by_barcode
+ * @param {string} upc - The item's UPC or EAN barcode.
+ * @param {string} store_id - The store ID.
+ * @returns {ItemMetadata|null} The item's metadata, or `null` if the store has no item with this barcode.
+ */
+await contract.get_item_by_barcode(upc, store_id);
+
+/**
+ * Checks whether an item is registered under a barcode. This is a view method.
+ *
+ * @function does_barcode_exist
+ * @param {string} upc - The UPC or EAN barcode.
+ * @returns {boolean} `true` if an item uses the barcode.
+ */
+await contract.does_barcode_exist(upc);
+
+/**
+ * Sets the fee paid into the treasury for each featured banner. Only callable by the overseer. This is an action that changes the contract state.
+ *
+ * @function set_banner_fee
+ * @param {string} fee - The banner fee, as a U128 string.
+ * @returns {void}
+ */
+await contract.set_banner_fee(fee);
+
+/**
+ * Retrieves the fee paid into the treasury for each featured banner. This is a view method.
+ *
+ * @function get_banner_fee
+ * @returns {string} The banner fee, as a U128 string.
+ */
+await contract.get_banner_fee();
+
+/**
+ * Features a store on the homepage between the banner's start and end times. The attached deposit must equal the banner fee, which goes to the treasury. At most 5 banners can be active at once. Only callable by the overseer. This is an action that changes the contract state.
+ *
+ * @function set_featured_banner
+ * @param {FeaturedBanner} banner - The banner to feature.
+ * @returns {void}
+ */
+await contract.set_featured_banner(banner);
+
+/**
+ * Removes a store's featured banners. Only callable by the overseer. This is an action that changes the contract state.
+ *
+ * @function remove_featured_banner
+ * @param {string} store_id - The store ID.
+ * @returns {void}
+ */
+await contract.remove_featured_banner(store_id);
+
+/**
+ * Retrieves the featured banners shown at the current block. This is a view method.
+ *
+ * @function get_active_featured_banners
+ * @returns {FeaturedBanner[]} The active banners.
+ */
+await contract.get_active_featured_banners();
+
+/**
+ * Counts a view of an item's video. Repeated views by the same account are counted at most once an hour. This is an action that changes the contract state.
+ *
+ * @function record_video_view
+ * @param {string} item_id - The item ID.
+ * @param {string} store_id - The store ID.
+ * @returns {void}
+ */
+await contract.record_video_view(item_id, store_id);
+
+/**
+ * Retrieves the number of counted views of an item's video. This is a view method.
+ *
+ * @function get_item_video_views
+ * @param {string} item_id - The item ID.
+ * @param {string} store_id - The store ID.
+ * @returns {number} The number of views.
+ */
+await contract.get_item_video_views(item_id, store_id);
+
+/**
+ * Retrieves a store's items that have a video. This is a view method.
+ *
+ * @function get_items_with_video
+ * @param {string} store_id - The store ID.
+ * @returns {Array<[string, ItemMetadata]>} The item IDs paired with their metadata.
+ */
+await contract.get_items_with_video(store_id);
+
+/**
+ * Allows items to link to their listings on a chain. Only callable by the overseer. This is an action that changes the contract state.
+ *
+ * @function approve_chain
+ * @param {string} chain - The chain's name, e.g. `ethereum`.
+ * @returns {void}
+ */
+await contract.approve_chain(chain);
+
+/**
+ * Stops items from linking to a chain. Existing links are kept. Only callable by the overseer. This is an action that changes the contract state.
+ *
+ * @function revoke_chain
+ * @param {string} chain - The chain's name.
+ * @returns {void}
+ */
+await contract.revoke_chain(chain);
+
+/**
+ * Retrieves the chains items can link to. This is a view method.
+ *
+ * @function get_approved_chains
+ * @returns {string[]} The approved chains.
+ */
+await contract.get_approved_chains();
+
+/**
+ * Links an item to the same item listed on another chain, up to 5 links per item. The chain must be approved. Only callable by a store owner. This is an action that changes the contract state.
+ *
+ * @function add_crosschain_link
+ * @param {string} item_id - The item ID.
+ * @param {string} store_id - The store ID.
+ * @param {CrossChainLink} link - The item's listing on the other chain.
+ * @returns {void}
+ */
+await contract.add_crosschain_link(item_id, store_id, link);
+
+/**
+ * Removes an item's link to another chain. Only callable by a store owner. This is an action that changes the contract state.
+ *
+ * @function remove_crosschain_link
+ * @param {string} item_id - The item ID.
+ * @param {string} store_id - The store ID.
+ * @param {CrossChainLink} link - The link to remove.
+ * @returns {void}
+ */
+await contract.remove_crosschain_link(item_id, store_id, link);
+
+/**
+ * Retrieves an item's links to other chains. This is a view method.
+ *
+ * @function get_crosschain_links
+ * @param {string} item_id - The item ID.
+ * @param {string} store_id - The store ID.
+ * @returns {CrossChainLink[]} The item's cross-chain links.
+ */
+await contract.get_crosschain_links(item_id, store_id);
+
+/**
+ * Reassigns an item to a new owner, e.g. to recover it from a compromised account. The reason is recorded in an `admin_ownership_override` log and posted as a platform announcement. Only callable by the overseer. This is an action that changes the contract state.
+ *
+ * @function overseer_transfer_item_ownership
+ * @param {string} item_id - The item ID.
+ * @param {string} store_id - The store ID.
+ * @param {string} new_owner - The new owner's account ID.
+ * @param {string} reason - Why the ownership is reassigned.
+ * @returns {void}
+ */
+await contract.overseer_transfer_item_ownership(item_id, store_id, new_owner, reason);
+
+/**
+ * Previews the cost of buying several items at once, with store sales and shipping applied. Each entry is a single unit; discount codes are not supported yet. The protocol fee is taken from the sellers' proceeds, so it is reported but already part of the grand total. This is a view method.
+ *
+ * @function calculate_checkout_total
+ * @param {Array<[string, string, number, string | null]>} items - `[item_id, store_id, quantity, discount_code]` entries.
+ * @returns {CheckoutSummary} The per-item lines and the aggregated totals.
+ */
+await contract.calculate_checkout_total(items);
+
+/**
+ * Replaces an item's search tags. Tags are trimmed and lowercased, and an item can have up to 10. Only callable by a store owner or manager. This is an action that changes the contract state.
+ *
+ * @function set_item_tags
+ * @param {string} item_id - The item ID.
+ * @param {string} store_id - The store ID.
+ * @param {string[]} tags - The new tags; an empty list removes all of them.
+ * @returns {void}
+ */
+await contract.set_item_tags(item_id, store_id, tags);
+
+/**
+ * Retrieves an item's search tags. This is a view method.
+ *
+ * @function get_item_tags
+ * @param {string} item_id - The item ID.
+ * @param {string} store_id - The store ID.
+ * @returns {string[]} The item's tags.
+ */
+await contract.get_item_tags(item_id, store_id);
+
+/**
+ * Searches items across all stores by tag. This is a view method.
+ *
+ * @function search_items_by_tags
+ * @param {string[]} tags - The tags to search for.
+ * @param {boolean} match_all - Whether items must have all of the tags, rather than any of them.
+ * @param {number} [from_index] - The index to start from.
+ * @param {number} [limit] - The maximum number of items to return.
+ * @returns {Array<[string, ItemMetadata]>} The matching items' `STOREID + DELIMITER + ITEM_ID` keys and metadata.
+ */
+await contract.search_items_by_tags(tags, match_all, from_index, limit);
+
+/**
+ * Sets how many yoctoNEAR one unit of an approved fungible token is worth. Only callable by the overseer. This is an action that changes the contract state.
+ *
+ * @function set_ft_exchange_rate
+ * @param {string} ft_account_id - The fungible token's account ID.
+ * @param {string} rate - The yoctoNEAR value of one token unit (U128).
+ * @returns {void}
+ */
+await contract.set_ft_exchange_rate(ft_account_id, rate);
+
+/**
+ * Retrieves how many yoctoNEAR one unit of a fungible token is worth. This is a view method.
+ *
+ * @function get_ft_exchange_rate
+ * @param {string} ft_account_id - The fungible token's account ID.
+ * @returns {string | null} The exchange rate (U128), or null if none is set.
+ */
+await contract.get_ft_exchange_rate(ft_account_id);
+
+/**
+ * Retrieves an item's price in yoctoNEAR, converting FT-denominated prices with the token's exchange rate. Fails if the token has no exchange rate. This is a view method.
+ *
+ * @function get_item_price_in_near
+ * @param {string} item_id - The item ID.
+ * @param {string} store_id - The store ID.
+ * @returns {string} The price in yoctoNEAR (U128).
+ */
+await contract.get_item_price_in_near(item_id, store_id);
+
+/**
+ * Requires `threshold` of `accounts` to approve the critical overseer actions `set_protocol_fee`, `add_ft` and `withdraw_treasury`, which the overseer can then no longer call directly. Can only be set up once. Only callable by the overseer. This is an action that changes the contract state.
+ *
+ * @function setup_overseer_multisig
+ * @param {string[]} accounts - The multisig members' account IDs.
+ * @param {number} threshold - How many members must approve an action.
+ * @returns {void}
+ */
+await contract.setup_overseer_multisig(accounts, threshold);
+
+/**
+ * Retrieves the multisig members and approval threshold. This is a view method.
+ *
+ * @function get_overseer_multisig
+ * @returns {[string[], number] | null} The members and threshold, or null if no multisig is set up.
+ */
+await contract.get_overseer_multisig();
+
+/**
+ * Proposes a critical overseer action, counting as the proposer's approval. `action_data` is the fee in basis points for `set_protocol_fee`, the token's account ID for `add_ft` and the amount in yoctoNEAR for `withdraw_treasury`. Only callable by multisig members. This is an action that changes the contract state.
+ *
+ * @function propose_admin_action
+ * @param {string} action_type - `set_protocol_fee`, `add_ft` or `withdraw_treasury`.
+ * @param {string} action_data - The action's argument.
+ * @returns {string} The ID of the admin action.
+ */
+await contract.propose_admin_action(action_type, action_data);
+
+/**
+ * Approves a pending admin action, executing it once the threshold is reached. Only callable by multisig members. This is an action that changes the contract state.
+ *
+ * @function approve_admin_action
+ * @param {string} action_id - The admin action ID.
+ * @returns {void}
+ */
+await contract.approve_admin_action(action_id);
+
+/**
+ * Retrieves the admin actions awaiting approval. This is a view method.
+ *
+ * @function get_pending_admin_actions
+ * @returns {MultiSigAction[]} The pending admin actions.
+ */
+await contract.get_pending_admin_actions();
+
+/**
+ * Hands a store over to a DAO contract. The DAO becomes the store's only owner, and from then on it is the only account allowed to manage the store. Only callable by the store's sole owner. This is an action that changes the contract state.
+ *
+ * @function assign_store_to_dao
+ * @param {string} store_id - The store ID.
+ * @param {string} dao_contract - The DAO contract's account ID.
+ * @returns {void}
+ */
+await contract.assign_store_to_dao(store_id, dao_contract);
+
+/**
+ * Checks if a store is controlled by a DAO contract. This is a view method.
+ *
+ * @function is_dao_controlled
+ * @param {string} store_id - The store ID.
+ * @returns {boolean} Whether the store is DAO-controlled.
+ */
+await contract.is_dao_controlled(store_id);
+
+/**
+ * Requires buyers of an item to hold a minimum number of tokens from an NFT collection. Gated items can only be bought through `buy_nft_gated`. An item can't be both token and NFT gated. Only callable by a store owner. This is an action that changes the contract state.
+ *
+ * @function set_item_nft_requirement
+ * @param {string} item_id - The item ID.
+ * @param {string} store_id - The store ID.
+ * @param {string} nft_contract - The NFT collection's contract ID.
+ * @param {number} min_token_count - The minimum number of tokens the buyer must hold.
+ * @returns {void}
+ */
+await contract.set_item_nft_requirement(item_id, store_id, nft_contract, min_token_count);
+
+/**
+ * Retrieves the NFT collection and token count required to buy an item.
+ *
+ * @function get_item_nft_requirement
+ * @param {string} item_id - The item ID.
+ * @param {string} store_id - The store ID.
+ * @returns {[string, number]|null} The `[nft_contract, min_token_count]` pair, or `null` if the item isn't gated.
+ */
+await contract.get_item_nft_requirement(item_id, store_id);
+
+/**
+ * Buys an NFT-gated item. The purchase is checked before the signer's tokens are looked up through the collection's `nft_tokens_for_owner`, and fails right away if it can't go through. If there are too few tokens, or the purchase can no longer go through once they are known, the deposit is refunded and no purchase is made. This is an action that changes the contract state.
+ *
+ * @function buy_nft_gated
+ * @param {string} item_id - The item ID.
+ * @param {string} store_id - The store ID.
+ * @param {string|null} buyer_region - The buyer's ISO 3166-1 alpha-2 region, required by geo-restricted stores.
+ * @returns {BuyReceipt|null} The purchase receipt, or `null` if the deposit was refunded.
+ */
+await contract.buy_nft_gated(item_id, store_id, buyer_region);
+
+/**
+ * Retrieves a summary of a store's owners, items, sales and ratings, e.g. for due diligence. Stores created before creation times were recorded have no `creation_timestamp`. This is a view method.
+ *
+ * @function generate_store_report
+ * @param {string} store_id - The store ID.
+ * @returns {StoreReport} The store's report.
+ */
+await contract.generate_store_report(store_id);
+
+/**
+ * Retrieves when a store was created. This is a view method.
+ *
+ * @function get_store_created_at
+ * @param {string} store_id - The store ID.
+ * @returns {number|null} The creation timestamp, or `null` for stores created before creation times were recorded.
+ */
+await contract.get_store_created_at(store_id);
+
+/**
+ * Retrieves stores along with their creation time, newest first. Stores created before creation times were recorded are left out. This is a view method.
+ *
+ * @function get_stores_by_age
+ * @param {number} [from_index] - The index to start from.
+ * @param {number} [limit] - The maximum number of stores to return.
+ * @returns {Array<[string, number]>} `[store_id, created_at]` pairs.
+ */
+await contract.get_stores_by_age(from_index, limit);
+
+/**
+ * Caps how many times an item can ever be sold, resales included. The cap can't be changed once set. Only callable by a store owner. This is an action that changes the contract state.
+ *
+ * @function set_item_supply_cap
+ * @param {string} item_id - The item ID.
+ * @param {string} store_id - The store ID.
+ * @param {number} cap - The maximum number of sales.
+ * @returns {void}
+ */
+await contract.set_item_supply_cap(item_id, store_id, cap);
+
+/**
+ * Retrieves how many times an item can ever be sold. This is a view method.
+ *
+ * @function get_item_supply_cap
+ * @param {string} item_id - The item ID.
+ * @param {string} store_id - The store ID.
+ * @returns {number|null} The supply cap, or `null` if the item is uncapped.
+ */
+await contract.get_item_supply_cap(item_id, store_id);
+
+/**
+ * Retrieves how many more times an item can be sold. This is a view method.
+ *
+ * @function get_item_remaining_supply
+ * @param {string} item_id - The item ID.
+ * @param {string} store_id - The store ID.
+ * @returns {number} The remaining supply, or the largest u64 if the item is uncapped.
+ */
+await contract.get_item_remaining_supply(item_id, store_id);
+
+/**
+ * Donates a share of an item's sales to a charity. The donation is paid out of the seller's proceeds, up to 5000 basis points, and is logged as a `charity_transfer`. Only callable by a store owner. This is an action that changes the contract state.
+ *
+ * @function set_item_charity_split
+ * @param {string} item_id - The item ID.
+ * @param {string} store_id - The store ID.
+ * @param {string} charity - The charity's account ID.
+ * @param {number} split_bps - The share donated, in basis points; 0 removes the split.
+ * @returns {void}
+ */
+await contract.set_item_charity_split(item_id, store_id, charity, split_bps);
+
+/**
+ * Retrieves the charity an item donates to and its share. This is a view method.
+ *
+ * @function get_item_charity_split
+ * @param {string} item_id - The item ID.
+ * @param {string} store_id - The store ID.
+ * @returns {[string, number]|null} The `[charity, split_bps]` pair, or `null` if the item has no split.
+ */
+await contract.get_item_charity_split(item_id, store_id);
+
+/**
+ * Retrieves the total donated to charity from an item's sales. This is a view method.
+ *
+ * @function get_total_donated_by_item
+ * @param {string} item_id - The item ID.
+ * @param {string} store_id - The store ID.
+ * @returns {string} The total donated (U128).
+ */
+await contract.get_total_donated_by_item(item_id, store_id);
+
+/**
+ * Offers an item for rent at an hourly price. Only callable by a store owner. This is an action that changes the contract state.
+ *
+ * @function set_item_rental_rate
+ * @param {string} item_id - The item ID.
+ * @param {string} store_id - The store ID.
+ * @param {string|null} price_per_hour - The hourly rental price (U128); `null` withdraws the offer.
+ * @returns {void}
+ */
+await contract.set_item_rental_rate(item_id, store_id, price_per_hour);
+
+/**
+ * Retrieves an item's hourly rental price. This is a view method.
+ *
+ * @function get_item_rental_rate
+ * @param {string} item_id - The item ID.
+ * @param {string} store_id - The store ID.
+ * @returns {string|null} The hourly price (U128), or `null` if the item isn't for rent.
+ */
+await contract.get_item_rental_rate(item_id, store_id);
+
+/**
+ * Rents a store-owned item for a duration of up to 365 days. The attached deposit must cover the hourly price for the whole duration, and it is credited to the store's balance. The item's owner doesn't change. This is an action that changes the contract state.
+ *
+ * @function rent_item
+ * @param {string} item_id - The item ID.
+ * @param {string} store_id - The store ID.
+ * @param {number} duration_seconds - How long the rental lasts, in seconds.
+ * @returns {string} The ID of the rental.
+ */
+await contract.rent_item(item_id, store_id, duration_seconds);
+
+/**
+ * Retrieves a rental by its ID. This is a view method.
+ *
+ * @function get_rental
+ * @param {string} rental_id - The rental ID.
+ * @returns {ItemRental|null} The rental, or `null` if it doesn't exist or was cleared.
+ */
+await contract.get_rental(rental_id);
+
+/**
+ * Checks if an account has an unexpired rental of an item. This is a view method.
+ *
+ * @function is_item_rented_by
+ * @param {string} item_id - The item ID.
+ * @param {string} store_id - The store ID.
+ * @param {string} account_id - The account ID.
+ * @returns {boolean} Whether the account currently rents the item.
+ */
+await contract.is_item_rented_by(item_id, store_id, account_id);
+
+/**
+ * Clears an expired rental. Callable by anyone. This is an action that changes the contract state.
+ *
+ * @function expire_rental
+ * @param {string} rental_id - The rental ID.
+ * @returns {void}
+ */
+await contract.expire_rental(rental_id);
+
+/**
+ * Retrieves an item by its normalized name within a store, for human-friendly URLs. This is a view method.
+ *
+ * @function get_item_by_name_and_store
+ * @param {string} store_id - The store ID.
+ * @param {string} normalized_name - The item's name, lowercased with whitespace runs turned into dashes (e.g. `cool-sneakers`).
+ * @returns {ItemMetadata|null} The item's metadata, or `null` if no item has that name.
+ */
+await contract.get_item_by_name_and_store(store_id, normalized_name);
+
+/**
+ * Retrieves the ID of an item by its normalized name within a store. This is a view method.
+ *
+ * @function get_item_id_by_name
+ * @param {string} store_id - The store ID.
+ * @param {string} normalized_name - The item's name, lowercased with whitespace runs turned into dashes.
+ * @returns {string|null} The item ID, or `null` if no item has that name.
+ */
+await contract.get_item_id_by_name(store_id, normalized_name);
+
+/**
+ * Adds an `https://` image to an item's gallery, up to 10 images. The first image is the item's primary thumbnail. Only callable by a store owner. This is an action that changes the contract state.
+ *
+ * @function add_item_image
+ * @param {string} item_id - The item ID.
+ * @param {string} store_id - The store ID.
+ * @param {string} url - The image URL.
+ * @returns {void}
+ */
+await contract.add_item_image(item_id, store_id, url);
+
+/**
+ * Removes an image from an item's gallery. Only callable by a store owner. This is an action that changes the contract state.
+ *
+ * @function remove_item_image
+ * @param {string} item_id - The item ID.
+ * @param {string} store_id - The store ID.
+ * @param {number} index - The position of the image in the gallery.
+ * @returns {void}
+ */
+await contract.remove_item_image(item_id, store_id, index);
+
+/**
+ * Retrieves an item's gallery images. The first image is the item's primary thumbnail. This is a view method.
+ *
+ * @function get_item_images
+ * @param {string} item_id - The item ID.
+ * @param {string} store_id - The store ID.
+ * @returns {string[]} The image URLs.
+ */
+await contract.get_item_images(item_id, store_id);
+
+/**
+ * Records what an item cost the store and the minimum margin its price must keep. Price updates and scheduled price changes below `cost * (10000 + min_margin_bps) / 10000` are then rejected; the overseer's price override is not. Only callable by a store owner. This is an action that changes the contract state.
+ *
+ * @function set_item_cost_basis
+ * @param {string} item_id - The item ID.
+ * @param {string} store_id - The store ID.
+ * @param {string} cost - The item's cost (U128).
+ * @param {number} min_margin_bps - The minimum margin over the cost, in basis points.
+ * @returns {void}
+ */
+await contract.set_item_cost_basis(item_id, store_id, cost, min_margin_bps);
+
+/**
+ * Retrieves an item's current margin over its cost, in basis points; negative when sold at a loss. Margins too large for a 64-bit integer are capped at its maximum. This is a view method.
+ *
+ * @function get_item_effective_margin_bps
+ * @param {string} item_id - The item ID.
+ * @param {string} store_id - The store ID.
+ * @returns {number|null} The margin, or `null` if the item has no cost basis.
+ */
+await contract.get_item_effective_margin_bps(item_id, store_id);
+
+/**
+ * Retrieves an item's reviews that come with photos or videos. This is a view method.
+ *
+ * @function get_item_reviews_with_media
+ * @param {string} item_id - The item ID.
+ * @param {string} store_id - The store ID.
+ * @returns {ItemRating[]} The reviews with media.
+ */
+await contract.get_item_reviews_with_media(item_id, store_id);
+
+/**
+ * Flags a review for moderation. Each account can report a review once. This is an action that changes the contract state.
+ *
+ * @function report_review
+ * @param {string} review_id - The review ID, `STOREID + DELIMITER + ITEM_ID + DELIMITER + REVIEWER`.
+ * @param {string} reason - Why the review is reported.
+ * @returns {void}
+ */
+await contract.report_review(review_id, reason);
+
+/**
+ * Retrieves the reviews flagged for moderation, along with their reports. This is a view method.
+ *
+ * @function get_reported_reviews
+ * @param {number} [from_index] - The index to start from.
+ * @param {number} [limit] - The maximum number of reviews to return.
+ * @returns {Array<[string, Array<[string, string]>]>} `[review_id, [reporter, reason][]]` pairs.
+ */
+await contract.get_reported_reviews(from_index, limit);
+
+/**
+ * Removes a review and its reports. Its author can't review the item again. Only callable by the overseer. This is an action that changes the contract state.
+ *
+ * @function remove_review
+ * @param {string} review_id - The review ID.
+ * @returns {void}
+ */
+await contract.remove_review(review_id);
+
+/**
+ * Sets an `https://` URL to be notified of the store's sales. Every sale then emits a `store_sale` event carrying the URL, which indexers can relay to it. The URL is therefore public in the contract's logs. Only callable by a store owner. This is an action that changes the contract state.
+ *
+ * @function set_store_webhook_url
+ * @param {string} store_id - The store ID.
+ * @param {string|null} url - The webhook URL; `null` removes it.
+ * @returns {void}
+ */
+await contract.set_store_webhook_url(store_id, url);
+
+/**
+ * Retrieves the URL notified of the store's sales. This is a view method.
+ *
+ * @function get_store_webhook_url
+ * @param {string} store_id - The store ID.
+ * @returns {string|null} The webhook URL, or `null` if none is set.
+ */
+await contract.get_store_webhook_url(store_id);
+
+/**
+ * Retrieves an item's previous prices, oldest first, with the time each was replaced. Price updates, scheduled price changes, dynamic pricing bumps and overseer overrides are all recorded, up to the last 100. This is a view method.
+ *
+ * @function get_item_price_history
+ * @param {string} item_id - The item ID.
+ * @param {string} store_id - The store ID.
+ * @param {number} [from_index] - The index to start from.
+ * @param {number} [limit] - The maximum number of entries to return.
+ * @returns {Array<[string, number]>} `[price, replaced_at]` pairs, the price being a U128.
+ */
+await contract.get_item_price_history(item_id, store_id, from_index, limit);
+
+/**
+ * Retrieves a store's items added at or after a timestamp, newest first. This is a view method.
+ *
+ * @function get_items_added_since
+ * @param {string} store_id - The store ID.
+ * @param {number} since_timestamp - The earliest creation time, in nanoseconds.
+ * @param {number} [from_index] - The index to start from.
+ * @param {number} [limit] - The maximum number of items to return.
+ * @returns {Array<[string, ItemMetadata]>} `[item_id, metadata]` pairs.
+ */
+await contract.get_items_added_since(store_id, since_timestamp, from_index, limit);
+
+/**
+ * Retrieves a store's most recently added items, newest first. This is a view method.
+ *
+ * @function get_newest_items_by_store
+ * @param {string} store_id - The store ID.
+ * @param {number} limit - The maximum number of items to return.
+ * @returns {Array<[string, ItemMetadata]>} `[item_id, metadata]` pairs.
+ */
+await contract.get_newest_items_by_store(store_id, limit);
@@ -3,8 +3,13 @@ export interface ItemMetadata {
   price: string;  // U128 in NEAR is used for large integer values, which can be represented as a string in TypeScript
   imgUrl: string;
   owner: string;  // AccountId in NEAR is a string that represents an account's unique ID
+  condition: ItemCondition | null;
+  upc: string | null;  // 12-digit UPC-A or 13-digit EAN-13 barcode
+  video_url: string | null;  // https:// URL
 }
 
+export type ItemCondition = "New" | "LikeNew" | "VeryGood" | "Good" | "Acceptable";
+
 export interface Log {
   id: string;
   timestamp: number;  // u64 in Rust is a large integer, which can be represented as a number in TypeScript
@@ -12,4 +17,251 @@ export interface Log {
   actor: string;
   entity: string;
   extra: string;
-}
\ No newline at end of file
+  prev_hash: number[] | null;  // sha256 of the previous log in the chain
+}
+
+export interface BuyReceipt {
+  message: string;
+  transaction_id: string;
+  item_id: string;
+  store_id: string;
+  buyer: string;
+  price_paid: string;  // U128
+  block_timestamp: number;
+}
+
+export interface ScheduledPriceChange {
+  item_id: string;
+  store_id: string;
+  new_price: string;  // U128
+  execute_at: number;
+}
+
+export interface GroupBuy {
+  item_id: string;
+  store_id: string;
+  target_count: number;
+  discount_price: string;  // U128
+  deadline: number;
+  participants: string[];
+  deposits: number[];
+  executed: boolean;
+}
+
+export interface Giveaway {
+  item_id: string;
+  store_id: string;
+  max_entries: number;
+  entries: string[];
+  winner: string | null;
+  end_time: number;
+  executed: boolean;
+}
+
+export interface StoreRating {
+  store_id: string;
+  reviewer: string;
+  score: number;
+  comment: string;
+  created_at: number;
+}
+
+export interface ItemRating {
+  item_id: string;
+  store_id: string;
+  reviewer: string;
+  score: number;
+  review: string;
+  created_at: number;
+  media: ReviewMedia[];
+}
+
+export interface ReviewMedia {
+  url: string;  // https:// URL
+  media_type: "image" | "video";
+}
+
+export interface StoreGeoPolicy {
+  mode: "Allow" | "Deny";
+  regions: string[];  // ISO 3166-1 alpha-2 codes
+}
+
+export interface ShippingInfo {
+  ships_from_region: string;
+  estimated_days_min: number;
+  estimated_days_max: number;
+  flat_shipping_fee: string;  // U128
+  free_shipping_threshold: string | null;  // U128
+}
+
+export interface SubscriptionPlan {
+  store_id: string;
+  item_id: string;
+  price_per_period: string;  // U128
+  period_seconds: number;
+  max_periods: number | null;
+}
+
+export interface Subscription {
+  plan_id: string;
+  subscriber: string;
+  started_at: number;
+  periods_paid: number;
+  next_due: number;
+  active: boolean;
+}
+
+export interface Invoice {
+  invoice_id: string;
+  buyer: string;
+  seller: string;
+  item_name: string;
+  price: string;  // U128
+  currency: string;
+  tax_rate_bps: number;
+  tax_amount: string;  // U128
+  subtotal: string;  // U128
+  total: string;  // U128
+  transaction_timestamp: number;
+  store_id: string;
+}
+
+export interface StoreSalePeriod {
+  store_id: string;
+  discount_bps: number;
+  start_time: number;
+  end_time: number;
+}
+
+export interface StoreMetadata {
+  name: string;
+  description: string;
+  logo_url: string;
+  website_url: string;
+}
+
+export interface CatalogItem {
+  id: string;
+  name: string;
+  price: string;  // U128
+  img_url: string;
+}
+
+export interface ImportResult {
+  imported: number;
+  failed: number;
+  errors: string[];
+}
+
+export interface ReferralProgram {
+  store_id: string;
+  referral_reward_bps: number;
+  referred_discount_bps: number;
+  active: boolean;
+}
+
+export type ActivityEvent =
+  | { Log: Log }
+  | { NewItem: { item_id: string; timestamp: number } }
+  | { Sale: { item_id: string; buyer: string; price: string; timestamp: number } };  // price is a U128
+
+export interface DynamicPricingConfig {
+  store_id: string;
+  item_id: string;
+  price_increment_bps: number;
+  max_price: string | null;  // U128
+}
+
+export interface PlatformAnnouncement {
+  id: string;
+  title: string;
+  body: string;
+  posted_at: number;
+  expires_at: number | null;
+}
+
+export interface ItemCertificate {
+  issuer: string;
+  certificate_type: string;
+  certificate_id: string;
+  issued_at: number;
+  expires_at: number | null;
+  metadata_url: string;
+}
+
+export interface LoyaltyTier {
+  name: string;
+  min_lifetime_spend: string;  // U128
+  benefits: string;
+}
+
+export interface Partnership {
+  store_a: string;
+  store_b: string;
+  created_at: number;
+  cross_discount_bps: number;
+  accepted: boolean;
+}
+
+export interface FeaturedBanner {
+  store_id: string;
+  image_url: string;
+  cta_text: string;
+  link_url: string;
+  starts_at: number;
+  ends_at: number;
+}
+
+export interface CrossChainLink {
+  chain: string;
+  contract: string;
+  item_id: string;
+}
+
+export interface LineItem {
+  item_id: string;
+  store_id: string;
+  quantity: number;
+  unit_price: string;  // U128
+  discount: string;  // U128
+  shipping: string;  // U128
+  total: string;  // U128
+}
+
+export interface CheckoutSummary {
+  line_items: LineItem[];
+  subtotal: string;  // U128
+  total_discount: string;  // U128
+  total_shipping: string;  // U128
+  protocol_fee: string;  // U128
+  grand_total: string;  // U128
+}
+
+export interface MultiSigAction {
+  id: string;
+  action_type: string;
+  action_data: string;
+  proposer: string;
+  approvals: string[];
+}
+
+export interface StoreReport {
+  store_id: string;
+  owner_count: number;
+  item_count: number;
+  total_sales: number;
+  total_revenue: string;  // U128
+  average_item_price: string;  // U128
+  creation_timestamp: number | null;
+  average_rating: number | null;  // rounded to the nearest score
+  latest_sale_timestamp: number | null;
+}
+
+export interface ItemRental {
+  item_id: string;
+  store_id: string;
+  renter: string;
+  rental_price: string;  // U128
+  duration_seconds: number;
+  started_at: number;
+}
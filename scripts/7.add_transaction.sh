@@ -1 +1 @@
-near call $NFT_CONTRACT_ID add_transaction '{"action": "String", "actor": "String", "entity": "String", "extra": "String"}' --accountId $BUYER_ID
\ No newline at end of file
+near call $NFT_CONTRACT_ID admin_add_log '{"action": "String", "entity": "String", "extra": "String"}' --accountId $OVERSEER_ID
\ No newline at end of file
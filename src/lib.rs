@@ -1,19 +1,215 @@
+// contract methods take their arguments flat, as the JSON interface exposes them
+#![allow(clippy::too_many_arguments)]
+
 use std::vec;
 
+use near_contract_standards::fungible_token::core::ext_ft_core;
+use near_contract_standards::non_fungible_token::Token;
 use near_sdk::json_types::U128;
 
 use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
 use near_sdk::collections::{LookupMap, TreeMap, UnorderedMap, UnorderedSet};
 use near_sdk::serde::{Deserialize, Serialize};
 use near_sdk::{
-    self, env, near_bindgen, require, AccountId, BorshStorageKey, IntoStorageKey, PanicOnDefault,
-    Promise,
+    self, env, ext_contract, near_bindgen, require, AccountId, BorshStorageKey, Gas,
+    IntoStorageKey, PanicOnDefault, Promise, PromiseError,
 };
 use serde_json::json;
+use std::fmt;
 
 // every item metadata will have a unique ID which is `STOREID + DELIMITER + ITEM_ID`
 static DELIMETER: &str = ".";
 
+// layout version of the contract state, bumped whenever a migration is required
+const STATE_VERSION: u8 = 2;
+
+// maximum number of items repriced in one `update_item_price_batch` call
+const MAX_PRICE_BATCH_SIZE: usize = 100;
+
+// maximum number of items fetched in one `batch_get_items` call
+const MAX_ITEM_BATCH_SIZE: usize = 100;
+
+// number of previous metadata versions kept per item
+const MAX_ITEM_HISTORY_SIZE: usize = 20;
+
+// number of previous prices kept per item
+const MAX_PRICE_HISTORY_SIZE: usize = 100;
+
+// maximum number of items imported in one `import_store_from_json` call
+const MAX_IMPORT_BATCH_SIZE: usize = 50;
+
+// maximum number of co-owners added in one `add_store_owners_batch` call
+const MAX_OWNERS_BATCH_SIZE: usize = 20;
+
+// maximum number of entries returned by the store leaderboards
+const MAX_LEADERBOARD_SIZE: u64 = 50;
+
+// maximum number of stores returned by the cross-store price comparison
+const MAX_PRICE_COMPARISON_SIZE: usize = 20;
+
+// maximum number of platform announcements kept at once
+const MAX_ANNOUNCEMENTS: usize = 10;
+
+// time before an account's next view of an item's video is counted again, in nanoseconds
+const VIDEO_VIEW_COOLDOWN: u64 = 3_600_000_000_000;
+
+// maximum number of cross-chain links per item
+const MAX_CROSSCHAIN_LINKS: usize = 5;
+
+// maximum number of featured banners shown at once
+const MAX_ACTIVE_BANNERS: usize = 5;
+
+// maximum number of photos and videos attached to a review
+const MAX_REVIEW_MEDIA: usize = 5;
+
+// maximum number of gallery images per item
+const MAX_ITEM_IMAGES: usize = 10;
+
+// maximum number of search tags per item
+const MAX_ITEM_TAGS: usize = 10;
+
+// minimum age buyers must attest to before buying age-restricted items
+const MIN_RESTRICTED_AGE: u8 = 18;
+
+// shortest voting period of a governance proposal, 3 days in nanoseconds
+const MIN_PROPOSAL_VOTING_PERIOD: u64 = 259_200_000_000_000;
+
+// fewest votes a governance proposal needs before it can pass
+const MIN_PROPOSAL_QUORUM: u64 = 3;
+
+// longest an item can be rented for at once, 365 days in seconds
+const MAX_RENTAL_DURATION: u64 = 31_536_000;
+
+// longest a subscription period can be, 365 days in seconds
+const MAX_SUBSCRIPTION_PERIOD: u64 = 31_536_000;
+
+// highest protocol fee the overseer can charge on a sale, in basis points
+const MAX_PROTOCOL_FEE_BPS: u16 = 1_000;
+
+// highest resale royalty a store can set, in basis points
+const MAX_ROYALTY_BPS: u16 = 5_000;
+
+// highest share of an item's sales a store can donate to charity, in basis points
+const MAX_CHARITY_SPLIT_BPS: u16 = 5_000;
+
+// gas for the token balance lookup of a token-gated purchase, and for the purchase itself
+const GAS_FOR_FT_BALANCE_OF: Gas = Gas(10_000_000_000_000);
+const GAS_FOR_FT_GATE_CALLBACK: Gas = Gas(50_000_000_000_000);
+
+// gas for the token lookup of an NFT-gated purchase, and for the purchase itself
+const GAS_FOR_NFT_TOKENS_FOR_OWNER: Gas = Gas(20_000_000_000_000);
+const GAS_FOR_NFT_GATE_CALLBACK: Gas = Gas(50_000_000_000_000);
+
+// gas for handing a cross-contract purchase's receipt back to the calling contract
+const GAS_FOR_CROSS_CONTRACT_BUY_CALLBACK: Gas = Gas(5_000_000_000_000);
+
+// NEP-297 event standard name and version emitted by this contract
+static EVENT_STANDARD: &str = "storehub";
+static EVENT_VERSION: &str = "1.0.0";
+
+// Errors the contract panics with, rendered as the `StoreHub: ...` messages clients match on
+#[derive(Debug, PartialEq)]
+pub enum StoreHubError {
+    AccessDenied,
+    SignerNotStoreOwner,
+    StoreNotFound,
+    ItemNotFound,
+    ItemNotInStore,
+    ItemNotTokenGated,
+    ItemNotNftGated,
+    ItemNotForRent,
+    ItemPricedInFt(AccountId),
+    InsufficientDeposit(u128, u128),
+    FeesExceedDeposit,
+    BuyerRegionRequired,
+    InvalidImportPayload,
+    AffiliateCodeNotFound,
+    ReferralProgramNotFound,
+    ScheduledPriceChangeNotFound,
+    GroupBuyNotFound,
+    ProposalNotFound,
+    GiveawayNotFound,
+    SubscriptionPlanNotFound,
+    SubscriptionNotFound,
+    AnnouncementNotFound,
+    CertificateNotFound,
+    PartnershipNotFound,
+    MultisigNotFound,
+    AdminActionNotFound,
+    RentalNotFound,
+    ReviewNotFound,
+    ExchangeRateNotFound(AccountId),
+    LogNotFound(String),
+    NotInitialized,
+    AlreadyMigrated,
+    InternalError,
+}
+
+impl fmt::Display for StoreHubError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::AccessDenied => write!(f, "StoreHub: access denied"),
+            Self::SignerNotStoreOwner => write!(f, "StoreHub: signer not store owner"),
+            Self::StoreNotFound => write!(f, "StoreHub: store doesn't exist"),
+            Self::ItemNotFound => write!(f, "StoreHub: item doesn't exist"),
+            Self::ItemNotInStore => write!(f, "StoreHub: this item doesn't exist for this store"),
+            Self::ItemNotTokenGated => write!(f, "StoreHub: item is not token gated"),
+            Self::ItemNotNftGated => write!(f, "StoreHub: item is not NFT gated"),
+            Self::ItemNotForRent => write!(f, "StoreHub: item is not for rent"),
+            Self::ItemPricedInFt(ft_account_id) => write!(
+                f,
+                "StoreHub: item is priced in {}, use buy_with_ft",
+                ft_account_id
+            ),
+            Self::InsufficientDeposit(deposit, price) => write!(
+                f,
+                "StoreHub: deposit is below price ({} < {})",
+                deposit, price
+            ),
+            Self::FeesExceedDeposit => write!(f, "StoreHub: fees exceed deposit"),
+            Self::BuyerRegionRequired => write!(f, "StoreHub: buyer region required"),
+            Self::InvalidImportPayload => write!(f, "StoreHub: invalid import payload"),
+            Self::AffiliateCodeNotFound => write!(f, "StoreHub: affiliate code doesn't exist"),
+            Self::ReferralProgramNotFound => write!(f, "StoreHub: referral program doesn't exist"),
+            Self::ScheduledPriceChangeNotFound => {
+                write!(f, "StoreHub: scheduled price change doesn't exist")
+            }
+            Self::GroupBuyNotFound => write!(f, "StoreHub: group buy doesn't exist"),
+            Self::ProposalNotFound => write!(f, "StoreHub: proposal doesn't exist"),
+            Self::GiveawayNotFound => write!(f, "StoreHub: giveaway doesn't exist"),
+            Self::SubscriptionPlanNotFound => {
+                write!(f, "StoreHub: subscription plan doesn't exist")
+            }
+            Self::SubscriptionNotFound => write!(f, "StoreHub: subscription doesn't exist"),
+            Self::AnnouncementNotFound => write!(f, "StoreHub: announcement doesn't exist"),
+            Self::CertificateNotFound => write!(f, "StoreHub: certificate doesn't exist"),
+            Self::PartnershipNotFound => write!(f, "StoreHub: partnership doesn't exist"),
+            Self::MultisigNotFound => write!(f, "StoreHub: multisig not set up"),
+            Self::AdminActionNotFound => write!(f, "StoreHub: admin action doesn't exist"),
+            Self::RentalNotFound => write!(f, "StoreHub: rental doesn't exist"),
+            Self::ReviewNotFound => write!(f, "StoreHub: review doesn't exist"),
+            Self::ExchangeRateNotFound(ft_account_id) => {
+                write!(f, "StoreHub: no exchange rate for {}", ft_account_id)
+            }
+            Self::LogNotFound(log_id) => write!(f, "No log found with id {}", log_id),
+            Self::NotInitialized => write!(f, "StoreHub: contract is not initialized"),
+            Self::AlreadyMigrated => write!(f, "StoreHub: state already migrated"),
+            Self::InternalError => write!(f, "StoreHub: internal contract error"),
+        }
+    }
+}
+
+// NEP-181 enumeration of the tokens an account holds in an NFT collection
+#[ext_contract(ext_nft_enumeration)]
+pub trait NftEnumeration {
+    fn nft_tokens_for_owner(
+        &self,
+        account_id: AccountId,
+        from_index: Option<U128>,
+        limit: Option<u64>,
+    ) -> Vec<Token>;
+}
+
 // Creating custom types to use within the contract. This makes things more readable.
 pub type ItemId = String;
 pub type StoreId = AccountId;
@@ -27,6 +223,21 @@ pub struct ItemMetadata {
     pub price: U128,
     pub img_url: String,
     pub owner: AccountId,
+    pub condition: Option<ItemCondition>,
+    pub upc: Option<String>,
+    pub video_url: Option<String>,
+}
+
+// Defines the grade of a second-hand item
+#[derive(
+    Serialize, Deserialize, BorshDeserialize, BorshSerialize, Clone, Copy, PartialEq, Debug,
+)]
+pub enum ItemCondition {
+    New,
+    LikeNew,
+    VeryGood,
+    Good,
+    Acceptable,
 }
 
 // Defines action-driven event on each store
@@ -39,6 +250,392 @@ pub struct Log {
     pub actor: String,
     pub entity: String,
     pub extra: String,
+    pub prev_hash: Option<[u8; 32]>,
+}
+
+// Defines the receipt returned to the buyer of an item
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+pub struct BuyReceipt {
+    pub message: String,
+    pub transaction_id: String,
+    pub item_id: ItemId,
+    pub store_id: AccountId,
+    pub buyer: AccountId,
+    pub price_paid: U128,
+    pub block_timestamp: u64,
+}
+
+// Defines a referral code earning its affiliate a commission on a store's sales
+#[derive(Serialize, Deserialize, BorshDeserialize, BorshSerialize, Clone)]
+pub struct AffiliateCode {
+    pub code: String,
+    pub affiliate: AccountId,
+    pub store_id: AccountId,
+    pub commission_bps: u16,
+    pub active: bool,
+}
+
+// Defines an item price change to be applied once `execute_at` is reached
+#[derive(Serialize, Deserialize, BorshDeserialize, BorshSerialize)]
+pub struct ScheduledPriceChange {
+    pub item_id: ItemId,
+    pub store_id: AccountId,
+    pub new_price: U128,
+    pub execute_at: u64,
+}
+
+// Defines a collective purchase unlocking `discount_price` once `target_count` buyers joined
+#[derive(Serialize, Deserialize, BorshDeserialize, BorshSerialize)]
+pub struct GroupBuy {
+    pub item_id: ItemId,
+    pub store_id: AccountId,
+    pub target_count: u32,
+    pub discount_price: U128,
+    pub deadline: u64,
+    pub participants: Vec<AccountId>,
+    pub deposits: Vec<u128>,
+    pub executed: bool,
+}
+
+// Defines a buyer's review of a store, scored from 1 to 5
+#[derive(Serialize, Deserialize, BorshDeserialize, BorshSerialize)]
+pub struct StoreRating {
+    pub store_id: AccountId,
+    pub reviewer: AccountId,
+    pub score: u8,
+    pub comment: String,
+    pub created_at: u64,
+}
+
+// Defines a buyer's review of an item, scored from 1 to 5
+#[derive(Serialize, Deserialize, BorshDeserialize, BorshSerialize)]
+pub struct ItemRating {
+    pub item_id: ItemId,
+    pub store_id: AccountId,
+    pub reviewer: AccountId,
+    pub score: u8,
+    pub review: String,
+    pub created_at: u64,
+    pub media: Vec<ReviewMedia>,
+}
+
+// Defines a photo or video attached to an item review
+#[derive(Serialize, Deserialize, BorshDeserialize, BorshSerialize, Clone, Debug, PartialEq)]
+pub struct ReviewMedia {
+    pub url: String,
+    pub media_type: String,
+}
+
+// Defines whether a store's geo policy lists the regions it ships to or the ones it doesn't
+#[derive(Serialize, Deserialize, BorshDeserialize, BorshSerialize, PartialEq)]
+pub enum GeoMode {
+    Allow,
+    Deny,
+}
+
+// Defines the regions, as ISO 3166-1 alpha-2 codes, a store accepts purchases from
+#[derive(Serialize, Deserialize, BorshDeserialize, BorshSerialize)]
+pub struct StoreGeoPolicy {
+    pub mode: GeoMode,
+    pub regions: Vec<String>,
+}
+
+// Defines how a physical item is delivered and what the buyer pays for it
+#[derive(Serialize, Deserialize, BorshDeserialize, BorshSerialize)]
+pub struct ShippingInfo {
+    pub ships_from_region: String,
+    pub estimated_days_min: u32,
+    pub estimated_days_max: u32,
+    pub flat_shipping_fee: U128,
+    pub free_shipping_threshold: Option<U128>,
+}
+
+// Defines a recurring payment plan for an item, billed every `period_seconds`
+#[derive(Serialize, Deserialize, BorshDeserialize, BorshSerialize)]
+pub struct SubscriptionPlan {
+    pub store_id: AccountId,
+    pub item_id: ItemId,
+    pub price_per_period: U128,
+    pub period_seconds: u64,
+    pub max_periods: Option<u32>,
+}
+
+// Defines an account's subscription to a plan, paid up until `next_due`
+#[derive(Serialize, Deserialize, BorshDeserialize, BorshSerialize)]
+pub struct Subscription {
+    pub plan_id: String,
+    pub subscriber: AccountId,
+    pub started_at: u64,
+    pub periods_paid: u32,
+    pub next_due: u64,
+    pub active: bool,
+}
+
+// Defines the invoice of a purchase, assembled from its `buy` log
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+pub struct Invoice {
+    pub invoice_id: String,
+    pub buyer: AccountId,
+    pub seller: AccountId,
+    pub item_name: String,
+    pub price: U128,
+    pub currency: String,
+    pub tax_rate_bps: u16,
+    pub tax_amount: U128,
+    pub subtotal: U128,
+    pub total: U128,
+    pub transaction_timestamp: u64,
+    pub store_id: AccountId,
+}
+
+// Defines a store-wide discount applied to first-hand sales between `start_time` and `end_time`
+#[derive(Serialize, Deserialize, BorshDeserialize, BorshSerialize)]
+pub struct StoreSalePeriod {
+    pub store_id: AccountId,
+    pub discount_bps: u16,
+    pub start_time: u64,
+    pub end_time: u64,
+}
+
+// Defines what an account may do in a store: owners manage everything, managers
+// manage the catalog and viewers can only read the store's private data
+#[derive(
+    Serialize, Deserialize, BorshDeserialize, BorshSerialize, Clone, Copy, PartialEq, Debug,
+)]
+pub enum StoreRole {
+    Owner,
+    Manager,
+    Viewer,
+}
+
+// Defines how a store presents itself in the marketplace
+#[derive(Serialize, Deserialize, BorshDeserialize, BorshSerialize, Clone, PartialEq, Debug)]
+pub struct StoreMetadata {
+    pub name: String,
+    pub description: String,
+    pub logo_url: String,
+    pub website_url: String,
+}
+
+// Defines an item in the catalog format used to import and export stores
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+pub struct CatalogItem {
+    pub id: ItemId,
+    pub name: String,
+    pub price: U128,
+    pub img_url: String,
+}
+
+// Defines the outcome of a catalog import
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+pub struct ImportResult {
+    pub imported: u32,
+    pub failed: u32,
+    pub errors: Vec<String>,
+}
+
+// Defines a store's referral program: referred buyers get a discount on the
+// store's listings and their referrer is rewarded out of the sale
+#[derive(Serialize, Deserialize, BorshDeserialize, BorshSerialize, Clone)]
+pub struct ReferralProgram {
+    pub store_id: AccountId,
+    pub referral_reward_bps: u16,
+    pub referred_discount_bps: u16,
+    pub active: bool,
+}
+
+// Defines how much an item's price rises after each purchase
+#[derive(Serialize, Deserialize, BorshDeserialize, BorshSerialize)]
+pub struct DynamicPricingConfig {
+    pub store_id: AccountId,
+    pub item_id: ItemId,
+    pub price_increment_bps: u16,
+    pub max_price: Option<U128>,
+}
+
+// Defines a notice the overseer broadcasts to every front-end
+#[derive(Serialize, Deserialize, BorshDeserialize, BorshSerialize, Clone)]
+pub struct PlatformAnnouncement {
+    pub id: String,
+    pub title: String,
+    pub body: String,
+    pub posted_at: u64,
+    pub expires_at: Option<u64>,
+}
+
+// Defines a document vouching for an item's authenticity
+#[derive(Serialize, Deserialize, BorshDeserialize, BorshSerialize, Clone)]
+pub struct ItemCertificate {
+    pub issuer: AccountId,
+    pub certificate_type: String,
+    pub certificate_id: String,
+    pub issued_at: u64,
+    pub expires_at: Option<u64>,
+    pub metadata_url: String,
+}
+
+// Defines a platform loyalty tier reached at a lifetime spending threshold
+#[derive(Serialize, Deserialize, BorshDeserialize, BorshSerialize, Clone, PartialEq, Debug)]
+pub struct LoyaltyTier {
+    pub name: String,
+    pub min_lifetime_spend: U128,
+    pub benefits: String,
+}
+
+// Defines a cross-promotion between two stores: once accepted, buyers of
+// either store get `cross_discount_bps` off the other store's listings
+#[derive(Serialize, Deserialize, BorshDeserialize, BorshSerialize, Clone)]
+pub struct Partnership {
+    pub store_a: AccountId,
+    pub store_b: AccountId,
+    pub created_at: u64,
+    pub cross_discount_bps: u16,
+    pub accepted: bool,
+}
+
+// Defines a paid homepage placement for a store between `starts_at` and `ends_at`
+#[derive(Serialize, Deserialize, BorshDeserialize, BorshSerialize, Clone)]
+pub struct FeaturedBanner {
+    pub store_id: AccountId,
+    pub image_url: String,
+    pub cta_text: String,
+    pub link_url: String,
+    pub starts_at: u64,
+    pub ends_at: u64,
+}
+
+// Defines where the same item is listed on another chain
+#[derive(Serialize, Deserialize, BorshDeserialize, BorshSerialize, Clone, PartialEq, Debug)]
+pub struct CrossChainLink {
+    pub chain: String,
+    pub contract: String,
+    pub item_id: String,
+}
+
+// Defines a priced line of a checkout preview
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+pub struct LineItem {
+    pub item_id: ItemId,
+    pub store_id: AccountId,
+    pub quantity: u32,
+    pub unit_price: U128,
+    pub discount: U128,
+    pub shipping: U128,
+    pub total: U128,
+}
+
+// Defines a summary of a store's activity, for due diligence
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+pub struct StoreReport {
+    pub store_id: AccountId,
+    pub owner_count: u64,
+    pub item_count: u64,
+    pub total_sales: u64,
+    pub total_revenue: U128,
+    pub average_item_price: U128,
+    pub creation_timestamp: Option<u64>,
+    pub average_rating: Option<u8>,
+    pub latest_sale_timestamp: Option<u64>,
+}
+
+// Defines the totals of a checkout preview
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+pub struct CheckoutSummary {
+    pub line_items: Vec<LineItem>,
+    pub subtotal: U128,
+    pub total_discount: U128,
+    pub total_shipping: U128,
+    pub protocol_fee: U128,
+    pub grand_total: U128,
+}
+
+// Defines an entry of a store's activity feed
+#[derive(Serialize, Deserialize)]
+pub enum ActivityEvent {
+    Log(Log),
+    NewItem {
+        item_id: ItemId,
+        timestamp: u64,
+    },
+    Sale {
+        item_id: ItemId,
+        buyer: AccountId,
+        price: U128,
+        timestamp: u64,
+    },
+}
+
+// Defines an on-chain vote on an overseer action, one account one vote
+#[derive(BorshDeserialize, BorshSerialize)]
+pub struct GovernanceProposal {
+    pub id: String,
+    pub description: String,
+    pub target_fn: String,
+    pub calldata: String,
+    pub votes_for: u64,
+    pub votes_against: u64,
+    pub voters: UnorderedSet<AccountId>,
+    pub end_time: u64,
+    pub executed: bool,
+}
+
+// Defines time-limited access to an item, its ownership staying with the store
+#[derive(Serialize, Deserialize, BorshDeserialize, BorshSerialize, Debug, PartialEq)]
+pub struct ItemRental {
+    pub item_id: ItemId,
+    pub store_id: AccountId,
+    pub renter: AccountId,
+    pub rental_price: U128,
+    pub duration_seconds: u64,
+    pub started_at: u64,
+}
+
+// Defines the accounts that must jointly approve critical overseer actions
+#[derive(BorshDeserialize, BorshSerialize)]
+pub struct OverseerMultisig {
+    pub accounts: Vec<AccountId>,
+    pub threshold: u32,
+    pub pending_actions: UnorderedMap<String, MultiSigAction>,
+    pub next_action_id: u64,
+}
+
+// Defines a critical overseer action awaiting multisig approval
+#[derive(Serialize, Deserialize, BorshDeserialize, BorshSerialize, Clone)]
+pub struct MultiSigAction {
+    pub id: String,
+    pub action_type: String,
+    pub action_data: String,
+    pub proposer: AccountId,
+    pub approvals: Vec<AccountId>,
+}
+
+// Defines a free distribution of a store's item to a random entrant after `end_time`
+#[derive(Serialize, Deserialize, BorshDeserialize, BorshSerialize)]
+pub struct Giveaway {
+    pub item_id: ItemId,
+    pub store_id: AccountId,
+    pub max_entries: u32,
+    pub entries: Vec<AccountId>,
+    pub winner: Option<AccountId>,
+    pub end_time: u64,
+    pub executed: bool,
+}
+
+// Defines which cross-contract gate a purchase already passed
+#[derive(Clone, Copy, PartialEq)]
+enum PurchaseGate {
+    Ft,
+    Nft,
+}
+
+// Defines the price and discounts of a purchase that passed its checks
+struct BuyQuote {
+    price: u128,
+    sale_discount: u128,
+    partner_discount: u128,
+    referral_discount: u128,
+    shipping_fee: u128,
+    referral_program: Option<ReferralProgram>,
 }
 
 /// Helper structure for storage keys of the persistent collections.
@@ -53,6 +650,95 @@ pub enum StorageKey {
     FungibleTokenIds,
     StoresPerOwnerInner,
     AuditLogs,
+    BuyerPurchases,
+    BuyerPurchasesInner { account_hash: Vec<u8> },
+    StoreMinimumDeposits,
+    ItemsPerStore,
+    ItemsPerStoreInner { store_hash: Vec<u8> },
+    ItemPurchaseCounts,
+    AffiliateCodes,
+    AffiliateEarnings,
+    ScheduledPriceChanges,
+    GroupBuys,
+    NextItemSequence,
+    AllStores,
+    StoreRevenue,
+    StoreBalances,
+    StoreCurrencies,
+    ItemCurrencies,
+    Giveaways,
+    GovernanceProposals,
+    ProposalVotersInner { proposal_hash: Vec<u8> },
+    StoreRatings,
+    ItemRatings,
+    StoreRoyalties,
+    StoreRoyaltiesEarned,
+    ItemRoyaltiesEarned,
+    StoresByItem,
+    StoresByItemInner { item_hash: Vec<u8> },
+    AllItems,
+    ItemPurchaseLimits,
+    BuyerItemPurchaseCounts,
+    StoreGeoPolicies,
+    ItemFtRequirements,
+    ItemShipping,
+    LockedItems,
+    SubscriptionPlans,
+    Subscriptions,
+    StoreTaxRates,
+    StoreSalePeriods,
+    StoreRoles,
+    ItemHistory,
+    CallerContracts,
+    StoreMetadataById,
+    ItemsPendingApproval,
+    ClosedForListing,
+    BuyerStoreSpending,
+    ReferralPrograms,
+    ReferralEarnings,
+    ItemCooldowns,
+    BuyerLastPurchaseTime,
+    ItemCreatedAt,
+    StoreTerms,
+    TermsAcceptances,
+    AgeRestrictedItems,
+    AgeAttestations,
+    DynamicPricing,
+    StoreStakes,
+    StoreStakesInner { store_hash: Vec<u8> },
+    ItemWaitlists,
+    ItemCertificates,
+    CertifiedIssuers,
+    BuyerLifetimeSpend,
+    StorePartnerships,
+    BarcodeIndex,
+    ItemVideoViews,
+    LastVideoViews,
+    ItemCrosschainLinks,
+    ApprovedChains,
+    TagIndex,
+    TagIndexInner { tag_hash: Vec<u8> },
+    ItemTags,
+    FtExchangeRates,
+    AdminActions,
+    DaoControlledStores,
+    ItemNftRequirements,
+    StoreCreatedAt,
+    ItemSupplyCaps,
+    ItemCharitySplits,
+    ItemCharityDonations,
+    ItemRentalRates,
+    ActiveRentals,
+    ItemNameIndex,
+    ItemExtraImages,
+    ItemCostBasis,
+    ItemMinMarginBps,
+    ReviewReports,
+    StoreWebhookUrls,
+    ItemPriceHistory,
+    AcceptedPartnershipsByStore,
+    RentalsByItemAndRenter,
+    RemovedReviews,
 }
 
 #[near_bindgen]
@@ -65,6 +751,100 @@ pub struct Contract {
     pub metadata_by_storeanditem_ids: Option<UnorderedMap<StoreAndItemIds, ItemMetadata>>,
     pub audit_logs: UnorderedSet<Log>,
     pub approved_ft_token_ids: UnorderedSet<AccountId>,
+    pub buyer_purchases: LookupMap<AccountId, UnorderedSet<StoreAndItemIds>>,
+    pub store_minimum_deposits: LookupMap<StoreId, u128>,
+    pub items_per_store: LookupMap<StoreId, UnorderedSet<ItemId>>,
+    pub item_purchase_counts: LookupMap<StoreAndItemIds, u64>,
+    pub affiliate_codes: UnorderedMap<String, AffiliateCode>,
+    pub affiliate_earnings: LookupMap<AccountId, u128>,
+    pub log_nonce: u64,
+    pub scheduled_price_changes: UnorderedMap<String, ScheduledPriceChange>,
+    pub group_buys: UnorderedMap<String, GroupBuy>,
+    pub next_item_sequence: LookupMap<StoreId, u64>,
+    pub state_version: u8,
+    pub all_stores: UnorderedSet<StoreId>,
+    pub store_revenue: LookupMap<StoreId, u128>,
+    pub store_balances: LookupMap<StoreId, u128>,
+    pub protocol_fee_bps: u16,
+    pub treasury_balance: u128,
+    pub store_currency: LookupMap<StoreId, Option<AccountId>>,
+    pub item_currency: LookupMap<StoreAndItemIds, Option<AccountId>>,
+    pub giveaways: UnorderedMap<String, Giveaway>,
+    pub proposals: UnorderedMap<String, GovernanceProposal>,
+    pub store_ratings: UnorderedMap<String, StoreRating>,
+    pub item_ratings: UnorderedMap<String, ItemRating>,
+    pub store_royalty_bps: LookupMap<StoreId, u16>,
+    pub store_royalties_earned: LookupMap<StoreId, u128>,
+    pub item_royalties_earned: LookupMap<StoreAndItemIds, u128>,
+    pub stores_by_item: LookupMap<ItemId, UnorderedSet<StoreId>>,
+    pub all_items: UnorderedSet<StoreAndItemIds>,
+    pub item_purchase_limits: LookupMap<StoreAndItemIds, u32>,
+    pub buyer_item_purchase_counts: LookupMap<(AccountId, StoreAndItemIds), u32>,
+    pub store_geo_policies: LookupMap<StoreId, StoreGeoPolicy>,
+    pub log_chain_head: Option<[u8; 32]>,
+    pub item_ft_requirements: LookupMap<StoreAndItemIds, (AccountId, U128)>,
+    pub item_shipping: LookupMap<StoreAndItemIds, ShippingInfo>,
+    pub locked_items: UnorderedSet<StoreAndItemIds>,
+    pub subscription_plans: UnorderedMap<String, SubscriptionPlan>,
+    pub subscriptions: UnorderedMap<String, Subscription>,
+    pub platform_min_items_for_active_store: Option<u64>,
+    pub store_tax_rates: LookupMap<StoreId, u16>,
+    pub store_sale_periods: LookupMap<StoreId, StoreSalePeriod>,
+    pub store_roles: UnorderedMap<(StoreId, AccountId), StoreRole>,
+    pub item_history: UnorderedMap<StoreAndItemIds, Vec<(ItemMetadata, u64)>>,
+    pub caller_contracts: UnorderedSet<AccountId>,
+    pub store_metadata: LookupMap<StoreId, StoreMetadata>,
+    pub require_item_approval: bool,
+    pub items_pending_approval: UnorderedSet<StoreAndItemIds>,
+    pub stores_closed_for_listing: UnorderedSet<StoreId>,
+    pub buyer_store_spending: LookupMap<(AccountId, StoreId), u128>,
+    pub referral_programs: UnorderedMap<StoreId, ReferralProgram>,
+    pub referral_earnings: LookupMap<AccountId, u128>,
+    pub item_cooldowns: LookupMap<StoreAndItemIds, u64>,
+    pub buyer_last_purchase_time: LookupMap<(AccountId, StoreAndItemIds), u64>,
+    pub item_created_at: LookupMap<StoreAndItemIds, u64>,
+    pub store_terms: LookupMap<StoreId, String>,
+    pub terms_acceptances: LookupMap<(StoreId, AccountId), u64>,
+    pub age_restricted_items: UnorderedSet<StoreAndItemIds>,
+    pub age_attestations: LookupMap<AccountId, (u8, u64)>,
+    pub dynamic_pricing: LookupMap<StoreAndItemIds, DynamicPricingConfig>,
+    pub platform_announcements: Vec<PlatformAnnouncement>,
+    pub store_stakes: LookupMap<StoreId, UnorderedMap<AccountId, u128>>,
+    pub item_waitlists: LookupMap<StoreAndItemIds, Vec<AccountId>>,
+    pub item_certificates: UnorderedMap<StoreAndItemIds, Vec<ItemCertificate>>,
+    pub certified_issuers: UnorderedSet<AccountId>,
+    pub loyalty_tiers: Vec<LoyaltyTier>,
+    pub buyer_lifetime_spend: LookupMap<AccountId, u128>,
+    pub store_partnerships: UnorderedMap<String, Partnership>,
+    pub barcode_index: UnorderedMap<String, StoreAndItemIds>,
+    pub featured_banners: Vec<FeaturedBanner>,
+    pub banner_fee: u128,
+    pub item_video_views: LookupMap<StoreAndItemIds, u64>,
+    pub last_video_views: LookupMap<(AccountId, StoreAndItemIds), u64>,
+    pub item_crosschain_links: UnorderedMap<StoreAndItemIds, Vec<CrossChainLink>>,
+    pub approved_chains: UnorderedSet<String>,
+    pub tag_to_items: LookupMap<String, UnorderedSet<StoreAndItemIds>>,
+    pub item_tags: LookupMap<StoreAndItemIds, Vec<String>>,
+    pub ft_exchange_rates: LookupMap<AccountId, U128>,
+    pub overseer_multisig: Option<OverseerMultisig>,
+    pub dao_controlled_stores: LookupMap<StoreId, AccountId>,
+    pub item_nft_requirements: LookupMap<StoreAndItemIds, (AccountId, u64)>,
+    pub store_created_at: LookupMap<StoreId, u64>,
+    pub item_supply_caps: LookupMap<StoreAndItemIds, u64>,
+    pub item_charity_splits: LookupMap<StoreAndItemIds, (AccountId, u16)>,
+    pub item_charity_donations: LookupMap<StoreAndItemIds, u128>,
+    pub item_rental_rates: LookupMap<StoreAndItemIds, U128>,
+    pub active_rentals: UnorderedMap<String, ItemRental>,
+    pub item_name_index: UnorderedMap<(StoreId, String), ItemId>,
+    pub item_extra_images: LookupMap<StoreAndItemIds, Vec<String>>,
+    pub item_cost_basis: LookupMap<StoreAndItemIds, u128>,
+    pub item_min_margin_bps: LookupMap<StoreAndItemIds, u16>,
+    pub review_reports: UnorderedMap<String, Vec<(AccountId, String)>>,
+    pub store_webhook_urls: LookupMap<StoreId, String>,
+    pub item_price_history: UnorderedMap<StoreAndItemIds, Vec<(u128, u64)>>,
+    pub accepted_partnerships_by_store: LookupMap<StoreId, Vec<String>>,
+    pub rentals_by_item_and_renter: LookupMap<(StoreAndItemIds, AccountId), Vec<String>>,
+    pub removed_reviews: LookupMap<String, u64>,
 }
 
 #[near_bindgen]
@@ -72,23 +852,7 @@ impl Contract {
     /// Initialize contract state
     #[init]
     pub fn new(overseer_id: AccountId) -> Self {
-        let mut this = Self {
-            overseer_id,
-            stores_by_account_id: Some(LookupMap::new(
-                StorageKey::StoresByAccountId.into_storage_key(),
-            )),
-            owners_per_store_id: Some(LookupMap::new(
-                StorageKey::OwnersByStoreId.into_storage_key(),
-            )),
-            item_by_store_id: TreeMap::new(StorageKey::ItemByStoreId.into_storage_key()),
-            metadata_by_storeanditem_ids: Some(UnorderedMap::new(
-                StorageKey::TransactionsByStoreAndItemIds.into_storage_key(),
-            )),
-            audit_logs: UnorderedSet::new(StorageKey::AuditLogs.into_storage_key()),
-            approved_ft_token_ids: UnorderedSet::new(
-                StorageKey::FungibleTokenIds.into_storage_key(),
-            ),
-        };
+        let mut this = Self::internal_new(overseer_id);
 
         this.approved_ft_token_ids.insert(&test_account());
 
@@ -108,6 +872,10 @@ impl Contract {
 
             store_ids.insert(&store_id);
             stores_by_account_id.insert(&signer_id, &store_ids);
+            if self.all_stores.insert(&store_id) {
+                self.store_created_at
+                    .insert(&store_id, &env::block_timestamp());
+            }
 
             self.add_store_owners(store_id, signer_id)
         }
@@ -127,7 +895,7 @@ impl Contract {
                 if let Some(stores_by_account_id) = &self.stores_by_account_id {
                     let store_ids = stores_by_account_id.get(&signer_id).unwrap();
                     if !store_ids.contains(&store_id) {
-                        env::panic_str("StoreHub: signer not store owner")
+                        panic_with(StoreHubError::SignerNotStoreOwner)
                     }
                 };
             }
@@ -143,11 +911,7 @@ impl Contract {
 
         if let Some(stores_by_account_id) = &self.stores_by_account_id {
             match stores_by_account_id.get(&signer_id) {
-                Some(store_ids) => store_ids
-                    .iter()
-                    .skip(0usize)
-                    .take(store_ids.len() as usize)
-                    .collect(),
+                Some(store_ids) => store_ids.iter().collect(),
                 None => vec![],
             }
         } else {
@@ -155,15 +919,40 @@ impl Contract {
         }
     }
 
+    /// Retrieve the stores created by any account
+    pub fn get_stores_for_account(
+        &self,
+        account_id: AccountId,
+        from_index: Option<u64>,
+        limit: Option<u64>,
+    ) -> Vec<AccountId> {
+        match self
+            .stores_by_account_id
+            .as_ref()
+            .and_then(|by_id| by_id.get(&account_id))
+        {
+            Some(store_ids) => store_ids
+                .iter()
+                .skip(from_index.unwrap_or(0) as usize)
+                .take(limit.unwrap_or(store_ids.len()) as usize)
+                .collect(),
+            None => vec![],
+        }
+    }
+
+    /// Retrieve the number of stores created by an account
+    pub fn get_store_count_for_account(&self, account_id: AccountId) -> u64 {
+        self.stores_by_account_id
+            .as_ref()
+            .and_then(|by_id| by_id.get(&account_id))
+            .map_or(0, |store_ids| store_ids.len())
+    }
+
     /// Retrieve the owners of a store by its id
     pub fn get_owners_by_store_id(&self, store_id: AccountId) -> Vec<AccountId> {
         if let Some(owners_per_store_id) = &self.owners_per_store_id {
             match owners_per_store_id.get(&store_id) {
-                Some(owner_ids) => owner_ids
-                    .iter()
-                    .skip(0usize)
-                    .take(owner_ids.len() as usize)
-                    .collect(),
+                Some(owner_ids) => owner_ids.iter().collect(),
                 None => vec![],
             }
         } else {
@@ -172,6 +961,8 @@ impl Contract {
     }
 
     /// Retrieve an existing item's metadata under a store.
+    ///
+    /// Deprecated: use `get_item_metadata`, which takes the store id explicitly.
     pub fn get_item_by_store_id(&self, item_id: ItemId) -> Option<ItemMetadata> {
         let store_id = self.item_by_store_id.get(&item_id)?;
         let store_and_item_id = format!("{}{}{}", store_id, DELIMETER, item_id);
@@ -180,6 +971,13 @@ impl Contract {
             .get(&store_and_item_id)
     }
 
+    /// Retrieve an item's metadata given both its id and its store's id
+    pub fn get_item_metadata(&self, item_id: ItemId, store_id: AccountId) -> Option<ItemMetadata> {
+        self.metadata_by_storeanditem_ids
+            .as_ref()?
+            .get(&store_and_item_id(&store_id, &item_id))
+    }
+
     /// Add a new item and its metadata to an existing store
     pub fn add_store_item(
         &mut self,
@@ -188,382 +986,10316 @@ impl Contract {
         item_name: String,
         item_price: U128,
         item_img_url: String,
+        item_condition: Option<ItemCondition>,
+        item_upc: Option<String>,
+        item_video_url: Option<String>,
     ) {
-        let signer_id = env::predecessor_account_id();
-        match &self.owners_per_store_id {
-            Some(owners_per_store_id) => {
-                let owners_set = owners_per_store_id.get(&store_id).unwrap();
-                if !owners_set.contains(&signer_id) {
-                    env::panic_str("StoreHub: access denied")
-                }
-            }
-            None => env::panic_str("StoreHub: internal contract error"),
-        }
+        self.assert_store_manager(&store_id);
+        self.add_store_item_internal(
+            item_id,
+            store_id,
+            item_name,
+            item_price,
+            item_img_url,
+            item_condition,
+            item_upc,
+            item_video_url,
+        );
+    }
 
-        self.item_by_store_id.insert(&item_id, &store_id);
+    /// Add a new item to an existing store under a generated id,
+    /// returning the id so the caller can reference the item
+    pub fn add_store_item_auto(
+        &mut self,
+        store_id: AccountId,
+        item_name: String,
+        item_price: U128,
+        item_img_url: String,
+    ) -> ItemId {
+        self.assert_store_manager(&store_id);
 
-        let item_metadata = ItemMetadata {
-            name: item_name,
-            price: item_price,
-            img_url: item_img_url,
-            owner: store_id.clone(),
+        // skip sequence numbers already taken by explicitly chosen ids
+        let mut sequence = self.next_item_sequence.get(&store_id).unwrap_or(0);
+        let item_id = loop {
+            sequence += 1;
+            let item_id = format!("{}-{}", store_id, sequence);
+            if self.item_by_store_id.get(&item_id).is_none() {
+                break item_id;
+            }
         };
+        self.next_item_sequence.insert(&store_id, &sequence);
 
-        let storeanditem_id = format!("{}{}{}", store_id, DELIMETER, item_id);
+        self.add_store_item_internal(
+            item_id.clone(),
+            store_id,
+            item_name,
+            item_price,
+            item_img_url,
+            None,
+            None,
+            None,
+        );
 
-        self.metadata_by_storeanditem_ids
-            .as_mut()
-            .and_then(|by_id| by_id.insert(&storeanditem_id, &item_metadata));
+        item_id
     }
 
     /// Transfers assest across buyer and the store_id,
     /// creating a transaction log in the process
     #[payable]
-    pub fn buy(&mut self, item_id: String, store_id: AccountId) -> String {
-        // check both item and store exist, and be right places
-        match self.item_by_store_id.get(&item_id) {
-            Some(returned_store_id) => {
-                if returned_store_id.ne(&store_id) {
-                    env::panic_str("StoreHub: this item doesn't exist for this store")
-                }
-            }
-            None => {
-                env::panic_str("StoreHub: item doesn't exist");
-            }
-        }
+    pub fn buy(&mut self, item_id: String, store_id: AccountId) -> BuyReceipt {
+        let signer_id = env::signer_account_id();
+        self.internal_buy(item_id, store_id, signer_id, None, None, None)
+    }
+
+    /// Buys an item on behalf of `recipient` (e.g. as a gift).
+    /// The signer pays and the recipient becomes the item's owner.
+    #[payable]
+    pub fn proxy_buy(
+        &mut self,
+        item_id: ItemId,
+        store_id: AccountId,
+        recipient: AccountId,
+    ) -> BuyReceipt {
+        self.internal_buy(item_id, store_id, recipient, None, None, None)
+    }
 
-        let storeanditem_id = format!("{}{}{}", store_id, DELIMETER, item_id);
+    /// Buys an item from a geo-restricted store. The buyer's region is self-attested,
+    /// so the restriction relies on the front-end supplying an honest region
+    #[payable]
+    pub fn buy_with_region(
+        &mut self,
+        item_id: ItemId,
+        store_id: AccountId,
+        buyer_region: String,
+    ) -> BuyReceipt {
         let signer_id = env::signer_account_id();
-        let deposit = env::attached_deposit();
+        self.internal_buy(item_id, store_id, signer_id, None, None, Some(buyer_region))
+    }
 
-        // check deposit, ownership and update contract's state
-        self.metadata_by_storeanditem_ids.as_mut().and_then({
-            |by_id| {
-                if let Some(metadata) = &mut by_id.get(&storeanditem_id) {
-                    require!(
-                        deposit >= metadata.price.0,
-                        "StoreHub: deposit is below price"
-                    );
-                    require!(
-                        signer_id.ne(&metadata.owner),
-                        "StoreHub: can't buy owned item"
-                    );
+    /// Buys a token-gated item, checking the signer's balance of the required
+    /// token before the purchase goes through. `buyer_region` is only needed
+    /// for geo-restricted stores
+    #[payable]
+    pub fn buy_with_ft_gate(
+        &mut self,
+        item_id: ItemId,
+        store_id: AccountId,
+        buyer_region: Option<String>,
+    ) -> Promise {
+        let (ft_contract, _) = self
+            .item_ft_requirements
+            .get(&store_and_item_id(&store_id, &item_id))
+            .unwrap_or_else(|| panic_with(StoreHubError::ItemNotTokenGated));
 
-                    Promise::new(metadata.owner.clone()).transfer(deposit);
-                    metadata.owner = signer_id.clone();
+        // fail the purchase here rather than in the callback, where it would have to be refunded
+        let signer_id = env::signer_account_id();
+        self.internal_check_buy(
+            &item_id,
+            &store_id,
+            &signer_id,
+            None,
+            None,
+            buyer_region.clone(),
+            Some(PurchaseGate::Ft),
+        )
+        .unwrap_or_else(|err| env::panic_str(&err));
 
-                    by_id.insert(&storeanditem_id, metadata);
+        ext_ft_core::ext(ft_contract)
+            .with_static_gas(GAS_FOR_FT_BALANCE_OF)
+            .ft_balance_of(signer_id)
+            .then(
+                Self::ext(env::current_account_id())
+                    .with_attached_deposit(env::attached_deposit())
+                    .with_static_gas(GAS_FOR_FT_GATE_CALLBACK)
+                    .on_ft_gate_balance(item_id, store_id, buyer_region),
+            )
+    }
 
-                    Some(())
-                } else {
-                    None
-                }
+    /// Complete a token-gated purchase once the buyer's balance is known.
+    /// A panic here would leave the deposit with the contract, so an
+    /// insufficient balance or a purchase that can no longer go through
+    /// refunds the buyer and returns `None` instead
+    #[private]
+    #[payable]
+    pub fn on_ft_gate_balance(
+        &mut self,
+        item_id: ItemId,
+        store_id: AccountId,
+        buyer_region: Option<String>,
+        #[callback_result] balance: Result<U128, PromiseError>,
+    ) -> Option<BuyReceipt> {
+        // a requirement lifted since the purchase started no longer gates it
+        let min_balance = self
+            .item_ft_requirements
+            .get(&store_and_item_id(&store_id, &item_id))
+            .map_or(0, |(_, min_balance)| min_balance.0);
+
+        let signer_id = env::signer_account_id();
+        let checked = if balance.map_or(true, |balance| balance.0 < min_balance) {
+            Err("StoreHub: insufficient token balance".to_string())
+        } else {
+            self.internal_check_buy(
+                &item_id,
+                &store_id,
+                &signer_id,
+                None,
+                None,
+                buyer_region,
+                Some(PurchaseGate::Ft),
+            )
+        };
+
+        match checked {
+            Ok(quote) => {
+                Some(self.internal_complete_buy(item_id, store_id, signer_id, None, None, quote))
             }
-        });
+            Err(err) => {
+                env::log_str(&err);
+                Promise::new(signer_id).transfer(env::attached_deposit());
+                None
+            }
+        }
+    }
 
-        // add new buy transaction log to state
-        let extra = json!({
-            "paid": deposit,
-            "previous_owner": store_id,
-        });
-        let tx_id = self.add_log(
-            "buy".to_string(),
-            signer_id.to_string(),
-            storeanditem_id,
-            extra.to_string(),
-        );
+    /// Buys an NFT-gated item, checking how many tokens of the required
+    /// collection the signer holds before the purchase goes through.
+    /// `buyer_region` is only needed for geo-restricted stores
+    #[payable]
+    pub fn buy_nft_gated(
+        &mut self,
+        item_id: ItemId,
+        store_id: AccountId,
+        buyer_region: Option<String>,
+    ) -> Promise {
+        let (nft_contract, min_token_count) = self
+            .item_nft_requirements
+            .get(&store_and_item_id(&store_id, &item_id))
+            .unwrap_or_else(|| panic_with(StoreHubError::ItemNotNftGated));
 
-        json!({
-            "message": "your purchase is ready",
-            "transaction_id": tx_id,
-        })
-        .to_string()
+        // fail the purchase here rather than in the callback, where it would have to be refunded
+        let signer_id = env::signer_account_id();
+        self.internal_check_buy(
+            &item_id,
+            &store_id,
+            &signer_id,
+            None,
+            None,
+            buyer_region.clone(),
+            Some(PurchaseGate::Nft),
+        )
+        .unwrap_or_else(|err| env::panic_str(&err));
+
+        ext_nft_enumeration::ext(nft_contract)
+            .with_static_gas(GAS_FOR_NFT_TOKENS_FOR_OWNER)
+            .nft_tokens_for_owner(signer_id, None, Some(min_token_count))
+            .then(
+                Self::ext(env::current_account_id())
+                    .with_attached_deposit(env::attached_deposit())
+                    .with_static_gas(GAS_FOR_NFT_GATE_CALLBACK)
+                    .on_nft_check_complete(item_id, store_id, buyer_region),
+            )
     }
 
-    /// Add a new audit log to the contract's state
-    pub fn add_log(
+    /// Complete an NFT-gated purchase once the buyer's tokens are known.
+    /// Like `on_ft_gate_balance`, an unmet requirement or a purchase that
+    /// can no longer go through refunds the buyer and returns `None`
+    #[private]
+    #[payable]
+    pub fn on_nft_check_complete(
         &mut self,
-        action: String,
-        actor: String,
-        entity: String,
-        extra: String,
-    ) -> String {
-        let log_id = format!("{}{}{}", entity, DELIMETER, env::block_timestamp());
-        let log = Log {
-            id: log_id.clone(),
-            timestamp: env::block_timestamp(),
-            action,
-            actor,
-            entity,
-            extra,
+        item_id: ItemId,
+        store_id: AccountId,
+        buyer_region: Option<String>,
+        #[callback_result] tokens: Result<Vec<Token>, PromiseError>,
+    ) -> Option<BuyReceipt> {
+        // a requirement lifted since the purchase started no longer gates it
+        let min_token_count = self
+            .item_nft_requirements
+            .get(&store_and_item_id(&store_id, &item_id))
+            .map_or(0, |(_, min_token_count)| min_token_count);
+
+        let signer_id = env::signer_account_id();
+        let checked = if tokens.map_or(true, |tokens| (tokens.len() as u64) < min_token_count) {
+            Err("StoreHub: NFT requirement not met".to_string())
+        } else {
+            self.internal_check_buy(
+                &item_id,
+                &store_id,
+                &signer_id,
+                None,
+                None,
+                buyer_region,
+                Some(PurchaseGate::Nft),
+            )
         };
 
-        self.audit_logs.insert(&log);
+        match checked {
+            Ok(quote) => {
+                Some(self.internal_complete_buy(item_id, store_id, signer_id, None, None, quote))
+            }
+            Err(err) => {
+                env::log_str(&err);
+                Promise::new(signer_id).transfer(env::attached_deposit());
+                None
+            }
+        }
+    }
 
-        log_id
+    /// Buys an item on behalf of `buyer` from an approved contract, forwarding
+    /// the attached deposit. The receipt is returned through a callback
+    #[payable]
+    pub fn cross_contract_buy(
+        &mut self,
+        item_id: ItemId,
+        store_id: AccountId,
+        buyer: AccountId,
+        ft_contract: Option<AccountId>,
+    ) -> Promise {
+        require!(
+            self.caller_contracts
+                .contains(&env::predecessor_account_id()),
+            "StoreHub: caller contract not approved"
+        );
+        require!(
+            ft_contract.is_none(),
+            "StoreHub: fungible token purchases are not supported"
+        );
+
+        let receipt = self.internal_buy(item_id, store_id, buyer, None, None, None);
+
+        Self::ext(env::current_account_id())
+            .with_static_gas(GAS_FOR_CROSS_CONTRACT_BUY_CALLBACK)
+            .on_cross_contract_buy(receipt)
     }
 
-    /// Add a new support payment means
-    pub fn add_ft(&mut self, ft_account_id: AccountId) {
+    /// Hand a cross-contract purchase's receipt back to the calling contract
+    #[private]
+    pub fn on_cross_contract_buy(&self, receipt: BuyReceipt) -> BuyReceipt {
+        receipt
+    }
+
+    /// Buys an item through an affiliate code, paying the affiliate
+    /// a commission out of the seller's proceeds
+    #[payable]
+    pub fn buy_with_affiliate(
+        &mut self,
+        item_id: ItemId,
+        store_id: AccountId,
+        affiliate_code: String,
+    ) -> BuyReceipt {
+        let code = self
+            .affiliate_codes
+            .get(&affiliate_code)
+            .unwrap_or_else(|| panic_with(StoreHubError::AffiliateCodeNotFound));
+        require!(code.active, "StoreHub: affiliate code is inactive");
         require!(
-            env::signer_account_id().eq(&self.overseer_id),
-            "StoreHub: access denied"
+            code.store_id.eq(&store_id),
+            "StoreHub: affiliate code doesn't exist for this store"
+        );
+
+        let signer_id = env::signer_account_id();
+        self.internal_buy(item_id, store_id, signer_id, Some(code), None, None)
+    }
+
+    /// Buys an item with the discount of the store's referral program,
+    /// rewarding `referrer` out of the sale
+    #[payable]
+    pub fn buy_with_referral(
+        &mut self,
+        item_id: ItemId,
+        store_id: AccountId,
+        referrer: AccountId,
+    ) -> BuyReceipt {
+        let signer_id = env::signer_account_id();
+        require!(referrer.ne(&signer_id), "StoreHub: can't refer yourself");
+        self.internal_buy(item_id, store_id, signer_id, None, Some(referrer), None)
+    }
+
+    /// Create an affiliate code for a store
+    pub fn create_affiliate_code(
+        &mut self,
+        code: String,
+        store_id: AccountId,
+        affiliate: AccountId,
+        commission_bps: u16,
+    ) {
+        self.assert_store_owner(&store_id);
+        require!(
+            commission_bps <= 10_000,
+            "StoreHub: commission can't exceed 10000 bps"
+        );
+        require!(
+            self.affiliate_codes.get(&code).is_none(),
+            "StoreHub: affiliate code already exists"
         );
+
+        let affiliate_code = AffiliateCode {
+            code: code.clone(),
+            affiliate,
+            store_id,
+            commission_bps,
+            active: true,
+        };
+        self.affiliate_codes.insert(&code, &affiliate_code);
+    }
+
+    /// Deactivate an existing affiliate code of a store
+    pub fn deactivate_affiliate_code(&mut self, code: String) {
+        let mut affiliate_code = self
+            .affiliate_codes
+            .get(&code)
+            .unwrap_or_else(|| panic_with(StoreHubError::AffiliateCodeNotFound));
+        self.assert_store_owner(&affiliate_code.store_id);
+
+        affiliate_code.active = false;
+        self.affiliate_codes.insert(&code, &affiliate_code);
+    }
+
+    /// Retrieve the total commission earned by an affiliate
+    pub fn get_affiliate_earnings(&self, account_id: AccountId) -> U128 {
+        U128(self.affiliate_earnings.get(&account_id).unwrap_or(0))
+    }
+
+    /// Add an administrative annotation to the audit logs, recorded with the overseer as actor
+    pub fn admin_add_log(&mut self, action: String, entity: String, extra: String) -> String {
+        self.assert_overseer();
+        self.add_log_internal(action, env::signer_account_id().to_string(), entity, extra)
+    }
+
+    /// Add a new support payment means
+    pub fn add_ft(&mut self, ft_account_id: AccountId) {
+        self.assert_overseer();
+        self.assert_no_overseer_multisig();
         self.approved_ft_token_ids.insert(&ft_account_id);
     }
 
+    /// Emergency correction of an item's price by the overseer,
+    /// bypassing the store owners
+    pub fn overseer_override_item_price(
+        &mut self,
+        item_id: ItemId,
+        store_id: AccountId,
+        new_price: U128,
+    ) {
+        self.assert_overseer();
+
+        let storeanditem_id = store_and_item_id(&store_id, &item_id);
+        let mut metadata = self.internal_get_item_metadata(&storeanditem_id);
+        let old_price = metadata.price;
+        metadata.price = new_price;
+        self.internal_record_price_change(&storeanditem_id, old_price);
+        self.internal_set_item_metadata(&storeanditem_id, &metadata);
+
+        let extra = json!({
+            "old_price": old_price,
+            "new_price": new_price,
+        });
+        self.add_log_internal(
+            "overseer_price_override".to_string(),
+            env::signer_account_id().to_string(),
+            storeanditem_id.clone(),
+            extra.to_string(),
+        );
+
+        emit_event(
+            "overseer_price_override",
+            json!({
+                "item_id": item_id,
+                "store_id": store_id,
+                "old_price": old_price,
+                "new_price": new_price,
+            }),
+        );
+    }
+
     /// Retrieve a log by id
     pub fn get_log(&self, log_id: String) -> Log {
         match self.audit_logs.iter().find(|log| log.id == log_id) {
             Some(log) => log,
-            None => {
-                let msg = format!("No log found with id {}", log_id);
-                env::panic_str(&msg)
+            None => panic_with(StoreHubError::LogNotFound(log_id)),
+        }
+    }
+
+    /// Retrieve the items an account has paid for
+    pub fn get_buyer_purchases(
+        &self,
+        account_id: AccountId,
+        from_index: Option<u64>,
+        limit: Option<u64>,
+    ) -> Vec<StoreAndItemIds> {
+        match self.buyer_purchases.get(&account_id) {
+            Some(purchases) => purchases
+                .iter()
+                .skip(from_index.unwrap_or(0) as usize)
+                .take(limit.unwrap_or(purchases.len()) as usize)
+                .collect(),
+            None => vec![],
+        }
+    }
+
+    /// Set the minimum deposit a store accepts for any purchase,
+    /// independently of the item's price
+    pub fn set_store_minimum_deposit(&mut self, store_id: AccountId, min_deposit: U128) {
+        self.assert_store_owner(&store_id);
+        self.store_minimum_deposits
+            .insert(&store_id, &min_deposit.0);
+    }
+
+    /// Retrieve the minimum deposit a store accepts
+    pub fn get_store_minimum_deposit(&self, store_id: AccountId) -> U128 {
+        U128(self.store_minimum_deposits.get(&store_id).unwrap_or(0))
+    }
+
+    /// Retrieve the top `limit` best selling items of a store,
+    /// as `(item_id, purchase_count)` sorted by purchase count descending
+    pub fn get_store_items_by_sales(&self, store_id: AccountId, limit: u64) -> Vec<(ItemId, u64)> {
+        let mut items = self.internal_store_items_with_sales(&store_id);
+        let top_n = items.len().min(limit as usize);
+
+        // selection sort of the first `top_n` positions only
+        for i in 0..top_n {
+            let mut max_index = i;
+            for j in (i + 1)..items.len() {
+                if items[j].1 > items[max_index].1 {
+                    max_index = j;
+                }
             }
+            items.swap(i, max_index);
         }
+        items.truncate(top_n);
+
+        items
     }
 
-    /// Check if payment method exists
-    pub fn is_ft_approved(&self, ft_account_id: AccountId) -> bool {
-        self.approved_ft_token_ids.contains(&ft_account_id)
+    /// Retrieve the 1-indexed rank of an item among its store's items by purchase count
+    pub fn get_item_sales_rank(&self, item_id: ItemId, store_id: AccountId) -> u64 {
+        let items = self.internal_store_items_with_sales(&store_id);
+        let purchase_count = match items.iter().find(|(id, _)| id.eq(&item_id)) {
+            Some((_, count)) => *count,
+            None => panic_with(StoreHubError::ItemNotInStore),
+        };
+
+        1 + items
+            .iter()
+            .filter(|(_, count)| *count > purchase_count)
+            .count() as u64
     }
-}
 
-pub fn test_account() -> AccountId {
-    AccountId::new_unchecked("testnet".to_string())
-}
+    /// Retrieve the logs of a store's entities recorded for a given action
+    pub fn get_logs_by_store_and_action(
+        &self,
+        store_id: AccountId,
+        action: String,
+        from_index: Option<u64>,
+        limit: Option<u64>,
+    ) -> Vec<Log> {
+        self.audit_logs
+            .iter()
+            .filter(|log| log.action == action && is_store_entity(&log.entity, &store_id))
+            .skip(from_index.unwrap_or(0) as usize)
+            .take(limit.unwrap_or(self.audit_logs.len()) as usize)
+            .collect()
+    }
 
-#[cfg(all(test, not(target_arch = "wasm32")))]
-mod tests {
-    use super::*;
-    use near_sdk::json_types::U128;
-    use near_sdk::test_utils::{accounts, VMContextBuilder};
-    use near_sdk::testing_env;
+    /// Retrieve the number of logs of a store's entities recorded for a given action
+    pub fn get_logs_count_by_store_and_action(&self, store_id: AccountId, action: String) -> u64 {
+        self.audit_logs
+            .iter()
+            .filter(|log| log.action == action && is_store_entity(&log.entity, &store_id))
+            .count() as u64
+    }
 
-    #[test]
-    fn test_init_contract() {
-        let context = VMContextBuilder::new();
-        testing_env!(context.build());
+    /// Schedule an item price change for a future timestamp,
+    /// returning the schedule id
+    pub fn schedule_price_change(
+        &mut self,
+        item_id: ItemId,
+        store_id: AccountId,
+        new_price: U128,
+        execute_at: u64,
+    ) -> String {
+        self.assert_store_owner(&store_id);
+        require!(
+            execute_at > env::block_timestamp(),
+            "StoreHub: execution time must be in the future"
+        );
 
-        let contract = Contract::new(accounts(0));
+        let storeanditem_id = store_and_item_id(&store_id, &item_id);
+        self.internal_get_item_metadata(&storeanditem_id);
+        require!(
+            new_price.0 >= self.internal_price_floor(&storeanditem_id),
+            "StoreHub: price below cost floor"
+        );
+
+        let schedule_id = format!("{}{}{}", storeanditem_id, DELIMETER, execute_at);
+        require!(
+            self.scheduled_price_changes.get(&schedule_id).is_none(),
+            "StoreHub: price change already scheduled"
+        );
+
+        let scheduled_change = ScheduledPriceChange {
+            item_id,
+            store_id,
+            new_price,
+            execute_at,
+        };
+        self.scheduled_price_changes
+            .insert(&schedule_id, &scheduled_change);
+
+        schedule_id
+    }
+
+    /// Apply a scheduled price change once its execution time is reached.
+    /// Callable by anyone.
+    pub fn execute_scheduled_price_change(&mut self, schedule_id: String) {
+        let scheduled_change = self
+            .scheduled_price_changes
+            .get(&schedule_id)
+            .unwrap_or_else(|| panic_with(StoreHubError::ScheduledPriceChangeNotFound));
+        require!(
+            env::block_timestamp() >= scheduled_change.execute_at,
+            "StoreHub: scheduled price change not yet due"
+        );
+
+        let storeanditem_id =
+            store_and_item_id(&scheduled_change.store_id, &scheduled_change.item_id);
+        let mut metadata = self.internal_get_item_metadata(&storeanditem_id);
+        require!(
+            scheduled_change.new_price.0 >= self.internal_price_floor(&storeanditem_id),
+            "StoreHub: price below cost floor"
+        );
+        let old_price = metadata.price;
+        metadata.price = scheduled_change.new_price;
+        self.internal_record_price_change(&storeanditem_id, old_price);
+        self.internal_set_item_metadata(&storeanditem_id, &metadata);
+        self.scheduled_price_changes.remove(&schedule_id);
+
+        let extra = json!({
+            "schedule_id": schedule_id,
+            "old_price": old_price,
+            "new_price": scheduled_change.new_price,
+        });
+        self.add_log_internal(
+            "scheduled_price_change".to_string(),
+            env::predecessor_account_id().to_string(),
+            storeanditem_id,
+            extra.to_string(),
+        );
+    }
+
+    /// Cancel a pending scheduled price change
+    pub fn cancel_scheduled_price_change(&mut self, schedule_id: String) {
+        let scheduled_change = self
+            .scheduled_price_changes
+            .get(&schedule_id)
+            .unwrap_or_else(|| panic_with(StoreHubError::ScheduledPriceChangeNotFound));
+        self.assert_store_owner(&scheduled_change.store_id);
+
+        self.scheduled_price_changes.remove(&schedule_id);
+    }
+
+    /// Retrieve the pending price changes of a store's items as `(schedule_id, change)`
+    pub fn get_pending_price_changes_by_store(
+        &self,
+        store_id: AccountId,
+    ) -> Vec<(String, ScheduledPriceChange)> {
+        self.scheduled_price_changes
+            .iter()
+            .filter(|(_, scheduled_change)| scheduled_change.store_id == store_id)
+            .collect()
+    }
+
+    /// Open a group buy on a store item, returning the group buy id
+    pub fn create_group_buy(
+        &mut self,
+        item_id: ItemId,
+        store_id: AccountId,
+        target_count: u32,
+        discount_price: U128,
+        deadline: u64,
+    ) -> String {
+        self.assert_store_owner(&store_id);
+        require!(target_count > 0, "StoreHub: target count must be positive");
+        require!(
+            deadline > env::block_timestamp(),
+            "StoreHub: deadline must be in the future"
+        );
+
+        let storeanditem_id = store_and_item_id(&store_id, &item_id);
+        self.internal_get_item_metadata(&storeanditem_id);
+        require!(
+            target_count as u64
+                <= self.get_item_remaining_supply(item_id.clone(), store_id.clone()),
+            "StoreHub: target exceeds remaining supply"
+        );
+
+        let group_buy_id = format!("{}{}{}", storeanditem_id, DELIMETER, env::block_timestamp());
+        require!(
+            self.group_buys.get(&group_buy_id).is_none(),
+            "StoreHub: group buy already exists"
+        );
+
+        let group_buy = GroupBuy {
+            item_id,
+            store_id,
+            target_count,
+            discount_price,
+            deadline,
+            participants: vec![],
+            deposits: vec![],
+            executed: false,
+        };
+        self.group_buys.insert(&group_buy_id, &group_buy);
+
+        group_buy_id
+    }
+
+    /// Join a group buy by depositing its discount price
+    #[payable]
+    pub fn join_group_buy(&mut self, group_buy_id: String) {
+        let mut group_buy = self.internal_get_group_buy(&group_buy_id);
+        let signer_id = env::signer_account_id();
+        let deposit = env::attached_deposit();
+
+        require!(!group_buy.executed, "StoreHub: group buy already executed");
+        require!(
+            env::block_timestamp() < group_buy.deadline,
+            "StoreHub: group buy deadline passed"
+        );
+        require!(
+            deposit >= group_buy.discount_price.0,
+            "StoreHub: deposit is below price"
+        );
+        require!(
+            !group_buy.participants.contains(&signer_id),
+            "StoreHub: already joined group buy"
+        );
+        require!(
+            (group_buy.participants.len() as u64)
+                < self.get_item_remaining_supply(
+                    group_buy.item_id.clone(),
+                    group_buy.store_id.clone()
+                ),
+            "StoreHub: item supply exhausted"
+        );
+
+        // only the discount price is pooled, any overpayment goes straight back
+        let price = group_buy.discount_price.0;
+        if deposit > price {
+            Promise::new(signer_id.clone()).transfer(deposit - price);
+        }
+
+        group_buy.participants.push(signer_id);
+        group_buy.deposits.push(price);
+        self.group_buys.insert(&group_buy_id, &group_buy);
+    }
+
+    /// Settle a group buy that reached its target, crediting the pooled
+    /// deposits to the store's balance. If the store no longer owns the item,
+    /// or its remaining supply can't cover every participant, each one is
+    /// refunded instead. Callable by anyone.
+    pub fn execute_group_buy(&mut self, group_buy_id: String) {
+        let mut group_buy = self.internal_get_group_buy(&group_buy_id);
+        require!(!group_buy.executed, "StoreHub: group buy already executed");
+        require!(
+            group_buy.participants.len() >= group_buy.target_count as usize,
+            "StoreHub: group buy target not reached"
+        );
+
+        // the item may have been sold, removed or run out since the group buy opened
+        let remaining_supply =
+            self.get_item_remaining_supply(group_buy.item_id.clone(), group_buy.store_id.clone());
+        if !self.internal_store_owns_item(&group_buy.store_id, &group_buy.item_id)
+            || group_buy.participants.len() as u64 > remaining_supply
+        {
+            self.internal_refund_group_buy(&group_buy_id, &group_buy);
+            return;
+        }
+
+        let total: u128 = group_buy.deposits.iter().sum();
+        self.internal_credit_store_balance(&group_buy.store_id, total);
+        self.internal_add_store_revenue(&group_buy.store_id, total);
+
+        let storeanditem_id = store_and_item_id(&group_buy.store_id, &group_buy.item_id);
+        for (participant, deposit) in group_buy.participants.iter().zip(&group_buy.deposits) {
+            self.internal_record_purchase(participant, &storeanditem_id);
+            self.internal_add_buyer_store_spending(participant, &group_buy.store_id, *deposit);
+        }
+
+        group_buy.executed = true;
+        self.group_buys.insert(&group_buy_id, &group_buy);
+
+        let extra = json!({
+            "group_buy_id": group_buy_id,
+            "participants": group_buy.participants,
+            "paid": U128(total),
+        });
+        self.add_log_internal(
+            "group_buy".to_string(),
+            env::predecessor_account_id().to_string(),
+            storeanditem_id,
+            extra.to_string(),
+        );
+    }
+
+    /// Cancel a group buy whose deadline passed without reaching its target,
+    /// refunding every participant. Callable by anyone.
+    pub fn cancel_group_buy(&mut self, group_buy_id: String) {
+        let group_buy = self.internal_get_group_buy(&group_buy_id);
+        require!(!group_buy.executed, "StoreHub: group buy already executed");
+        require!(
+            env::block_timestamp() >= group_buy.deadline,
+            "StoreHub: group buy deadline not reached"
+        );
+        require!(
+            group_buy.participants.len() < group_buy.target_count as usize,
+            "StoreHub: group buy target reached"
+        );
+
+        self.internal_refund_group_buy(&group_buy_id, &group_buy);
+    }
+
+    /// Retrieve a group buy by id
+    pub fn get_group_buy(&self, group_buy_id: String) -> Option<GroupBuy> {
+        self.group_buys.get(&group_buy_id)
+    }
+
+    /// Reprice many items of a store at once, returning whether each update succeeded.
+    /// When `abort_on_error` is set, the first failing item aborts the whole batch.
+    pub fn update_item_price_batch(
+        &mut self,
+        store_id: AccountId,
+        updates: Vec<(ItemId, U128)>,
+        abort_on_error: bool,
+    ) -> Vec<(ItemId, bool)> {
+        self.assert_store_owner(&store_id);
+        require!(
+            updates.len() <= MAX_PRICE_BATCH_SIZE,
+            "StoreHub: too many items in batch"
+        );
+
+        let actor = env::predecessor_account_id().to_string();
+        let mut results = Vec::with_capacity(updates.len());
+        for (item_id, new_price) in updates {
+            let storeanditem_id = store_and_item_id(&store_id, &item_id);
+            let metadata = self
+                .metadata_by_storeanditem_ids
+                .as_ref()
+                .and_then(|by_id| by_id.get(&storeanditem_id));
+
+            let mut metadata = match metadata {
+                Some(metadata) => metadata,
+                None if abort_on_error => panic_with(StoreHubError::ItemNotInStore),
+                None => {
+                    results.push((item_id, false));
+                    continue;
+                }
+            };
+
+            if new_price.0 < self.internal_price_floor(&storeanditem_id) {
+                require!(!abort_on_error, "StoreHub: price below cost floor");
+                results.push((item_id, false));
+                continue;
+            }
+
+            let old_price = metadata.price;
+            metadata.price = new_price;
+            self.internal_record_price_change(&storeanditem_id, old_price);
+            self.internal_set_item_metadata(&storeanditem_id, &metadata);
+
+            let extra = json!({
+                "old_price": old_price,
+                "new_price": new_price,
+            });
+            self.add_log_internal(
+                "price_update".to_string(),
+                actor.clone(),
+                storeanditem_id,
+                extra.to_string(),
+            );
+
+            results.push((item_id, true));
+        }
+
+        results
+    }
+
+    /// Retrieve the total amount paid for a store's items
+    pub fn get_store_total_revenue(&self, store_id: AccountId) -> U128 {
+        U128(self.store_revenue.get(&store_id).unwrap_or(0))
+    }
+
+    /// Retrieve the stores with the highest revenue, capped at 50 entries.
+    /// This scans every store; an off-chain indexer is recommended once there are thousands.
+    pub fn get_top_stores_by_revenue(&self, limit: u64) -> Vec<(AccountId, U128)> {
+        let mut stores: Vec<(AccountId, U128)> = self
+            .all_stores
+            .iter()
+            .map(|store_id| {
+                let revenue = self.get_store_total_revenue(store_id.clone());
+                (store_id, revenue)
+            })
+            .collect();
+        stores.sort_by(|a, b| b.1 .0.cmp(&a.1 .0));
+        stores.truncate(limit.min(MAX_LEADERBOARD_SIZE) as usize);
+
+        stores
+    }
+
+    /// Retrieve the stores listing the most items, capped at 50 entries.
+    /// This scans every store; an off-chain indexer is recommended once there are thousands.
+    pub fn get_top_stores_by_item_count(&self, limit: u64) -> Vec<(AccountId, u64)> {
+        let mut stores: Vec<(AccountId, u64)> = self
+            .all_stores
+            .iter()
+            .map(|store_id| {
+                let item_count = self
+                    .items_per_store
+                    .get(&store_id)
+                    .map_or(0, |item_ids| item_ids.len());
+                (store_id, item_count)
+            })
+            .collect();
+        stores.sort_by(|a, b| b.1.cmp(&a.1));
+        stores.truncate(limit.min(MAX_LEADERBOARD_SIZE) as usize);
+
+        stores
+    }
+
+    /// Retrieve the funds held by the contract on behalf of a store
+    pub fn get_store_balance(&self, store_id: AccountId) -> U128 {
+        U128(self.store_balances.get(&store_id).unwrap_or(0))
+    }
+
+    /// Withdraw funds held on behalf of a store to the calling owner
+    pub fn withdraw_store_balance(&mut self, store_id: AccountId, amount: U128) {
+        self.assert_store_owner(&store_id);
+
+        let balance = self.store_balances.get(&store_id).unwrap_or(0);
+        require!(amount.0 <= balance, "StoreHub: insufficient store balance");
+        self.store_balances.insert(&store_id, &(balance - amount.0));
+
+        Promise::new(env::predecessor_account_id()).transfer(amount.0);
+    }
+
+    /// Set the protocol fee kept by the treasury on every sale, in basis points
+    pub fn set_protocol_fee(&mut self, fee_bps: u16) {
+        self.assert_overseer();
+        self.assert_no_overseer_multisig();
+        self.internal_set_protocol_fee(fee_bps);
+    }
+
+    /// Retrieve the protocol fee, in basis points
+    pub fn get_protocol_fee(&self) -> u16 {
+        self.protocol_fee_bps
+    }
+
+    /// Retrieve the protocol fees accumulated by the treasury
+    pub fn get_treasury_balance(&self) -> U128 {
+        U128(self.treasury_balance)
+    }
+
+    /// Withdraw accumulated protocol fees to the overseer
+    pub fn withdraw_treasury(&mut self, amount: U128) {
+        self.assert_overseer();
+        self.assert_no_overseer_multisig();
+        self.internal_withdraw_treasury(amount.0);
+    }
+
+    /// Set the currency a store prices its items in, `None` being NEAR.
+    /// Only allowed before the store lists any item, so all its items share one currency
+    pub fn set_store_currency(&mut self, store_id: AccountId, ft_account_id: Option<AccountId>) {
+        self.assert_store_owner(&store_id);
+        if let Some(ft_account_id) = &ft_account_id {
+            require!(
+                self.approved_ft_token_ids.contains(ft_account_id),
+                "StoreHub: fungible token not approved"
+            );
+        }
+        require!(
+            self.items_per_store
+                .get(&store_id)
+                .map_or(true, |item_ids| item_ids.is_empty()),
+            "StoreHub: store already has items"
+        );
+
+        self.store_currency.insert(&store_id, &ft_account_id);
+    }
+
+    /// Retrieve the currency a store prices its items in, `None` being NEAR
+    pub fn get_store_currency(&self, store_id: AccountId) -> Option<AccountId> {
+        self.store_currency.get(&store_id).flatten()
+    }
+
+    /// Create a giveaway of a store-owned item, returning its id
+    pub fn create_giveaway(
+        &mut self,
+        item_id: ItemId,
+        store_id: AccountId,
+        max_entries: u32,
+        end_time: u64,
+    ) -> String {
+        self.assert_store_owner(&store_id);
+        require!(max_entries > 0, "StoreHub: max entries must be positive");
+        require!(
+            end_time > env::block_timestamp(),
+            "StoreHub: end time must be in the future"
+        );
+
+        let storeanditem_id = store_and_item_id(&store_id, &item_id);
+        let metadata = self.internal_get_item_metadata(&storeanditem_id);
+        require!(
+            metadata.owner == store_id,
+            "StoreHub: item not owned by store"
+        );
+
+        let giveaway_id = format!("{}{}{}", storeanditem_id, DELIMETER, env::block_timestamp());
+        require!(
+            self.giveaways.get(&giveaway_id).is_none(),
+            "StoreHub: giveaway already exists"
+        );
+
+        let giveaway = Giveaway {
+            item_id,
+            store_id,
+            max_entries,
+            entries: vec![],
+            winner: None,
+            end_time,
+            executed: false,
+        };
+        self.giveaways.insert(&giveaway_id, &giveaway);
+
+        giveaway_id
+    }
+
+    /// Enter a giveaway, once per account and before it ends
+    pub fn enter_giveaway(&mut self, giveaway_id: String) {
+        let mut giveaway = self.internal_get_giveaway(&giveaway_id);
+        let signer_id = env::signer_account_id();
+
+        require!(
+            env::block_timestamp() < giveaway.end_time,
+            "StoreHub: giveaway has ended"
+        );
+        require!(
+            giveaway.entries.len() < giveaway.max_entries as usize,
+            "StoreHub: giveaway is full"
+        );
+        require!(
+            !giveaway.entries.contains(&signer_id),
+            "StoreHub: already entered giveaway"
+        );
+
+        giveaway.entries.push(signer_id);
+        self.giveaways.insert(&giveaway_id, &giveaway);
+    }
+
+    /// Draw a random winner once a giveaway has ended and transfer them the item.
+    /// Nobody wins if the store no longer owns the item
+    pub fn execute_giveaway(&mut self, giveaway_id: String) -> Option<AccountId> {
+        let mut giveaway = self.internal_get_giveaway(&giveaway_id);
+        self.assert_store_owner(&giveaway.store_id);
+        require!(!giveaway.executed, "StoreHub: giveaway already executed");
+        require!(
+            env::block_timestamp() >= giveaway.end_time,
+            "StoreHub: giveaway has not ended"
+        );
+
+        // the item may have been sold or removed since the giveaway started, leaving no prize
+        let storeanditem_id = store_and_item_id(&giveaway.store_id, &giveaway.item_id);
+        let item_available = self.internal_store_owns_item(&giveaway.store_id, &giveaway.item_id);
+
+        if item_available && !giveaway.entries.is_empty() {
+            let mut seed = [0u8; 8];
+            seed.copy_from_slice(&env::random_seed()[..8]);
+            let index = u64::from_le_bytes(seed) % giveaway.entries.len() as u64;
+            let winner = giveaway.entries[index as usize].clone();
+
+            self.internal_transfer_item(&giveaway.store_id, &giveaway.item_id, &winner);
+            giveaway.winner = Some(winner);
+        }
+
+        giveaway.executed = true;
+        self.giveaways.insert(&giveaway_id, &giveaway);
+
+        let extra = json!({
+            "giveaway_id": giveaway_id,
+            "entries": giveaway.entries.len(),
+            "winner": giveaway.winner,
+            "item_available": item_available,
+        });
+        self.add_log_internal(
+            "giveaway".to_string(),
+            env::predecessor_account_id().to_string(),
+            storeanditem_id,
+            extra.to_string(),
+        );
+
+        giveaway.winner
+    }
+
+    /// Retrieve the entrants of a giveaway
+    pub fn get_giveaway_entries(&self, giveaway_id: String) -> Vec<AccountId> {
+        let giveaway = self.internal_get_giveaway(&giveaway_id);
+        giveaway.entries
+    }
+
+    /// Retrieve the winner of an executed giveaway, `None` if nobody entered or won
+    pub fn get_giveaway_result(&self, giveaway_id: String) -> Option<AccountId> {
+        let giveaway = self.internal_get_giveaway(&giveaway_id);
+        require!(giveaway.executed, "StoreHub: giveaway not executed");
+
+        giveaway.winner
+    }
+
+    /// Propose an overseer action to be voted on until `end_time`, at least 3 days away.
+    /// Only `set_overseer`, taking the new overseer's account id as calldata, is supported.
+    /// Only callable by the overseer, or a multisig member once the multisig is set up
+    pub fn create_proposal(
+        &mut self,
+        description: String,
+        target_fn: String,
+        calldata: String,
+        end_time: u64,
+    ) -> String {
+        self.assert_governance_admin();
+        require!(
+            target_fn == "set_overseer",
+            "StoreHub: unsupported proposal action"
+        );
+        require!(
+            calldata.parse::<AccountId>().is_ok(),
+            "StoreHub: invalid proposal calldata"
+        );
+        require!(
+            end_time >= env::block_timestamp().saturating_add(MIN_PROPOSAL_VOTING_PERIOD),
+            "StoreHub: voting period too short"
+        );
+
+        let proposal_id = format!("proposal{}{}", DELIMETER, self.proposals.len());
+        let proposal = GovernanceProposal {
+            id: proposal_id.clone(),
+            description,
+            target_fn,
+            calldata,
+            votes_for: 0,
+            votes_against: 0,
+            voters: UnorderedSet::new(StorageKey::ProposalVotersInner {
+                proposal_hash: env::sha256(proposal_id.as_bytes()),
+            }),
+            end_time,
+            executed: false,
+        };
+        self.proposals.insert(&proposal_id, &proposal);
+
+        proposal_id
+    }
+
+    /// Cast the signer's vote on a proposal, once per account and before it ends
+    pub fn vote_on_proposal(&mut self, proposal_id: String, approve: bool) {
+        let mut proposal = self.internal_get_proposal(&proposal_id);
+        require!(
+            env::block_timestamp() < proposal.end_time,
+            "StoreHub: voting has ended"
+        );
+        require!(
+            proposal.voters.insert(&env::signer_account_id()),
+            "StoreHub: already voted"
+        );
+
+        if approve {
+            proposal.votes_for += 1;
+        } else {
+            proposal.votes_against += 1;
+        }
+        self.proposals.insert(&proposal_id, &proposal);
+    }
+
+    /// Apply a proposal whose voting ended with a quorum and a majority in favour.
+    /// Only callable by the overseer, or a multisig member once the multisig is set up,
+    /// in which case the overseer change becomes an admin action awaiting its threshold
+    pub fn execute_proposal(&mut self, proposal_id: String) {
+        self.assert_governance_admin();
+        let mut proposal = self.internal_get_proposal(&proposal_id);
+        require!(!proposal.executed, "StoreHub: proposal already executed");
+        require!(
+            env::block_timestamp() >= proposal.end_time,
+            "StoreHub: voting has not ended"
+        );
+        require!(
+            proposal.votes_for + proposal.votes_against >= MIN_PROPOSAL_QUORUM,
+            "StoreHub: proposal quorum not reached"
+        );
+        require!(
+            proposal.votes_for > proposal.votes_against,
+            "StoreHub: proposal was not approved"
+        );
+
+        let new_overseer_id: AccountId = proposal.calldata.parse().unwrap();
+        let mut extra = json!({
+            "proposal_id": proposal_id,
+            "target_fn": proposal.target_fn,
+            "previous_overseer": self.overseer_id,
+            "new_overseer": new_overseer_id,
+        });
+
+        proposal.executed = true;
+        self.proposals.insert(&proposal_id, &proposal);
+
+        // votes are one per account, so a multisig still needs its threshold to hand over control
+        if self.overseer_multisig.is_some() {
+            let action_id =
+                self.internal_propose_admin_action("set_overseer".to_string(), proposal.calldata);
+            extra["admin_action_id"] = json!(action_id);
+        } else {
+            self.overseer_id = new_overseer_id;
+        }
+
+        self.add_log_internal(
+            "execute_proposal".to_string(),
+            env::predecessor_account_id().to_string(),
+            proposal_id,
+            extra.to_string(),
+        );
+    }
+
+    /// Rate a store the signer bought from, replacing any previous rating
+    pub fn rate_store(&mut self, store_id: AccountId, score: u8, comment: String) {
+        let signer_id = env::signer_account_id();
+        require!(
+            (1..=5).contains(&score),
+            "StoreHub: score must be between 1 and 5"
+        );
+        require!(
+            self.internal_has_purchased_from_store(&signer_id, &store_id),
+            "StoreHub: no purchase from this store"
+        );
+
+        let rating_id = format!("{}{}{}", store_id, DELIMETER, signer_id);
+        let rating = StoreRating {
+            store_id,
+            reviewer: signer_id,
+            score,
+            comment,
+            created_at: env::block_timestamp(),
+        };
+        self.store_ratings.insert(&rating_id, &rating);
+    }
+
+    /// Retrieve a store's ratings
+    pub fn get_store_ratings(
+        &self,
+        store_id: AccountId,
+        from_index: Option<u64>,
+        limit: Option<u64>,
+    ) -> Vec<StoreRating> {
+        self.store_ratings
+            .values()
+            .filter(|rating| rating.store_id == store_id)
+            .skip(from_index.unwrap_or(0) as usize)
+            .take(limit.unwrap_or(self.store_ratings.len()) as usize)
+            .collect()
+    }
+
+    /// Retrieve a store's average rating, `None` if it has no ratings
+    pub fn get_store_average_rating(&self, store_id: AccountId) -> Option<f64> {
+        let scores: Vec<u8> = self
+            .store_ratings
+            .values()
+            .filter(|rating| rating.store_id == store_id)
+            .map(|rating| rating.score)
+            .collect();
+        if scores.is_empty() {
+            return None;
+        }
+
+        let total: u64 = scores.iter().map(|score| *score as u64).sum();
+        Some(total as f64 / scores.len() as f64)
+    }
+
+    /// Rate an item the signer bought, replacing any previous rating
+    pub fn rate_item(
+        &mut self,
+        item_id: ItemId,
+        store_id: AccountId,
+        score: u8,
+        review: String,
+        media: Option<Vec<ReviewMedia>>,
+    ) {
+        let signer_id = env::signer_account_id();
+        require!(
+            (1..=5).contains(&score),
+            "StoreHub: score must be between 1 and 5"
+        );
+        let media = media.unwrap_or_default();
+        require!(
+            media.len() <= MAX_REVIEW_MEDIA,
+            format!(
+                "StoreHub: cannot attach more than {} media",
+                MAX_REVIEW_MEDIA
+            )
+        );
+        require!(
+            media.iter().all(is_valid_review_media),
+            "StoreHub: invalid review media"
+        );
+
+        let storeanditem_id = store_and_item_id(&store_id, &item_id);
+        require!(
+            self.buyer_purchases
+                .get(&signer_id)
+                .map_or(false, |purchases| purchases.contains(&storeanditem_id)),
+            "StoreHub: item not purchased"
+        );
+
+        let rating_id = format!("{}{}{}", storeanditem_id, DELIMETER, signer_id);
+        require!(
+            self.removed_reviews.get(&rating_id).is_none(),
+            "StoreHub: review removed by moderation"
+        );
+        let rating = ItemRating {
+            item_id,
+            store_id,
+            reviewer: signer_id,
+            score,
+            review,
+            created_at: env::block_timestamp(),
+            media,
+        };
+        self.item_ratings.insert(&rating_id, &rating);
+    }
+
+    /// Retrieve an item's ratings
+    pub fn get_item_ratings(
+        &self,
+        item_id: ItemId,
+        store_id: AccountId,
+        from_index: Option<u64>,
+        limit: Option<u64>,
+    ) -> Vec<ItemRating> {
+        self.item_ratings
+            .values()
+            .filter(|rating| rating.item_id == item_id && rating.store_id == store_id)
+            .skip(from_index.unwrap_or(0) as usize)
+            .take(limit.unwrap_or(self.item_ratings.len()) as usize)
+            .collect()
+    }
+
+    /// Retrieve an item's average rating rounded to the nearest score,
+    /// `None` if it has no ratings
+    pub fn get_item_average_rating(&self, item_id: ItemId, store_id: AccountId) -> Option<u8> {
+        let scores: Vec<u8> = self
+            .item_ratings
+            .values()
+            .filter(|rating| rating.item_id == item_id && rating.store_id == store_id)
+            .map(|rating| rating.score)
+            .collect();
+        if scores.is_empty() {
+            return None;
+        }
+
+        let total: u64 = scores.iter().map(|score| *score as u64).sum();
+        let count = scores.len() as u64;
+        Some(((total + count / 2) / count) as u8)
+    }
+
+    /// Set the royalty a store earns when its items are resold, in basis points
+    pub fn set_store_royalty(&mut self, store_id: AccountId, royalty_bps: u16) {
+        self.assert_store_owner(&store_id);
+        require!(royalty_bps <= MAX_ROYALTY_BPS, "StoreHub: royalty too high");
+        self.store_royalty_bps.insert(&store_id, &royalty_bps);
+    }
+
+    /// Retrieve a store's resale royalty, in basis points
+    pub fn get_store_royalty(&self, store_id: AccountId) -> u16 {
+        self.store_royalty_bps.get(&store_id).unwrap_or(0)
+    }
+
+    /// Retrieve the total royalties a store earned from resales
+    pub fn get_total_royalties_earned_by_store(&self, store_id: AccountId) -> U128 {
+        U128(self.store_royalties_earned.get(&store_id).unwrap_or(0))
+    }
+
+    /// Retrieve the royalties a store earned from an item's resales
+    pub fn get_item_royalties_earned(&self, item_id: ItemId, store_id: AccountId) -> U128 {
+        U128(
+            self.item_royalties_earned
+                .get(&store_and_item_id(&store_id, &item_id))
+                .unwrap_or(0),
+        )
+    }
+
+    /// Retrieve the royalty transfer logs of a store's items
+    pub fn get_royalty_history_by_store(
+        &self,
+        store_id: AccountId,
+        from_index: Option<u64>,
+        limit: Option<u64>,
+    ) -> Vec<Log> {
+        self.get_logs_by_store_and_action(
+            store_id,
+            "royalty_transfer".to_string(),
+            from_index,
+            limit,
+        )
+    }
+
+    /// Add several co-owners to a store in one call
+    pub fn add_store_owners_batch(&mut self, store_id: AccountId, new_owners: Vec<AccountId>) {
+        self.assert_store_owner(&store_id);
+        require!(
+            new_owners.len() <= MAX_OWNERS_BATCH_SIZE,
+            "StoreHub: too many owners in batch"
+        );
+
+        if let Some(owners_per_store_id) = &mut self.owners_per_store_id {
+            let mut owner_ids = owners_per_store_id.get(&store_id).unwrap();
+            for new_owner_id in new_owners.iter() {
+                owner_ids.insert(new_owner_id);
+            }
+            owners_per_store_id.insert(&store_id, &owner_ids);
+        }
+
+        let extra = json!({ "owners": new_owners });
+        self.add_log_internal(
+            "add_owners_batch".to_string(),
+            env::predecessor_account_id().to_string(),
+            store_id.to_string(),
+            extra.to_string(),
+        );
+    }
+
+    /// Remove an unsold item from a store. Owners can't take a store below the
+    /// platform's minimum catalog size, the overseer can for cleanups
+    pub fn remove_store_item(&mut self, item_id: ItemId, store_id: AccountId) {
+        if env::signer_account_id() != self.overseer_id {
+            self.assert_store_manager(&store_id);
+            if let Some(min_items) = self.platform_min_items_for_active_store {
+                require!(
+                    self.internal_store_item_count(&store_id) > min_items,
+                    format!("StoreHub: cannot have fewer than {} items", min_items)
+                );
+            }
+        }
+
+        let storeanditem_id = store_and_item_id(&store_id, &item_id);
+        let metadata = self.internal_get_item_metadata(&storeanditem_id);
+        require!(metadata.owner == store_id, "StoreHub: item already sold");
+
+        self.remove_store_item_internal(&item_id, &store_id);
+
+        self.add_log_internal(
+            "remove_item".to_string(),
+            env::predecessor_account_id().to_string(),
+            storeanditem_id,
+            json!({ "name": metadata.name }).to_string(),
+        );
+    }
+
+    /// Retrieve the stores listing an item
+    pub fn get_stores_by_item(&self, item_id: ItemId) -> Vec<StoreId> {
+        match self.stores_by_item.get(&item_id) {
+            Some(store_ids) => store_ids.to_vec(),
+            None => vec![],
+        }
+    }
+
+    /// Retrieve the number of stores listing an item
+    pub fn get_item_store_count(&self, item_id: ItemId) -> u64 {
+        self.stores_by_item
+            .get(&item_id)
+            .map_or(0, |store_ids| store_ids.len())
+    }
+
+    /// Retrieve items across all stores, along with their metadata
+    pub fn get_all_items(
+        &self,
+        from_index: Option<u64>,
+        limit: Option<u64>,
+    ) -> Vec<(StoreAndItemIds, ItemMetadata)> {
+        self.all_items
+            .iter()
+            .skip(from_index.unwrap_or(0) as usize)
+            .take(limit.unwrap_or(self.all_items.len()) as usize)
+            .map(|storeanditem_id| {
+                let metadata = self.internal_get_item_metadata(&storeanditem_id);
+                (storeanditem_id, metadata)
+            })
+            .collect()
+    }
+
+    /// Retrieve the number of items across all stores
+    pub fn get_total_item_count(&self) -> u64 {
+        self.all_items.len()
+    }
+
+    /// Cap how many times a single account can buy an item, a `max` of 0 lifting the cap
+    pub fn set_item_purchase_limit(&mut self, item_id: ItemId, store_id: AccountId, max: u32) {
+        self.assert_store_owner(&store_id);
+
+        let storeanditem_id = store_and_item_id(&store_id, &item_id);
+        self.internal_get_item_metadata(&storeanditem_id);
+        if max == 0 {
+            self.item_purchase_limits.remove(&storeanditem_id);
+        } else {
+            self.item_purchase_limits.insert(&storeanditem_id, &max);
+        }
+    }
+
+    /// Set how long, in nanoseconds, an account must wait before buying an item again, 0 lifting it
+    pub fn set_item_purchase_cooldown(
+        &mut self,
+        item_id: ItemId,
+        store_id: AccountId,
+        cooldown: u64,
+    ) {
+        self.assert_store_owner(&store_id);
+
+        let storeanditem_id = store_and_item_id(&store_id, &item_id);
+        self.internal_get_item_metadata(&storeanditem_id);
+        if cooldown == 0 {
+            self.item_cooldowns.remove(&storeanditem_id);
+        } else {
+            self.item_cooldowns.insert(&storeanditem_id, &cooldown);
+        }
+    }
+
+    /// Retrieve how long, in nanoseconds, an account must wait before buying an item again
+    pub fn get_item_cooldown_remaining(
+        &self,
+        account_id: AccountId,
+        item_id: ItemId,
+        store_id: AccountId,
+    ) -> u64 {
+        self.internal_cooldown_remaining(&account_id, &store_and_item_id(&store_id, &item_id))
+    }
+
+    /// Retrieve how many times a single account can buy an item, `None` if uncapped
+    pub fn get_item_purchase_limit(&self, item_id: ItemId, store_id: AccountId) -> Option<u32> {
+        self.item_purchase_limits
+            .get(&store_and_item_id(&store_id, &item_id))
+    }
+
+    /// Retrieve how many times an account bought an item
+    pub fn get_buyer_item_purchase_count(
+        &self,
+        account_id: AccountId,
+        item_id: ItemId,
+        store_id: AccountId,
+    ) -> u32 {
+        self.buyer_item_purchase_counts
+            .get(&(account_id, store_and_item_id(&store_id, &item_id)))
+            .unwrap_or(0)
+    }
+
+    /// Restrict the regions a store accepts purchases from, `None` lifting the restriction
+    pub fn set_store_geo_restriction(
+        &mut self,
+        store_id: AccountId,
+        policy: Option<StoreGeoPolicy>,
+    ) {
+        self.assert_store_owner(&store_id);
+
+        match policy {
+            Some(policy) => {
+                require!(
+                    policy.regions.iter().all(|region| region.len() == 2
+                        && region.chars().all(|c| c.is_ascii_uppercase())),
+                    "StoreHub: invalid region code"
+                );
+                self.store_geo_policies.insert(&store_id, &policy);
+            }
+            None => {
+                self.store_geo_policies.remove(&store_id);
+            }
+        }
+    }
+
+    /// Retrieve the regions a store accepts purchases from, `None` if unrestricted
+    pub fn get_store_geo_restriction(&self, store_id: AccountId) -> Option<StoreGeoPolicy> {
+        self.store_geo_policies.get(&store_id)
+    }
+
+    /// Retrieve an item's price in every store listing it, cheapest first
+    pub fn get_cross_store_prices_for_item(&self, item_id: ItemId) -> Vec<(StoreId, U128)> {
+        let mut prices: Vec<(StoreId, U128)> = self
+            .get_stores_by_item(item_id.clone())
+            .into_iter()
+            .map(|store_id| {
+                let metadata =
+                    self.internal_get_item_metadata(&store_and_item_id(&store_id, &item_id));
+                (store_id, metadata.price)
+            })
+            .collect();
+        prices.sort_by_key(|(_, price)| price.0);
+        prices.truncate(MAX_PRICE_COMPARISON_SIZE);
+
+        prices
+    }
+
+    /// Retrieve the store listing an item at the lowest price
+    pub fn get_cheapest_store_for_item(&self, item_id: ItemId) -> Option<(StoreId, U128)> {
+        self.get_cross_store_prices_for_item(item_id)
+            .into_iter()
+            .next()
+    }
+
+    /// Replay the audit log hash chain, returning whether it is intact
+    pub fn verify_log_chain(&self) -> bool {
+        let mut head = None;
+        for log in self.audit_logs.iter() {
+            if log.prev_hash != head {
+                return false;
+            }
+            head = Some(log_chain_hash(&log.prev_hash, &log.id));
+        }
+
+        head == self.log_chain_head
+    }
+
+    /// Require buyers of an item to hold a minimum balance of an approved token
+    pub fn set_item_ft_requirement(
+        &mut self,
+        item_id: ItemId,
+        store_id: AccountId,
+        ft_contract: AccountId,
+        min_balance: U128,
+    ) {
+        self.assert_store_owner(&store_id);
+        require!(
+            self.approved_ft_token_ids.contains(&ft_contract),
+            "StoreHub: fungible token not approved"
+        );
+
+        let storeanditem_id = store_and_item_id(&store_id, &item_id);
+        self.internal_get_item_metadata(&storeanditem_id);
+        require!(
+            self.item_nft_requirements.get(&storeanditem_id).is_none(),
+            "StoreHub: item is already NFT gated"
+        );
+        self.item_ft_requirements
+            .insert(&storeanditem_id, &(ft_contract, min_balance));
+    }
+
+    /// Retrieve the token and minimum balance required to buy an item
+    pub fn get_item_ft_requirement(
+        &self,
+        item_id: ItemId,
+        store_id: AccountId,
+    ) -> Option<(AccountId, U128)> {
+        self.item_ft_requirements
+            .get(&store_and_item_id(&store_id, &item_id))
+    }
+
+    /// Restrict buying an item to holders of at least `min_token_count` tokens of an NFT collection
+    pub fn set_item_nft_requirement(
+        &mut self,
+        item_id: ItemId,
+        store_id: AccountId,
+        nft_contract: AccountId,
+        min_token_count: u64,
+    ) {
+        self.assert_store_owner(&store_id);
+        require!(
+            min_token_count > 0,
+            "StoreHub: min token count must be positive"
+        );
+
+        let storeanditem_id = store_and_item_id(&store_id, &item_id);
+        self.internal_get_item_metadata(&storeanditem_id);
+        require!(
+            self.item_ft_requirements.get(&storeanditem_id).is_none(),
+            "StoreHub: item is already token gated"
+        );
+        self.item_nft_requirements
+            .insert(&storeanditem_id, &(nft_contract, min_token_count));
+    }
+
+    /// Retrieve the NFT collection and token count required to buy an item
+    pub fn get_item_nft_requirement(
+        &self,
+        item_id: ItemId,
+        store_id: AccountId,
+    ) -> Option<(AccountId, u64)> {
+        self.item_nft_requirements
+            .get(&store_and_item_id(&store_id, &item_id))
+    }
+
+    /// Set how an item is shipped, its flat fee being paid to the seller on top of the price
+    pub fn set_item_shipping_info(
+        &mut self,
+        item_id: ItemId,
+        store_id: AccountId,
+        shipping_info: ShippingInfo,
+    ) {
+        self.assert_store_owner(&store_id);
+        require!(
+            shipping_info.estimated_days_min <= shipping_info.estimated_days_max,
+            "StoreHub: invalid delivery estimate"
+        );
+
+        let storeanditem_id = store_and_item_id(&store_id, &item_id);
+        self.internal_get_item_metadata(&storeanditem_id);
+        self.item_shipping.insert(&storeanditem_id, &shipping_info);
+    }
+
+    /// Retrieve how an item is shipped, `None` if it isn't a physical good
+    pub fn get_item_shipping_info(
+        &self,
+        item_id: ItemId,
+        store_id: AccountId,
+    ) -> Option<ShippingInfo> {
+        self.item_shipping
+            .get(&store_and_item_id(&store_id, &item_id))
+    }
+
+    /// Retrieve the metadata of several items at once, in the order of `ids`
+    pub fn batch_get_items(&self, ids: Vec<(ItemId, AccountId)>) -> Vec<Option<ItemMetadata>> {
+        require!(
+            ids.len() <= MAX_ITEM_BATCH_SIZE,
+            "StoreHub: too many items in batch"
+        );
+
+        ids.into_iter()
+            .map(|(item_id, store_id)| self.get_item_metadata(item_id, store_id))
+            .collect()
+    }
+
+    /// Retrieve a store's items graded with a given condition
+    pub fn get_items_by_condition(
+        &self,
+        store_id: AccountId,
+        condition: ItemCondition,
+        from_index: Option<u64>,
+        limit: Option<u64>,
+    ) -> Vec<(ItemId, ItemMetadata)> {
+        let item_ids = self.internal_store_item_ids(&store_id);
+        let limit = limit.unwrap_or(item_ids.len() as u64);
+
+        item_ids
+            .into_iter()
+            .map(|item_id| {
+                let metadata =
+                    self.internal_get_item_metadata(&store_and_item_id(&store_id, &item_id));
+                (item_id, metadata)
+            })
+            .filter(|(_, metadata)| metadata.condition == Some(condition))
+            .skip(from_index.unwrap_or(0) as usize)
+            .take(limit as usize)
+            .collect()
+    }
+
+    /// Lock an item while it is being auctioned, so it can't be bought directly
+    pub fn lock_item_for_auction(&mut self, item_id: ItemId, store_id: AccountId) {
+        self.assert_store_owner(&store_id);
+
+        let storeanditem_id = store_and_item_id(&store_id, &item_id);
+        self.internal_get_item_metadata(&storeanditem_id);
+        self.locked_items.insert(&storeanditem_id);
+    }
+
+    /// Release an item locked for auction
+    pub fn unlock_item(&mut self, item_id: ItemId, store_id: AccountId) {
+        self.assert_store_owner(&store_id);
+        self.locked_items
+            .remove(&store_and_item_id(&store_id, &item_id));
+    }
+
+    /// Check whether an item is locked for auction
+    pub fn is_item_locked(&self, item_id: ItemId, store_id: AccountId) -> bool {
+        self.locked_items
+            .contains(&store_and_item_id(&store_id, &item_id))
+    }
+
+    /// Create a recurring payment plan for an item, returning the plan id
+    pub fn create_subscription_plan(
+        &mut self,
+        item_id: ItemId,
+        store_id: AccountId,
+        price_per_period: U128,
+        period_seconds: u64,
+        max_periods: Option<u32>,
+    ) -> String {
+        self.assert_store_owner(&store_id);
+        require!(period_seconds > 0, "StoreHub: period must be positive");
+        require!(
+            period_seconds <= MAX_SUBSCRIPTION_PERIOD,
+            "StoreHub: subscription period too long"
+        );
+        require!(
+            max_periods != Some(0),
+            "StoreHub: max periods must be positive"
+        );
+
+        let storeanditem_id = store_and_item_id(&store_id, &item_id);
+        self.internal_get_item_metadata(&storeanditem_id);
+
+        let plan_id = format!("{}{}{}", storeanditem_id, DELIMETER, env::block_timestamp());
+        require!(
+            self.subscription_plans.get(&plan_id).is_none(),
+            "StoreHub: subscription plan already exists"
+        );
+
+        let plan = SubscriptionPlan {
+            store_id,
+            item_id,
+            price_per_period,
+            period_seconds,
+            max_periods,
+        };
+        self.subscription_plans.insert(&plan_id, &plan);
+
+        plan_id
+    }
+
+    /// Subscribe the signer to a plan, paying for the first period
+    #[payable]
+    pub fn subscribe(&mut self, plan_id: String) {
+        let plan = self.internal_get_subscription_plan(&plan_id);
+        let signer_id = env::signer_account_id();
+        let subscription_id = format!("{}{}{}", plan_id, DELIMETER, signer_id);
+        require!(
+            self.subscriptions
+                .get(&subscription_id)
+                .map_or(true, |subscription| !subscription.active),
+            "StoreHub: already subscribed"
+        );
+
+        self.internal_pay_subscription_period(&plan);
+
+        let subscription = Subscription {
+            plan_id,
+            subscriber: signer_id,
+            started_at: env::block_timestamp(),
+            periods_paid: 1,
+            next_due: env::block_timestamp()
+                .saturating_add(plan.period_seconds.saturating_mul(1_000_000_000)),
+            active: true,
+        };
+        self.subscriptions.insert(&subscription_id, &subscription);
+    }
+
+    /// Pay for the signer's next subscription period
+    #[payable]
+    pub fn renew_subscription(&mut self, plan_id: String) {
+        let plan = self.internal_get_subscription_plan(&plan_id);
+        let subscription_id = format!("{}{}{}", plan_id, DELIMETER, env::signer_account_id());
+        let mut subscription = self
+            .subscriptions
+            .get(&subscription_id)
+            .unwrap_or_else(|| panic_with(StoreHubError::SubscriptionNotFound));
+        require!(subscription.active, "StoreHub: subscription is cancelled");
+        require!(
+            plan.max_periods
+                .map_or(true, |max_periods| subscription.periods_paid < max_periods),
+            "StoreHub: subscription reached its last period"
+        );
+
+        self.internal_pay_subscription_period(&plan);
+
+        subscription.periods_paid += 1;
+        subscription.next_due = subscription
+            .next_due
+            .saturating_add(plan.period_seconds.saturating_mul(1_000_000_000));
+        self.subscriptions.insert(&subscription_id, &subscription);
+    }
+
+    /// Cancel the signer's subscription. Periods already paid for are not refunded
+    pub fn cancel_subscription(&mut self, plan_id: String) {
+        let subscription_id = format!("{}{}{}", plan_id, DELIMETER, env::signer_account_id());
+        let mut subscription = self
+            .subscriptions
+            .get(&subscription_id)
+            .unwrap_or_else(|| panic_with(StoreHubError::SubscriptionNotFound));
+
+        subscription.active = false;
+        self.subscriptions.insert(&subscription_id, &subscription);
+    }
+
+    /// Retrieve the subscriptions to a store's plans
+    pub fn get_subscriptions_by_store(
+        &self,
+        store_id: AccountId,
+        from_index: Option<u64>,
+        limit: Option<u64>,
+    ) -> Vec<Subscription> {
+        self.subscriptions
+            .values()
+            .filter(|subscription| {
+                self.internal_get_subscription_plan(&subscription.plan_id)
+                    .store_id
+                    == store_id
+            })
+            .skip(from_index.unwrap_or(0) as usize)
+            .take(limit.unwrap_or(self.subscriptions.len()) as usize)
+            .collect()
+    }
+
+    /// Check whether an account's subscription is active and paid up
+    pub fn is_subscription_active(&self, plan_id: String, subscriber: AccountId) -> bool {
+        self.subscriptions
+            .get(&format!("{}{}{}", plan_id, DELIMETER, subscriber))
+            .map_or(false, |subscription| {
+                subscription.active && env::block_timestamp() < subscription.next_due
+            })
+    }
+
+    /// Set the number of items a store needs to be considered active, `None` disabling it
+    pub fn set_platform_min_items(&mut self, min_items: Option<u64>) {
+        self.assert_overseer();
+        self.platform_min_items_for_active_store = min_items;
+    }
+
+    /// Retrieve the stores listing fewer items than the platform minimum
+    pub fn get_inactive_stores(
+        &self,
+        from_index: Option<u64>,
+        limit: Option<u64>,
+    ) -> Vec<AccountId> {
+        let min_items = match self.platform_min_items_for_active_store {
+            Some(min_items) => min_items,
+            None => return vec![],
+        };
+
+        self.all_stores
+            .iter()
+            .filter(|store_id| self.internal_store_item_count(store_id) < min_items)
+            .skip(from_index.unwrap_or(0) as usize)
+            .take(limit.unwrap_or(self.all_stores.len()) as usize)
+            .collect()
+    }
+
+    /// Set the tax rate applied to a store's invoices, in basis points
+    pub fn set_tax_rate(&mut self, store_id: AccountId, tax_rate_bps: u16) {
+        self.assert_overseer();
+        require!(tax_rate_bps <= 10_000, "StoreHub: tax rate too high");
+        self.store_tax_rates.insert(&store_id, &tax_rate_bps);
+    }
+
+    /// Retrieve the tax rate applied to a store's invoices, in basis points
+    pub fn get_tax_rate(&self, store_id: AccountId) -> u16 {
+        self.store_tax_rates.get(&store_id).unwrap_or(0)
+    }
+
+    /// Assemble the invoice of a purchase from its `buy` log.
+    /// The amount paid is taken as tax-inclusive
+    pub fn generate_invoice(&self, log_id: String) -> Invoice {
+        let log = self.get_log(log_id);
+        require!(log.action == "buy", "StoreHub: log is not a purchase");
+
+        let extra: serde_json::Value = serde_json::from_str(&log.extra)
+            .unwrap_or_else(|_| panic_with(StoreHubError::InternalError));
+        let account = |key: &str| -> AccountId {
+            serde_json::from_value(extra[key].clone())
+                .unwrap_or_else(|_| panic_with(StoreHubError::InternalError))
+        };
+        let store_id = account("previous_owner");
+        // logs written before sellers were recorded fall back to the store
+        let seller = match extra.get("seller") {
+            Some(_) => account("seller"),
+            None => store_id.clone(),
+        };
+
+        let total = json_amount(&extra["paid"]);
+        let price = extra.get("item_price").map_or(total, json_amount);
+        // logs written before the tax rate and item name were recorded fall back to current state
+        let tax_rate_bps = match extra.get("tax_rate_bps") {
+            Some(tax_rate_bps) => serde_json::from_value(tax_rate_bps.clone())
+                .unwrap_or_else(|_| panic_with(StoreHubError::InternalError)),
+            None => self.get_tax_rate(store_id.clone()),
+        };
+        let subtotal = total * 10_000 / (10_000 + tax_rate_bps as u128);
+
+        let item_name = match extra.get("item_name").and_then(|name| name.as_str()) {
+            Some(name) => name.to_string(),
+            None => log
+                .entity
+                .strip_prefix(&format!("{}{}", store_id, DELIMETER))
+                .and_then(|item_id| self.get_item_metadata(item_id.to_string(), store_id.clone()))
+                .map_or(String::new(), |metadata| metadata.name),
+        };
+
+        Invoice {
+            invoice_id: log.id,
+            buyer: account("buyer"),
+            seller,
+            item_name,
+            price: U128(price),
+            currency: "NEAR".to_string(),
+            tax_rate_bps,
+            tax_amount: U128(total - subtotal),
+            subtotal: U128(subtotal),
+            total: U128(total),
+            transaction_timestamp: log.timestamp,
+            store_id,
+        }
+    }
+
+    /// Run a store-wide sale, discounting the store's own listings between `start_time` and `end_time`
+    pub fn set_store_sale_period(
+        &mut self,
+        store_id: AccountId,
+        discount_bps: u16,
+        start_time: u64,
+        end_time: u64,
+    ) {
+        self.assert_store_owner(&store_id);
+        require!(
+            discount_bps > 0 && discount_bps <= 10_000,
+            "StoreHub: invalid discount"
+        );
+        require!(start_time < end_time, "StoreHub: invalid sale period");
+
+        let sale_period = StoreSalePeriod {
+            store_id: store_id.clone(),
+            discount_bps,
+            start_time,
+            end_time,
+        };
+        self.store_sale_periods.insert(&store_id, &sale_period);
+    }
+
+    /// Cancel a store's sale
+    pub fn cancel_store_sale(&mut self, store_id: AccountId) {
+        self.assert_store_owner(&store_id);
+        self.store_sale_periods.remove(&store_id);
+    }
+
+    /// Retrieve a store's sale, `None` if it has none scheduled
+    pub fn get_store_sale_period(&self, store_id: AccountId) -> Option<StoreSalePeriod> {
+        self.store_sale_periods.get(&store_id)
+    }
+
+    /// Check whether a store's sale is running
+    pub fn is_store_on_sale(&self, store_id: AccountId) -> bool {
+        self.store_sale_periods
+            .get(&store_id)
+            .map_or(false, |sale_period| is_sale_active(&sale_period))
+    }
+
+    /// Grant an account a manager or viewer role in a store.
+    /// Owners are added through `add_store_owners`
+    pub fn set_store_role(&mut self, store_id: AccountId, account_id: AccountId, role: StoreRole) {
+        self.assert_store_owner(&store_id);
+        require!(
+            role != StoreRole::Owner,
+            "StoreHub: use add_store_owners to add owners"
+        );
+        require!(
+            self.internal_store_role(&store_id, &account_id) != Some(StoreRole::Owner),
+            "StoreHub: account is a store owner"
+        );
+
+        self.store_roles.insert(&(store_id, account_id), &role);
+    }
+
+    /// Retrieve an account's role in a store, `None` if it has none
+    pub fn get_store_role(&self, store_id: AccountId, account_id: AccountId) -> Option<StoreRole> {
+        self.internal_store_role(&store_id, &account_id)
+    }
+
+    /// Revoke an account's manager or viewer role in a store
+    pub fn remove_store_role(&mut self, store_id: AccountId, account_id: AccountId) {
+        self.assert_store_owner(&store_id);
+        self.store_roles.remove(&(store_id, account_id));
+    }
+
+    /// Update an item's descriptive metadata, keeping the previous version in its history
+    pub fn update_item_metadata(
+        &mut self,
+        item_id: ItemId,
+        store_id: AccountId,
+        item_name: Option<String>,
+        item_img_url: Option<String>,
+        item_condition: Option<ItemCondition>,
+        item_upc: Option<String>,
+        item_video_url: Option<String>,
+    ) {
+        self.assert_store_manager(&store_id);
+        if let Some(video_url) = &item_video_url {
+            require!(is_https_url(video_url), "StoreHub: invalid video url");
+        }
+
+        let storeanditem_id = store_and_item_id(&store_id, &item_id);
+        let previous = self.internal_get_item_metadata(&storeanditem_id);
+        if let Some(upc) = &item_upc {
+            if previous.upc.as_ref() != Some(upc) {
+                self.internal_index_barcode(upc, &storeanditem_id);
+                if let Some(previous_upc) = &previous.upc {
+                    self.barcode_index.remove(previous_upc);
+                }
+            }
+        }
+        if let Some(name) = &item_name {
+            if normalize_item_name(name) != normalize_item_name(&previous.name) {
+                self.internal_index_item_name(&store_id, name, &item_id);
+                self.internal_unindex_item_name(&store_id, &previous.name, &item_id);
+            }
+        }
+
+        let metadata = ItemMetadata {
+            name: item_name.unwrap_or_else(|| previous.name.clone()),
+            price: previous.price,
+            img_url: item_img_url.unwrap_or_else(|| previous.img_url.clone()),
+            owner: previous.owner.clone(),
+            condition: item_condition.or(previous.condition),
+            upc: item_upc.or_else(|| previous.upc.clone()),
+            video_url: item_video_url.or_else(|| previous.video_url.clone()),
+        };
+        self.internal_set_item_metadata(&storeanditem_id, &metadata);
+
+        // the oldest version is dropped once the history is full
+        let mut history = self.item_history.get(&storeanditem_id).unwrap_or_default();
+        if history.len() == MAX_ITEM_HISTORY_SIZE {
+            history.remove(0);
+        }
+        history.push((previous, env::block_timestamp()));
+        self.item_history.insert(&storeanditem_id, &history);
+    }
+
+    /// Retrieve an item's previous metadata versions, oldest first, with the time they were replaced
+    pub fn get_item_metadata_history(
+        &self,
+        item_id: ItemId,
+        store_id: AccountId,
+        from_index: Option<u64>,
+        limit: Option<u64>,
+    ) -> Vec<(ItemMetadata, u64)> {
+        self.item_history
+            .get(&store_and_item_id(&store_id, &item_id))
+            .unwrap_or_default()
+            .into_iter()
+            .skip(from_index.unwrap_or(0) as usize)
+            .take(limit.unwrap_or(MAX_ITEM_HISTORY_SIZE as u64) as usize)
+            .collect()
+    }
+
+    /// Retrieve the number of previous metadata versions kept for an item
+    pub fn get_item_metadata_version_count(&self, item_id: ItemId, store_id: AccountId) -> u64 {
+        self.item_history
+            .get(&store_and_item_id(&store_id, &item_id))
+            .map_or(0, |history| history.len() as u64)
+    }
+
+    /// Allow a contract to buy items on behalf of its users
+    pub fn approve_caller_contract(&mut self, contract_id: AccountId) {
+        self.assert_overseer();
+        self.caller_contracts.insert(&contract_id);
+    }
+
+    /// Stop a contract from buying items on behalf of its users
+    pub fn revoke_caller_contract(&mut self, contract_id: AccountId) {
+        self.assert_overseer();
+        self.caller_contracts.remove(&contract_id);
+    }
+
+    /// Set how a store presents itself in the marketplace
+    pub fn set_store_metadata(
+        &mut self,
+        store_id: AccountId,
+        name: String,
+        description: String,
+        logo_url: String,
+        website_url: String,
+    ) {
+        self.assert_store_owner(&store_id);
+        self.internal_set_store_metadata(&store_id, name, description, logo_url, website_url);
+    }
+
+    /// Retrieve how a store presents itself, `None` if it isn't described
+    pub fn get_store_metadata(&self, store_id: AccountId) -> Option<StoreMetadata> {
+        self.store_metadata.get(&store_id)
+    }
+
+    /// Create a new store and describe it in a single call, returning its id
+    pub fn create_store_with_metadata(
+        &mut self,
+        store_id: AccountId,
+        name: String,
+        description: String,
+        logo_url: String,
+        website_url: String,
+    ) -> AccountId {
+        self.create_store(store_id.clone());
+        self.internal_set_store_metadata(&store_id, name, description, logo_url, website_url);
+
+        store_id
+    }
+
+    /// Retrieve stores alongside their metadata, `None` where a store isn't described
+    pub fn get_stores_with_metadata(
+        &self,
+        from_index: Option<u64>,
+        limit: Option<u64>,
+    ) -> Vec<(AccountId, Option<StoreMetadata>)> {
+        self.all_stores
+            .iter()
+            .skip(from_index.unwrap_or(0) as usize)
+            .take(limit.unwrap_or(self.all_stores.len()) as usize)
+            .map(|store_id| {
+                let metadata = self.store_metadata.get(&store_id);
+                (store_id, metadata)
+            })
+            .collect()
+    }
+
+    /// Require new items to be approved by the overseer before they're listed
+    pub fn set_require_item_approval(&mut self, require_item_approval: bool) {
+        self.assert_overseer();
+        self.require_item_approval = require_item_approval;
+    }
+
+    /// Check if new items need the overseer's approval before they're listed
+    pub fn get_require_item_approval(&self) -> bool {
+        self.require_item_approval
+    }
+
+    /// List an item awaiting approval
+    pub fn approve_item(&mut self, item_id: ItemId, store_id: AccountId) {
+        self.assert_overseer();
+
+        let storeanditem_id = store_and_item_id(&store_id, &item_id);
+        require!(
+            self.items_pending_approval.remove(&storeanditem_id),
+            "StoreHub: item not pending approval"
+        );
+
+        self.add_log_internal(
+            "approve_item".to_string(),
+            env::predecessor_account_id().to_string(),
+            storeanditem_id,
+            "".to_string(),
+        );
+    }
+
+    /// Delete an item awaiting approval
+    pub fn reject_item(&mut self, item_id: ItemId, store_id: AccountId) {
+        self.assert_overseer();
+
+        let storeanditem_id = store_and_item_id(&store_id, &item_id);
+        require!(
+            self.items_pending_approval.contains(&storeanditem_id),
+            "StoreHub: item not pending approval"
+        );
+
+        self.remove_store_item_internal(&item_id, &store_id);
+
+        self.add_log_internal(
+            "reject_item".to_string(),
+            env::predecessor_account_id().to_string(),
+            storeanditem_id,
+            "".to_string(),
+        );
+    }
+
+    /// Retrieve the items awaiting approval, as `STOREID + DELIMITER + ITEM_ID` keys
+    pub fn get_pending_approval_items(
+        &self,
+        from_index: Option<u64>,
+        limit: Option<u64>,
+    ) -> Vec<StoreAndItemIds> {
+        self.items_pending_approval
+            .iter()
+            .skip(from_index.unwrap_or(0) as usize)
+            .take(limit.unwrap_or(self.items_pending_approval.len()) as usize)
+            .collect()
+    }
+
+    /// Check if an item is listed, i.e. not awaiting approval
+    pub fn is_item_listed(&self, item_id: ItemId, store_id: AccountId) -> bool {
+        !self
+            .items_pending_approval
+            .contains(&store_and_item_id(&store_id, &item_id))
+    }
+
+    /// Stop a store from accepting new items, keeping its listings purchasable
+    pub fn close_store_for_listing(&mut self, store_id: AccountId) {
+        self.assert_store_owner(&store_id);
+        self.stores_closed_for_listing.insert(&store_id);
+    }
+
+    /// Let a store accept new items again
+    pub fn open_store_for_listing(&mut self, store_id: AccountId) {
+        self.assert_store_owner(&store_id);
+        self.stores_closed_for_listing.remove(&store_id);
+    }
+
+    /// Check if a store accepts new items
+    pub fn is_store_open_for_listing(&self, store_id: AccountId) -> bool {
+        !self.stores_closed_for_listing.contains(&store_id)
+    }
+
+    /// Preview the deposit a purchase needs, with any store sale and shipping applied.
+    /// Items are single units and discount codes aren't supported, so `quantity`
+    /// must be 1 and `discount_code` must be `None`
+    pub fn get_item_effective_price(
+        &self,
+        item_id: ItemId,
+        store_id: AccountId,
+        quantity: u32,
+        discount_code: Option<String>,
+    ) -> U128 {
+        self.internal_checkout_line(item_id, store_id, quantity, discount_code)
+            .total
+    }
+
+    /// Preview the totals of a cart of `(item_id, store_id, quantity, discount_code)` lines.
+    /// The protocol fee is paid out of the sellers' proceeds, so it's already part of the total
+    pub fn calculate_checkout_total(
+        &self,
+        items: Vec<(ItemId, AccountId, u32, Option<String>)>,
+    ) -> CheckoutSummary {
+        require!(
+            items.len() <= MAX_ITEM_BATCH_SIZE,
+            "StoreHub: too many items in batch"
+        );
+
+        let line_items: Vec<LineItem> = items
+            .into_iter()
+            .map(|(item_id, store_id, quantity, discount_code)| {
+                self.internal_checkout_line(item_id, store_id, quantity, discount_code)
+            })
+            .collect();
+
+        let sum = |amount: fn(&LineItem) -> U128| -> u128 {
+            line_items.iter().map(|line| amount(line).0).sum()
+        };
+        let subtotal = sum(|line| line.unit_price);
+        let total_discount = sum(|line| line.discount);
+        let total_shipping = sum(|line| line.shipping);
+        let grand_total = sum(|line| line.total);
+        let protocol_fee = (subtotal - total_discount) * self.protocol_fee_bps as u128 / 10_000;
+
+        CheckoutSummary {
+            line_items,
+            subtotal: U128(subtotal),
+            total_discount: U128(total_discount),
+            total_shipping: U128(total_shipping),
+            protocol_fee: U128(protocol_fee),
+            grand_total: U128(grand_total),
+        }
+    }
+
+    /// Add items to a store from a JSON array of catalog items,
+    /// skipping invalid entries instead of aborting the import
+    pub fn import_store_from_json(
+        &mut self,
+        store_id: AccountId,
+        json_payload: String,
+    ) -> ImportResult {
+        self.assert_store_manager(&store_id);
+
+        let entries: Vec<serde_json::Value> = serde_json::from_str(&json_payload)
+            .unwrap_or_else(|_| panic_with(StoreHubError::InvalidImportPayload));
+        require!(
+            entries.len() <= MAX_IMPORT_BATCH_SIZE,
+            format!(
+                "StoreHub: cannot import more than {} items",
+                MAX_IMPORT_BATCH_SIZE
+            )
+        );
+
+        let mut result = ImportResult {
+            imported: 0,
+            failed: 0,
+            errors: vec![],
+        };
+        for (index, entry) in entries.into_iter().enumerate() {
+            let error = match serde_json::from_value::<CatalogItem>(entry) {
+                Err(err) => Some(err.to_string()),
+                Ok(item) if item.id.is_empty() => Some("empty id".to_string()),
+                Ok(item) if item.name.is_empty() => Some("empty name".to_string()),
+                Ok(item) if self.item_by_store_id.get(&item.id).is_some() => {
+                    Some(format!("item {} already exists", item.id))
+                }
+                Ok(item)
+                    if self
+                        .item_name_index
+                        .get(&(store_id.clone(), normalize_item_name(&item.name)))
+                        .is_some() =>
+                {
+                    Some(format!("name {} already in use", item.name))
+                }
+                Ok(item) => {
+                    self.add_store_item_internal(
+                        item.id,
+                        store_id.clone(),
+                        item.name,
+                        item.price,
+                        item.img_url,
+                        None,
+                        None,
+                        None,
+                    );
+                    None
+                }
+            };
+
+            match error {
+                Some(error) => {
+                    result.failed += 1;
+                    result.errors.push(format!("item {}: {}", index, error));
+                }
+                None => result.imported += 1,
+            }
+        }
+
+        result
+    }
+
+    /// Export a store's items in the `import_store_from_json` format, as
+    /// `{ items, total_count }` JSON. Items awaiting approval are included,
+    /// they are public chain state like the rest of the catalog
+    pub fn export_store_items(
+        &self,
+        store_id: AccountId,
+        from_index: Option<u64>,
+        limit: Option<u64>,
+    ) -> String {
+        let item_ids = self.internal_store_item_ids(&store_id);
+        let total_count = item_ids.len();
+
+        let items: Vec<CatalogItem> = item_ids
+            .into_iter()
+            .skip(from_index.unwrap_or(0) as usize)
+            .take(limit.unwrap_or(total_count as u64) as usize)
+            .map(|item_id| {
+                let metadata =
+                    self.internal_get_item_metadata(&store_and_item_id(&store_id, &item_id));
+                CatalogItem {
+                    id: item_id,
+                    name: metadata.name,
+                    price: metadata.price,
+                    img_url: metadata.img_url,
+                }
+            })
+            .collect();
+
+        json!({ "items": items, "total_count": total_count }).to_string()
+    }
+
+    /// Retrieve the amount a buyer spent per store, highest first
+    pub fn get_buyer_store_spending(&self, account_id: AccountId) -> Vec<(StoreId, U128)> {
+        let purchases = match self.buyer_purchases.get(&account_id) {
+            Some(purchases) => purchases,
+            None => return vec![],
+        };
+
+        // store ids contain the delimiter, so try every prefix of the key as a store
+        let mut spending: Vec<(StoreId, U128)> = vec![];
+        for storeanditem_id in purchases.iter() {
+            for (index, _) in storeanditem_id.match_indices(DELIMETER) {
+                let store_id = match AccountId::try_from(storeanditem_id[..index].to_string()) {
+                    Ok(store_id) => store_id,
+                    Err(_) => continue,
+                };
+                if spending.iter().any(|(id, _)| *id == store_id) {
+                    continue;
+                }
+                if let Some(spent) = self
+                    .buyer_store_spending
+                    .get(&(account_id.clone(), store_id.clone()))
+                {
+                    spending.push((store_id, U128(spent)));
+                }
+            }
+        }
+
+        spending.sort_by(|a, b| b.1 .0.cmp(&a.1 .0));
+        spending
+    }
+
+    /// Start or update a store's referral program
+    pub fn create_referral_program(
+        &mut self,
+        store_id: AccountId,
+        referral_reward_bps: u16,
+        referred_discount_bps: u16,
+    ) {
+        self.assert_store_owner(&store_id);
+        require!(
+            referral_reward_bps as u32 + referred_discount_bps as u32 <= 10_000,
+            "StoreHub: referral rates can't exceed 10000 bps"
+        );
+
+        let program = ReferralProgram {
+            store_id: store_id.clone(),
+            referral_reward_bps,
+            referred_discount_bps,
+            active: true,
+        };
+        self.referral_programs.insert(&store_id, &program);
+    }
+
+    /// End a store's referral program
+    pub fn end_referral_program(&mut self, store_id: AccountId) {
+        self.assert_store_owner(&store_id);
+
+        let mut program = self.internal_get_referral_program(&store_id);
+        program.active = false;
+        self.referral_programs.insert(&store_id, &program);
+    }
+
+    /// Retrieve a store's referral program
+    pub fn get_referral_program(&self, store_id: AccountId) -> Option<ReferralProgram> {
+        self.referral_programs.get(&store_id)
+    }
+
+    /// Retrieve the total rewards an account earned from referrals
+    pub fn get_referral_earnings(&self, account_id: AccountId) -> U128 {
+        U128(self.referral_earnings.get(&account_id).unwrap_or(0))
+    }
+
+    /// Retrieve a store's logs, new items and sales, most recent first
+    pub fn get_store_activity_feed(
+        &self,
+        store_id: AccountId,
+        from_index: Option<u64>,
+        limit: Option<u64>,
+    ) -> Vec<ActivityEvent> {
+        let item_prefix = format!("{}{}", store_id, DELIMETER);
+        let mut events: Vec<(u64, ActivityEvent)> = vec![];
+
+        for log in self
+            .audit_logs
+            .iter()
+            .filter(|log| is_store_entity(&log.entity, &store_id))
+        {
+            let timestamp = log.timestamp;
+            let item_id = log.entity.strip_prefix(&item_prefix);
+            let event = match item_id {
+                Some(item_id) if log.action == "buy" => {
+                    let extra: serde_json::Value = serde_json::from_str(&log.extra)
+                        .unwrap_or_else(|_| panic_with(StoreHubError::InternalError));
+                    ActivityEvent::Sale {
+                        item_id: item_id.to_string(),
+                        buyer: log
+                            .actor
+                            .parse()
+                            .unwrap_or_else(|_| panic_with(StoreHubError::InternalError)),
+                        price: U128(json_amount(&extra["paid"])),
+                        timestamp,
+                    }
+                }
+                _ => ActivityEvent::Log(log),
+            };
+            events.push((timestamp, event));
+        }
+
+        for item_id in self.internal_store_item_ids(&store_id) {
+            if let Some(timestamp) = self
+                .item_created_at
+                .get(&store_and_item_id(&store_id, &item_id))
+            {
+                events.push((timestamp, ActivityEvent::NewItem { item_id, timestamp }));
+            }
+        }
+
+        // stable, so events of the same block keep their recording order, reversed
+        events.reverse();
+        events.sort_by(|a, b| b.0.cmp(&a.0));
+
+        let limit = limit.unwrap_or(events.len() as u64);
+        events
+            .into_iter()
+            .skip(from_index.unwrap_or(0) as usize)
+            .take(limit as usize)
+            .map(|(_, event)| event)
+            .collect()
+    }
+
+    /// Set the terms buyers must acknowledge before buying from a store
+    /// (e.g. a URL or a hash of the text), `None` removing them
+    pub fn set_store_acceptance_terms(&mut self, store_id: AccountId, terms: Option<String>) {
+        self.assert_store_owner(&store_id);
+        match terms {
+            Some(terms) => {
+                require!(!terms.is_empty(), "StoreHub: terms can't be empty");
+                self.store_terms.insert(&store_id, &terms);
+            }
+            None => {
+                self.store_terms.remove(&store_id);
+            }
+        }
+    }
+
+    /// Retrieve the terms buyers must acknowledge before buying from a store
+    pub fn get_store_acceptance_terms(&self, store_id: AccountId) -> Option<String> {
+        self.store_terms.get(&store_id)
+    }
+
+    /// Acknowledge a store's terms, allowing the signer to buy from it
+    pub fn acknowledge_store_terms(&mut self, store_id: AccountId) {
+        require!(
+            self.store_terms.get(&store_id).is_some(),
+            "StoreHub: store has no terms"
+        );
+        self.terms_acceptances.insert(
+            &(store_id, env::signer_account_id()),
+            &env::block_timestamp(),
+        );
+    }
+
+    /// Check if an account acknowledged a store's terms
+    pub fn has_accepted_terms(&self, store_id: AccountId, account_id: AccountId) -> bool {
+        self.terms_acceptances.contains_key(&(store_id, account_id))
+    }
+
+    /// Restrict an item to buyers who attested to being of age
+    pub fn set_item_age_restricted(
+        &mut self,
+        item_id: ItemId,
+        store_id: AccountId,
+        restricted: bool,
+    ) {
+        self.assert_store_owner(&store_id);
+
+        let storeanditem_id = store_and_item_id(&store_id, &item_id);
+        self.internal_get_item_metadata(&storeanditem_id);
+        if restricted {
+            self.age_restricted_items.insert(&storeanditem_id);
+        } else {
+            self.age_restricted_items.remove(&storeanditem_id);
+        }
+    }
+
+    /// Check if an item is restricted to buyers who attested to being of age
+    pub fn is_item_age_restricted(&self, item_id: ItemId, store_id: AccountId) -> bool {
+        self.age_restricted_items
+            .contains(&store_and_item_id(&store_id, &item_id))
+    }
+
+    /// Attest that the signer is at least `minimum_age` years old.
+    /// The attestation is self-declared and isn't verified
+    pub fn attest_age(&mut self, minimum_age: u8) {
+        self.age_attestations.insert(
+            &env::signer_account_id(),
+            &(minimum_age, env::block_timestamp()),
+        );
+    }
+
+    /// Check if an account attested to being at least `required_age` years old
+    pub fn has_attested_age(&self, account_id: AccountId, required_age: u8) -> bool {
+        self.age_attestations
+            .get(&account_id)
+            .map_or(false, |(attested_age, _)| attested_age >= required_age)
+    }
+
+    /// Raise an item's price by `price_increment_bps` after each purchase, capped at `max_price`
+    pub fn set_dynamic_pricing(
+        &mut self,
+        item_id: ItemId,
+        store_id: AccountId,
+        price_increment_bps: u16,
+        max_price: Option<U128>,
+    ) {
+        self.assert_store_owner(&store_id);
+        require!(
+            price_increment_bps <= 10_000,
+            "StoreHub: price increment can't exceed 10000 bps"
+        );
+
+        let storeanditem_id = store_and_item_id(&store_id, &item_id);
+        self.internal_get_item_metadata(&storeanditem_id);
+        let config = DynamicPricingConfig {
+            store_id,
+            item_id,
+            price_increment_bps,
+            max_price,
+        };
+        self.dynamic_pricing.insert(&storeanditem_id, &config);
+    }
+
+    /// Stop raising an item's price after each purchase
+    pub fn remove_dynamic_pricing(&mut self, item_id: ItemId, store_id: AccountId) {
+        self.assert_store_owner(&store_id);
+        self.dynamic_pricing
+            .remove(&store_and_item_id(&store_id, &item_id));
+    }
+
+    /// Retrieve how an item's price rises after each purchase
+    pub fn get_dynamic_pricing(
+        &self,
+        item_id: ItemId,
+        store_id: AccountId,
+    ) -> Option<DynamicPricingConfig> {
+        self.dynamic_pricing
+            .get(&store_and_item_id(&store_id, &item_id))
+    }
+
+    /// Post a notice to every front-end, returning its id. Expired
+    /// announcements are dropped to make room for new ones
+    pub fn post_announcement(
+        &mut self,
+        title: String,
+        body: String,
+        expires_at: Option<u64>,
+    ) -> String {
+        self.assert_overseer();
+        self.internal_post_announcement(title, body, expires_at)
+    }
+
+    /// Delete a platform announcement
+    pub fn delete_announcement(&mut self, id: String) {
+        self.assert_overseer();
+
+        let index = self
+            .platform_announcements
+            .iter()
+            .position(|announcement| announcement.id == id)
+            .unwrap_or_else(|| panic_with(StoreHubError::AnnouncementNotFound));
+        self.platform_announcements.remove(index);
+    }
+
+    /// Retrieve the platform announcements that haven't expired
+    pub fn get_active_announcements(&self) -> Vec<PlatformAnnouncement> {
+        let now = env::block_timestamp();
+        self.platform_announcements
+            .iter()
+            .filter(|announcement| {
+                announcement
+                    .expires_at
+                    .map_or(true, |expires_at| expires_at > now)
+            })
+            .cloned()
+            .collect()
+    }
+
+    /// Stake the attached deposit to vouch for a store
+    #[payable]
+    pub fn stake_for_store(&mut self, store_id: AccountId) {
+        if !self.all_stores.contains(&store_id) {
+            panic_with(StoreHubError::StoreNotFound)
+        }
+        let deposit = env::attached_deposit();
+        require!(deposit > 0, "StoreHub: stake can't be zero");
+
+        let staker_id = env::predecessor_account_id();
+        let mut stakes = self.store_stakes.get(&store_id).unwrap_or_else(|| {
+            UnorderedMap::new(StorageKey::StoreStakesInner {
+                store_hash: env::sha256(store_id.as_bytes()),
+            })
+        });
+        let stake = stakes.get(&staker_id).unwrap_or(0);
+        stakes.insert(&staker_id, &(stake + deposit));
+        self.store_stakes.insert(&store_id, &stakes);
+    }
+
+    /// Withdraw part of the caller's stake on a store
+    pub fn unstake_from_store(&mut self, store_id: AccountId, amount: U128) {
+        let staker_id = env::predecessor_account_id();
+        let stake = self.get_staker_stake(store_id.clone(), staker_id.clone()).0;
+        require!(amount.0 > 0, "StoreHub: amount can't be zero");
+        require!(amount.0 <= stake, "StoreHub: insufficient stake");
+
+        let mut stakes = self
+            .store_stakes
+            .get(&store_id)
+            .unwrap_or_else(|| panic_with(StoreHubError::InternalError));
+
+        if amount.0 == stake {
+            stakes.remove(&staker_id);
+        } else {
+            stakes.insert(&staker_id, &(stake - amount.0));
+        }
+        self.store_stakes.insert(&store_id, &stakes);
+
+        Promise::new(staker_id).transfer(amount.0);
+    }
+
+    /// Retrieve the total staked on a store
+    pub fn get_store_total_stake(&self, store_id: AccountId) -> U128 {
+        let total = self
+            .store_stakes
+            .get(&store_id)
+            .map_or(0, |stakes| stakes.values().sum());
+        U128(total)
+    }
+
+    /// Retrieve an account's stake on a store
+    pub fn get_staker_stake(&self, store_id: AccountId, account_id: AccountId) -> U128 {
+        let stake = self
+            .store_stakes
+            .get(&store_id)
+            .and_then(|stakes| stakes.get(&account_id))
+            .unwrap_or(0);
+        U128(stake)
+    }
+
+    /// Join the waitlist of an item the store already sold
+    pub fn join_item_waitlist(&mut self, item_id: ItemId, store_id: AccountId) {
+        let storeanditem_id = store_and_item_id(&store_id, &item_id);
+        let metadata = self.internal_get_item_metadata(&storeanditem_id);
+        require!(metadata.owner != store_id, "StoreHub: item is in stock");
+
+        let account_id = env::signer_account_id();
+        let mut waitlist = self
+            .item_waitlists
+            .get(&storeanditem_id)
+            .unwrap_or_default();
+        require!(
+            !waitlist.contains(&account_id),
+            "StoreHub: already on waitlist"
+        );
+        waitlist.push(account_id);
+        self.item_waitlists.insert(&storeanditem_id, &waitlist);
+    }
+
+    /// Leave the waitlist of an item
+    pub fn leave_item_waitlist(&mut self, item_id: ItemId, store_id: AccountId) {
+        let storeanditem_id = store_and_item_id(&store_id, &item_id);
+        let account_id = env::signer_account_id();
+        let mut waitlist = self
+            .item_waitlists
+            .get(&storeanditem_id)
+            .unwrap_or_default();
+        require!(waitlist.contains(&account_id), "StoreHub: not on waitlist");
+
+        waitlist.retain(|waiting| *waiting != account_id);
+        if waitlist.is_empty() {
+            self.item_waitlists.remove(&storeanditem_id);
+        } else {
+            self.item_waitlists.insert(&storeanditem_id, &waitlist);
+        }
+    }
+
+    /// Retrieve the accounts waiting for an item, in signup order
+    pub fn get_item_waitlist(&self, item_id: ItemId, store_id: AccountId) -> Vec<AccountId> {
+        self.item_waitlists
+            .get(&store_and_item_id(&store_id, &item_id))
+            .unwrap_or_default()
+    }
+
+    /// Retrieve an account's 1-based position on an item's waitlist, `None` if not on it
+    pub fn get_waitlist_position(
+        &self,
+        item_id: ItemId,
+        store_id: AccountId,
+        account_id: AccountId,
+    ) -> Option<u32> {
+        self.item_waitlists
+            .get(&store_and_item_id(&store_id, &item_id))
+            .and_then(|waitlist| waitlist.iter().position(|waiting| *waiting == account_id))
+            .map(|index| index as u32 + 1)
+    }
+
+    /// Allow an account to certify items
+    pub fn add_certified_issuer(&mut self, account_id: AccountId) {
+        self.assert_overseer();
+        self.certified_issuers.insert(&account_id);
+    }
+
+    /// Stop an account from certifying items, keeping the certificates it issued
+    pub fn remove_certified_issuer(&mut self, account_id: AccountId) {
+        self.assert_overseer();
+        self.certified_issuers.remove(&account_id);
+    }
+
+    /// Attach an authenticity certificate to an item
+    pub fn add_item_certificate(
+        &mut self,
+        item_id: ItemId,
+        store_id: AccountId,
+        certificate_type: String,
+        certificate_id: String,
+        expires_at: Option<u64>,
+        metadata_url: String,
+    ) {
+        let issuer = env::signer_account_id();
+        require!(
+            issuer == self.overseer_id || self.certified_issuers.contains(&issuer),
+            "StoreHub: not a certified issuer"
+        );
+
+        let storeanditem_id = store_and_item_id(&store_id, &item_id);
+        self.internal_get_item_metadata(&storeanditem_id);
+        let mut certificates = self
+            .item_certificates
+            .get(&storeanditem_id)
+            .unwrap_or_default();
+        require!(
+            certificates
+                .iter()
+                .all(|certificate| certificate.certificate_id != certificate_id),
+            "StoreHub: certificate already exists"
+        );
+
+        certificates.push(ItemCertificate {
+            issuer,
+            certificate_type,
+            certificate_id,
+            issued_at: env::block_timestamp(),
+            expires_at,
+            metadata_url,
+        });
+        self.item_certificates
+            .insert(&storeanditem_id, &certificates);
+    }
+
+    /// Revoke an item's certificate. Only its issuer or the overseer can revoke it
+    pub fn revoke_item_certificate(
+        &mut self,
+        item_id: ItemId,
+        store_id: AccountId,
+        certificate_id: String,
+    ) {
+        let storeanditem_id = store_and_item_id(&store_id, &item_id);
+        let mut certificates = self
+            .item_certificates
+            .get(&storeanditem_id)
+            .unwrap_or_default();
+        let index = certificates
+            .iter()
+            .position(|certificate| certificate.certificate_id == certificate_id)
+            .unwrap_or_else(|| panic_with(StoreHubError::CertificateNotFound));
+
+        let signer_id = env::signer_account_id();
+        if signer_id != self.overseer_id && signer_id != certificates[index].issuer {
+            panic_with(StoreHubError::AccessDenied)
+        }
+
+        certificates.remove(index);
+        if certificates.is_empty() {
+            self.item_certificates.remove(&storeanditem_id);
+        } else {
+            self.item_certificates
+                .insert(&storeanditem_id, &certificates);
+        }
+    }
+
+    /// Retrieve an item's certificates, including expired ones
+    pub fn get_item_certificates(
+        &self,
+        item_id: ItemId,
+        store_id: AccountId,
+    ) -> Vec<ItemCertificate> {
+        self.item_certificates
+            .get(&store_and_item_id(&store_id, &item_id))
+            .unwrap_or_default()
+    }
+
+    /// Check if an item holds a certificate that hasn't expired
+    pub fn is_item_certified(&self, item_id: ItemId, store_id: AccountId) -> bool {
+        let now = env::block_timestamp();
+        self.get_item_certificates(item_id, store_id)
+            .iter()
+            .any(|certificate| {
+                certificate
+                    .expires_at
+                    .map_or(true, |expires_at| expires_at > now)
+            })
+    }
+
+    /// Add a loyalty tier reached once an account's lifetime spending meets `min_lifetime_spend`
+    pub fn create_loyalty_tier(
+        &mut self,
+        name: String,
+        min_lifetime_spend: U128,
+        benefits: String,
+    ) {
+        self.assert_overseer();
+        require!(!name.is_empty(), "StoreHub: tier name can't be empty");
+        require!(
+            self.loyalty_tiers.iter().all(|tier| tier.name != name),
+            "StoreHub: loyalty tier already exists"
+        );
+
+        // tiers are kept ordered by threshold, ascending
+        let index = self
+            .loyalty_tiers
+            .iter()
+            .position(|tier| tier.min_lifetime_spend.0 > min_lifetime_spend.0)
+            .unwrap_or(self.loyalty_tiers.len());
+        self.loyalty_tiers.insert(
+            index,
+            LoyaltyTier {
+                name,
+                min_lifetime_spend,
+                benefits,
+            },
+        );
+    }
+
+    /// Retrieve the highest loyalty tier an account's lifetime spending qualifies for
+    pub fn get_loyalty_tier_for_account(&self, account_id: AccountId) -> Option<LoyaltyTier> {
+        let lifetime_spend = self.buyer_lifetime_spend.get(&account_id).unwrap_or(0);
+        self.loyalty_tiers
+            .iter()
+            .rev()
+            .find(|tier| tier.min_lifetime_spend.0 <= lifetime_spend)
+            .cloned()
+    }
+
+    /// Retrieve the benefits a loyalty tier unlocks
+    pub fn get_loyalty_tier_benefits(&self, name: String) -> Option<String> {
+        self.loyalty_tiers
+            .iter()
+            .find(|tier| tier.name == name)
+            .map(|tier| tier.benefits.clone())
+    }
+
+    /// Propose a partnership between two stores, returning its id.
+    /// Only an owner of `store_a` can propose it
+    pub fn propose_partnership(
+        &mut self,
+        store_a: AccountId,
+        store_b: AccountId,
+        cross_discount_bps: u16,
+    ) -> String {
+        self.assert_store_owner(&store_a);
+        require!(store_a != store_b, "StoreHub: can't partner with itself");
+        if !self.all_stores.contains(&store_b) {
+            panic_with(StoreHubError::StoreNotFound)
+        }
+        require!(
+            cross_discount_bps <= 10_000,
+            "StoreHub: discount can't exceed 10000 bps"
+        );
+
+        let partnership_id = format!("{}{}{}", store_a, DELIMETER, store_b);
+        let reverse_id = format!("{}{}{}", store_b, DELIMETER, store_a);
+        require!(
+            self.store_partnerships.get(&partnership_id).is_none()
+                && self.store_partnerships.get(&reverse_id).is_none(),
+            "StoreHub: partnership already exists"
+        );
+
+        let partnership = Partnership {
+            store_a,
+            store_b,
+            created_at: env::block_timestamp(),
+            cross_discount_bps,
+            accepted: false,
+        };
+        self.store_partnerships
+            .insert(&partnership_id, &partnership);
+
+        partnership_id
+    }
+
+    /// Accept a proposed partnership. Only an owner of `store_b` can accept it
+    pub fn accept_partnership(&mut self, partnership_id: String) {
+        let mut partnership = self.internal_get_partnership(&partnership_id);
+        self.assert_store_owner(&partnership.store_b);
+        require!(
+            !partnership.accepted,
+            "StoreHub: partnership already accepted"
+        );
+
+        partnership.accepted = true;
+        self.store_partnerships
+            .insert(&partnership_id, &partnership);
+
+        for store_id in [&partnership.store_a, &partnership.store_b] {
+            let mut partnership_ids = self
+                .accepted_partnerships_by_store
+                .get(store_id)
+                .unwrap_or_default();
+            partnership_ids.push(partnership_id.clone());
+            self.accepted_partnerships_by_store
+                .insert(store_id, &partnership_ids);
+        }
+    }
+
+    /// End a partnership, or withdraw a proposal. Callable by an owner of either store
+    pub fn dissolve_partnership(&mut self, partnership_id: String) {
+        let partnership = self.internal_get_partnership(&partnership_id);
+        let caller = env::predecessor_account_id();
+        let is_partner_owner = [&partnership.store_a, &partnership.store_b]
+            .iter()
+            .any(|store_id| self.internal_store_role(store_id, &caller) == Some(StoreRole::Owner));
+        if !is_partner_owner {
+            panic_with(StoreHubError::AccessDenied)
+        }
+
+        self.store_partnerships.remove(&partnership_id);
+
+        if partnership.accepted {
+            for store_id in [&partnership.store_a, &partnership.store_b] {
+                let mut partnership_ids = self
+                    .accepted_partnerships_by_store
+                    .get(store_id)
+                    .unwrap_or_default();
+                partnership_ids.retain(|id| *id != partnership_id);
+                self.accepted_partnerships_by_store
+                    .insert(store_id, &partnership_ids);
+            }
+        }
+    }
+
+    /// Retrieve a store's partnerships, including pending proposals
+    pub fn get_store_partnerships(&self, store_id: AccountId) -> Vec<Partnership> {
+        self.store_partnerships
+            .values()
+            .filter(|partnership| {
+                partnership.store_a == store_id || partnership.store_b == store_id
+            })
+            .collect()
+    }
+
+    /// Retrieve a store's item by its barcode
+    pub fn get_item_by_barcode(&self, upc: String, store_id: AccountId) -> Option<ItemMetadata> {
+        let storeanditem_id = self.barcode_index.get(&upc)?;
+        let prefix = format!("{}{}", store_id, DELIMETER);
+        storeanditem_id.strip_prefix(&prefix)?;
+
+        self.metadata_by_storeanditem_ids
+            .as_ref()
+            .and_then(|by_id| by_id.get(&storeanditem_id))
+    }
+
+    /// Check if an item is registered under a barcode
+    pub fn does_barcode_exist(&self, upc: String) -> bool {
+        self.barcode_index.get(&upc).is_some()
+    }
+
+    /// Set the fee paid into the treasury for each featured banner
+    pub fn set_banner_fee(&mut self, fee: U128) {
+        self.assert_overseer();
+        self.banner_fee = fee.0;
+    }
+
+    /// Retrieve the fee paid into the treasury for each featured banner
+    pub fn get_banner_fee(&self) -> U128 {
+        U128(self.banner_fee)
+    }
+
+    /// Feature a store on the homepage, paying the banner fee into the treasury.
+    /// Ended banners are dropped to make room for new ones
+    #[payable]
+    pub fn set_featured_banner(&mut self, banner: FeaturedBanner) {
+        self.assert_overseer();
+        require!(
+            env::attached_deposit() == self.banner_fee,
+            "StoreHub: deposit must equal the banner fee"
+        );
+        require!(
+            banner.starts_at < banner.ends_at,
+            "StoreHub: banner must end after it starts"
+        );
+        if !self.all_stores.contains(&banner.store_id) {
+            panic_with(StoreHubError::StoreNotFound)
+        }
+
+        let now = env::block_timestamp();
+        self.featured_banners
+            .retain(|featured| featured.ends_at > now);
+        let overlapping = self
+            .featured_banners
+            .iter()
+            .filter(|featured| {
+                featured.starts_at < banner.ends_at && banner.starts_at < featured.ends_at
+            })
+            .count();
+        require!(
+            overlapping < MAX_ACTIVE_BANNERS,
+            format!(
+                "StoreHub: cannot have more than {} active banners",
+                MAX_ACTIVE_BANNERS
+            )
+        );
+
+        self.treasury_balance += self.banner_fee;
+        self.featured_banners.push(banner);
+    }
+
+    /// Remove a store's featured banners
+    pub fn remove_featured_banner(&mut self, store_id: AccountId) {
+        self.assert_overseer();
+        self.featured_banners
+            .retain(|featured| featured.store_id != store_id);
+    }
+
+    /// Retrieve the featured banners shown at the current block
+    pub fn get_active_featured_banners(&self) -> Vec<FeaturedBanner> {
+        let now = env::block_timestamp();
+        self.featured_banners
+            .iter()
+            .filter(|featured| featured.starts_at <= now && now < featured.ends_at)
+            .cloned()
+            .collect()
+    }
+
+    /// Count a view of an item's video, at most once an hour per account
+    pub fn record_video_view(&mut self, item_id: ItemId, store_id: AccountId) {
+        let storeanditem_id = store_and_item_id(&store_id, &item_id);
+        let metadata = self.internal_get_item_metadata(&storeanditem_id);
+        require!(metadata.video_url.is_some(), "StoreHub: item has no video");
+
+        let now = env::block_timestamp();
+        let viewer_key = (env::signer_account_id(), storeanditem_id.clone());
+        if let Some(last_view) = self.last_video_views.get(&viewer_key) {
+            if now < last_view + VIDEO_VIEW_COOLDOWN {
+                return;
+            }
+        }
+        self.last_video_views.insert(&viewer_key, &now);
+
+        let views = self.item_video_views.get(&storeanditem_id).unwrap_or(0);
+        self.item_video_views.insert(&storeanditem_id, &(views + 1));
+    }
+
+    /// Retrieve the number of counted views of an item's video
+    pub fn get_item_video_views(&self, item_id: ItemId, store_id: AccountId) -> u64 {
+        self.item_video_views
+            .get(&store_and_item_id(&store_id, &item_id))
+            .unwrap_or(0)
+    }
+
+    /// Retrieve a store's items that have a video
+    pub fn get_items_with_video(&self, store_id: AccountId) -> Vec<(ItemId, ItemMetadata)> {
+        self.internal_store_item_ids(&store_id)
+            .into_iter()
+            .map(|item_id| {
+                let metadata =
+                    self.internal_get_item_metadata(&store_and_item_id(&store_id, &item_id));
+                (item_id, metadata)
+            })
+            .filter(|(_, metadata)| metadata.video_url.is_some())
+            .collect()
+    }
+
+    /// Allow items to link to their listings on a chain
+    pub fn approve_chain(&mut self, chain: String) {
+        self.assert_overseer();
+        self.approved_chains.insert(&chain);
+    }
+
+    /// Stop items from linking to a chain, keeping existing links
+    pub fn revoke_chain(&mut self, chain: String) {
+        self.assert_overseer();
+        self.approved_chains.remove(&chain);
+    }
+
+    /// Retrieve the chains items can link to
+    pub fn get_approved_chains(&self) -> Vec<String> {
+        self.approved_chains.to_vec()
+    }
+
+    /// Link an item to the same item listed on another chain
+    pub fn add_crosschain_link(
+        &mut self,
+        item_id: ItemId,
+        store_id: AccountId,
+        link: CrossChainLink,
+    ) {
+        self.assert_store_owner(&store_id);
+        require!(
+            self.approved_chains.contains(&link.chain),
+            "StoreHub: chain not approved"
+        );
+
+        let storeanditem_id = store_and_item_id(&store_id, &item_id);
+        self.internal_get_item_metadata(&storeanditem_id);
+        let mut links = self
+            .item_crosschain_links
+            .get(&storeanditem_id)
+            .unwrap_or_default();
+        require!(
+            links.len() < MAX_CROSSCHAIN_LINKS,
+            format!(
+                "StoreHub: cannot have more than {} cross-chain links",
+                MAX_CROSSCHAIN_LINKS
+            )
+        );
+        require!(
+            !links.contains(&link),
+            "StoreHub: cross-chain link already exists"
+        );
+
+        links.push(link);
+        self.item_crosschain_links.insert(&storeanditem_id, &links);
+    }
+
+    /// Remove an item's link to another chain
+    pub fn remove_crosschain_link(
+        &mut self,
+        item_id: ItemId,
+        store_id: AccountId,
+        link: CrossChainLink,
+    ) {
+        self.assert_store_owner(&store_id);
+
+        let storeanditem_id = store_and_item_id(&store_id, &item_id);
+        let mut links = self
+            .item_crosschain_links
+            .get(&storeanditem_id)
+            .unwrap_or_default();
+        require!(
+            links.contains(&link),
+            "StoreHub: cross-chain link doesn't exist"
+        );
+
+        links.retain(|existing| *existing != link);
+        if links.is_empty() {
+            self.item_crosschain_links.remove(&storeanditem_id);
+        } else {
+            self.item_crosschain_links.insert(&storeanditem_id, &links);
+        }
+    }
+
+    /// Retrieve an item's links to other chains
+    pub fn get_crosschain_links(
+        &self,
+        item_id: ItemId,
+        store_id: AccountId,
+    ) -> Vec<CrossChainLink> {
+        self.item_crosschain_links
+            .get(&store_and_item_id(&store_id, &item_id))
+            .unwrap_or_default()
+    }
+
+    /// Reassign an item to a new owner, e.g. when the owner's account was compromised.
+    /// The reason is logged and posted as a platform announcement for transparency
+    pub fn overseer_transfer_item_ownership(
+        &mut self,
+        item_id: ItemId,
+        store_id: AccountId,
+        new_owner: AccountId,
+        reason: String,
+    ) {
+        self.assert_overseer();
+        require!(!reason.is_empty(), "StoreHub: reason can't be empty");
+
+        let storeanditem_id = store_and_item_id(&store_id, &item_id);
+        let old_owner = self.internal_get_item_metadata(&storeanditem_id).owner;
+        self.internal_transfer_item(&store_id, &item_id, &new_owner);
+
+        self.internal_post_announcement(
+            format!("Ownership of {} reassigned", storeanditem_id),
+            reason.clone(),
+            None,
+        );
+
+        let extra = json!({
+            "old_owner": old_owner,
+            "new_owner": new_owner,
+            "reason": reason,
+        });
+        self.add_log_internal(
+            "admin_ownership_override".to_string(),
+            env::predecessor_account_id().to_string(),
+            storeanditem_id,
+            extra.to_string(),
+        );
+    }
+
+    /// Replace the search tags of an item, tags being matched case-insensitively
+    pub fn set_item_tags(&mut self, item_id: ItemId, store_id: AccountId, tags: Vec<String>) {
+        self.assert_store_manager(&store_id);
+
+        let storeanditem_id = store_and_item_id(&store_id, &item_id);
+        self.internal_get_item_metadata(&storeanditem_id);
+
+        let mut tags: Vec<String> = tags.iter().map(|tag| tag.trim().to_lowercase()).collect();
+        tags.sort();
+        tags.dedup();
+        require!(
+            tags.iter().all(|tag| !tag.is_empty()),
+            "StoreHub: tag can't be empty"
+        );
+        require!(
+            tags.len() <= MAX_ITEM_TAGS,
+            format!("StoreHub: cannot have more than {} tags", MAX_ITEM_TAGS)
+        );
+
+        self.internal_untag_item(&storeanditem_id);
+        for tag in tags.iter() {
+            let mut tagged = self.tag_to_items.get(tag).unwrap_or_else(|| {
+                UnorderedSet::new(StorageKey::TagIndexInner {
+                    tag_hash: env::sha256(tag.as_bytes()),
+                })
+            });
+            tagged.insert(&storeanditem_id);
+            self.tag_to_items.insert(tag, &tagged);
+        }
+        if !tags.is_empty() {
+            self.item_tags.insert(&storeanditem_id, &tags);
+        }
+    }
+
+    /// Retrieve the search tags of an item
+    pub fn get_item_tags(&self, item_id: ItemId, store_id: AccountId) -> Vec<String> {
+        self.item_tags
+            .get(&store_and_item_id(&store_id, &item_id))
+            .unwrap_or_default()
+    }
+
+    /// Retrieve items tagged with all of `tags` when `match_all` is set,
+    /// or with any of them otherwise, along with their metadata
+    pub fn search_items_by_tags(
+        &self,
+        tags: Vec<String>,
+        match_all: bool,
+        from_index: Option<u64>,
+        limit: Option<u64>,
+    ) -> Vec<(StoreAndItemIds, ItemMetadata)> {
+        let tag_sets: Vec<Option<UnorderedSet<StoreAndItemIds>>> = tags
+            .iter()
+            .map(|tag| self.tag_to_items.get(&tag.trim().to_lowercase()))
+            .collect();
+
+        let mut matches: Vec<StoreAndItemIds> = if match_all {
+            match tag_sets.split_first() {
+                Some((Some(first), rest)) if rest.iter().all(Option::is_some) => first
+                    .iter()
+                    .filter(|storeanditem_id| {
+                        rest.iter()
+                            .flatten()
+                            .all(|tagged| tagged.contains(storeanditem_id))
+                    })
+                    .collect(),
+                _ => vec![],
+            }
+        } else {
+            tag_sets
+                .iter()
+                .flatten()
+                .flat_map(|tagged| tagged.iter())
+                .collect()
+        };
+        matches.sort();
+        matches.dedup();
+
+        matches
+            .into_iter()
+            .skip(from_index.unwrap_or(0) as usize)
+            .take(limit.map_or(usize::MAX, |limit| limit as usize))
+            .map(|storeanditem_id| {
+                let metadata = self.internal_get_item_metadata(&storeanditem_id);
+                (storeanditem_id, metadata)
+            })
+            .collect()
+    }
+
+    /// Set how many yoctoNEAR one unit of a fungible token is worth
+    pub fn set_ft_exchange_rate(&mut self, ft_account_id: AccountId, rate: U128) {
+        self.assert_overseer();
+        require!(
+            self.approved_ft_token_ids.contains(&ft_account_id),
+            "StoreHub: fungible token not approved"
+        );
+        require!(rate.0 > 0, "StoreHub: exchange rate must be positive");
+
+        self.ft_exchange_rates.insert(&ft_account_id, &rate);
+    }
+
+    /// Retrieve how many yoctoNEAR one unit of a fungible token is worth
+    pub fn get_ft_exchange_rate(&self, ft_account_id: AccountId) -> Option<U128> {
+        self.ft_exchange_rates.get(&ft_account_id)
+    }
+
+    /// Retrieve an item's price in yoctoNEAR, converting FT-denominated prices
+    pub fn get_item_price_in_near(&self, item_id: ItemId, store_id: AccountId) -> U128 {
+        let storeanditem_id = store_and_item_id(&store_id, &item_id);
+        let price = self.internal_get_item_metadata(&storeanditem_id).price.0;
+
+        match self.item_currency.get(&storeanditem_id).flatten() {
+            None => U128(price),
+            Some(ft_account_id) => {
+                let rate = self
+                    .ft_exchange_rates
+                    .get(&ft_account_id)
+                    .unwrap_or_else(|| {
+                        panic_with(StoreHubError::ExchangeRateNotFound(ft_account_id))
+                    });
+                // saturating keeps absurd rates sortable instead of failing the view
+                U128(price.saturating_mul(rate.0))
+            }
+        }
+    }
+
+    /// Require `threshold` of `accounts` to approve critical overseer actions
+    /// (`set_protocol_fee`, `add_ft`, `withdraw_treasury`) from now on. Can only be set up once
+    pub fn setup_overseer_multisig(&mut self, accounts: Vec<AccountId>, threshold: u32) {
+        self.assert_overseer();
+        require!(
+            self.overseer_multisig.is_none(),
+            "StoreHub: multisig already set up"
+        );
+
+        let mut accounts = accounts;
+        accounts.sort();
+        accounts.dedup();
+        require!(
+            threshold > 0 && threshold as usize <= accounts.len(),
+            "StoreHub: invalid multisig threshold"
+        );
+
+        self.overseer_multisig = Some(OverseerMultisig {
+            accounts,
+            threshold,
+            pending_actions: UnorderedMap::new(StorageKey::AdminActions),
+            next_action_id: 0,
+        });
+    }
+
+    /// Retrieve the multisig members and how many of them must approve an action
+    pub fn get_overseer_multisig(&self) -> Option<(Vec<AccountId>, u32)> {
+        self.overseer_multisig
+            .as_ref()
+            .map(|multisig| (multisig.accounts.clone(), multisig.threshold))
+    }
+
+    /// Propose a critical overseer action, approved by the proposer,
+    /// returning its id. Only callable by multisig members
+    pub fn propose_admin_action(&mut self, action_type: String, action_data: String) -> String {
+        require!(
+            matches!(
+                action_type.as_str(),
+                "set_protocol_fee" | "add_ft" | "withdraw_treasury"
+            ),
+            "StoreHub: unsupported admin action"
+        );
+        let valid_data = match action_type.as_str() {
+            "set_protocol_fee" => action_data.parse::<u16>().is_ok(),
+            "withdraw_treasury" => action_data.parse::<u128>().is_ok(),
+            _ => action_data.parse::<AccountId>().is_ok(),
+        };
+        require!(valid_data, "StoreHub: invalid admin action data");
+
+        self.internal_propose_admin_action(action_type, action_data)
+    }
+
+    /// Approve a pending admin action, executing it once enough members approved.
+    /// Only callable by multisig members
+    pub fn approve_admin_action(&mut self, action_id: String) {
+        let signer_id = env::signer_account_id();
+        let mut multisig = self.internal_take_overseer_multisig();
+        require!(
+            multisig.accounts.contains(&signer_id),
+            "StoreHub: not a multisig member"
+        );
+        let mut action = multisig
+            .pending_actions
+            .get(&action_id)
+            .unwrap_or_else(|| panic_with(StoreHubError::AdminActionNotFound));
+        require!(
+            !action.approvals.contains(&signer_id),
+            "StoreHub: already approved"
+        );
+
+        action.approvals.push(signer_id);
+        if action.approvals.len() < multisig.threshold as usize {
+            multisig.pending_actions.insert(&action_id, &action);
+            self.overseer_multisig = Some(multisig);
+            return;
+        }
+
+        multisig.pending_actions.remove(&action_id);
+        self.overseer_multisig = Some(multisig);
+        self.internal_execute_admin_action(&action);
+    }
+
+    /// Retrieve the admin actions awaiting approval
+    pub fn get_pending_admin_actions(&self) -> Vec<MultiSigAction> {
+        self.overseer_multisig.as_ref().map_or(vec![], |multisig| {
+            multisig.pending_actions.values().collect()
+        })
+    }
+
+    /// Hand a store over to a DAO contract, which becomes its only owner
+    /// and the only account allowed to manage it. Only callable by the store's sole owner
+    pub fn assign_store_to_dao(&mut self, store_id: AccountId, dao_contract: AccountId) {
+        self.assert_store_owner(&store_id);
+        let owner_id = env::predecessor_account_id();
+        require!(
+            self.get_owners_by_store_id(store_id.clone()) == vec![owner_id.clone()],
+            "StoreHub: store has several owners"
+        );
+
+        if let Some(owners_per_store_id) = &mut self.owners_per_store_id {
+            let mut owner_ids = owners_per_store_id
+                .get(&store_id)
+                .unwrap_or_else(|| panic_with(StoreHubError::InternalError));
+            owner_ids.remove(&owner_id);
+            owner_ids.insert(&dao_contract);
+            owners_per_store_id.insert(&store_id, &owner_ids);
+        }
+        if let Some(stores_by_account_id) = &mut self.stores_by_account_id {
+            if let Some(mut store_ids) = stores_by_account_id.get(&owner_id) {
+                store_ids.remove(&store_id);
+                stores_by_account_id.insert(&owner_id, &store_ids);
+            }
+            let mut store_ids = stores_by_account_id.get(&dao_contract).unwrap_or_else(|| {
+                UnorderedSet::new(StorageKey::StoresByAccountIdInner {
+                    account_hash: env::sha256(dao_contract.as_bytes()),
+                })
+            });
+            store_ids.insert(&store_id);
+            stores_by_account_id.insert(&dao_contract, &store_ids);
+        }
+        self.dao_controlled_stores.insert(&store_id, &dao_contract);
+
+        self.add_log_internal(
+            "assign_store_to_dao".to_string(),
+            owner_id.to_string(),
+            store_id.to_string(),
+            json!({ "dao_contract": dao_contract }).to_string(),
+        );
+    }
+
+    /// Check if a store is controlled by a DAO contract
+    pub fn is_dao_controlled(&self, store_id: AccountId) -> bool {
+        self.dao_controlled_stores.contains_key(&store_id)
+    }
+
+    /// Retrieve a summary of a store's owners, items, sales and ratings.
+    /// Stores created before creation times were recorded have none
+    pub fn generate_store_report(&self, store_id: AccountId) -> StoreReport {
+        if !self.all_stores.contains(&store_id) {
+            panic_with(StoreHubError::StoreNotFound)
+        }
+
+        let prices: Vec<u128> = self
+            .internal_store_item_ids(&store_id)
+            .iter()
+            .map(|item_id| {
+                self.internal_get_item_metadata(&store_and_item_id(&store_id, item_id))
+                    .price
+                    .0
+            })
+            .collect();
+        let average_item_price = match prices.len() {
+            0 => 0,
+            count => prices.iter().sum::<u128>() / count as u128,
+        };
+        let latest_sale_timestamp = self
+            .audit_logs
+            .iter()
+            .filter(|log| log.action == "buy" && is_store_entity(&log.entity, &store_id))
+            .map(|log| log.timestamp)
+            .max();
+
+        StoreReport {
+            owner_count: self.get_owners_by_store_id(store_id.clone()).len() as u64,
+            item_count: prices.len() as u64,
+            total_sales: self
+                .internal_store_items_with_sales(&store_id)
+                .iter()
+                .map(|(_, count)| count)
+                .sum(),
+            total_revenue: self.get_store_total_revenue(store_id.clone()),
+            average_item_price: U128(average_item_price),
+            creation_timestamp: self.store_created_at.get(&store_id),
+            average_rating: self
+                .get_store_average_rating(store_id.clone())
+                .map(|average| average.round() as u8),
+            latest_sale_timestamp,
+            store_id,
+        }
+    }
+
+    /// Retrieve when a store was created, `None` for stores created before this was recorded
+    pub fn get_store_created_at(&self, store_id: AccountId) -> Option<u64> {
+        self.store_created_at.get(&store_id)
+    }
+
+    /// Retrieve stores along with their creation time, newest first.
+    /// Stores created before creation times were recorded are left out
+    pub fn get_stores_by_age(
+        &self,
+        from_index: Option<u64>,
+        limit: Option<u64>,
+    ) -> Vec<(AccountId, u64)> {
+        let mut stores: Vec<(AccountId, u64)> = self
+            .all_stores
+            .iter()
+            .filter_map(|store_id| {
+                let created_at = self.store_created_at.get(&store_id)?;
+                Some((store_id, created_at))
+            })
+            .collect();
+        stores.sort_by(|a, b| b.1.cmp(&a.1));
+
+        let limit = limit.unwrap_or(stores.len() as u64);
+        stores
+            .into_iter()
+            .skip(from_index.unwrap_or(0) as usize)
+            .take(limit as usize)
+            .collect()
+    }
+
+    /// Cap how many times an item can ever be sold. The cap can't be changed once set
+    pub fn set_item_supply_cap(&mut self, item_id: ItemId, store_id: AccountId, cap: u64) {
+        self.assert_store_owner(&store_id);
+        require!(cap > 0, "StoreHub: supply cap must be positive");
+
+        let storeanditem_id = store_and_item_id(&store_id, &item_id);
+        self.internal_get_item_metadata(&storeanditem_id);
+        require!(
+            self.item_supply_caps.get(&storeanditem_id).is_none(),
+            "StoreHub: supply cap already set"
+        );
+        self.item_supply_caps.insert(&storeanditem_id, &cap);
+    }
+
+    /// Retrieve how many times an item can ever be sold, `None` if uncapped
+    pub fn get_item_supply_cap(&self, item_id: ItemId, store_id: AccountId) -> Option<u64> {
+        self.item_supply_caps
+            .get(&store_and_item_id(&store_id, &item_id))
+    }
+
+    /// Retrieve how many more times an item can be sold, `u64::MAX` if uncapped
+    pub fn get_item_remaining_supply(&self, item_id: ItemId, store_id: AccountId) -> u64 {
+        let storeanditem_id = store_and_item_id(&store_id, &item_id);
+        match self.item_supply_caps.get(&storeanditem_id) {
+            Some(cap) => {
+                let total_sold = self.item_purchase_counts.get(&storeanditem_id).unwrap_or(0);
+                cap.saturating_sub(total_sold)
+            }
+            None => u64::MAX,
+        }
+    }
+
+    /// Donate a share of an item's sales to a charity, in basis points, 0 removing it
+    pub fn set_item_charity_split(
+        &mut self,
+        item_id: ItemId,
+        store_id: AccountId,
+        charity: AccountId,
+        split_bps: u16,
+    ) {
+        self.assert_store_owner(&store_id);
+        require!(
+            split_bps <= MAX_CHARITY_SPLIT_BPS,
+            "StoreHub: charity split too high"
+        );
+
+        let storeanditem_id = store_and_item_id(&store_id, &item_id);
+        self.internal_get_item_metadata(&storeanditem_id);
+        if split_bps == 0 {
+            self.item_charity_splits.remove(&storeanditem_id);
+        } else {
+            self.item_charity_splits
+                .insert(&storeanditem_id, &(charity, split_bps));
+        }
+    }
+
+    /// Retrieve the charity an item donates to and its share, in basis points
+    pub fn get_item_charity_split(
+        &self,
+        item_id: ItemId,
+        store_id: AccountId,
+    ) -> Option<(AccountId, u16)> {
+        self.item_charity_splits
+            .get(&store_and_item_id(&store_id, &item_id))
+    }
+
+    /// Retrieve the total donated to charity from an item's sales
+    pub fn get_total_donated_by_item(&self, item_id: ItemId, store_id: AccountId) -> U128 {
+        U128(
+            self.item_charity_donations
+                .get(&store_and_item_id(&store_id, &item_id))
+                .unwrap_or(0),
+        )
+    }
+
+    /// Offer an item for rent at an hourly price, `None` withdrawing the offer
+    pub fn set_item_rental_rate(
+        &mut self,
+        item_id: ItemId,
+        store_id: AccountId,
+        price_per_hour: Option<U128>,
+    ) {
+        self.assert_store_owner(&store_id);
+
+        let storeanditem_id = store_and_item_id(&store_id, &item_id);
+        self.internal_get_item_metadata(&storeanditem_id);
+        match price_per_hour {
+            Some(price_per_hour) => {
+                require!(
+                    price_per_hour.0 > 0,
+                    "StoreHub: rental price must be positive"
+                );
+                self.item_rental_rates
+                    .insert(&storeanditem_id, &price_per_hour);
+            }
+            None => {
+                self.item_rental_rates.remove(&storeanditem_id);
+            }
+        }
+    }
+
+    /// Retrieve an item's hourly rental price, `None` if it isn't for rent
+    pub fn get_item_rental_rate(&self, item_id: ItemId, store_id: AccountId) -> Option<U128> {
+        self.item_rental_rates
+            .get(&store_and_item_id(&store_id, &item_id))
+    }
+
+    /// Rent a store-owned item for `duration_seconds`, up to 365 days, returning the rental's id.
+    /// The item's owner doesn't change; the rent is credited to the store's balance
+    #[payable]
+    pub fn rent_item(
+        &mut self,
+        item_id: ItemId,
+        store_id: AccountId,
+        duration_seconds: u64,
+    ) -> String {
+        require!(duration_seconds > 0, "StoreHub: duration must be positive");
+        require!(
+            duration_seconds <= MAX_RENTAL_DURATION,
+            "StoreHub: rental duration too long"
+        );
+
+        let storeanditem_id = store_and_item_id(&store_id, &item_id);
+        let metadata = self.internal_get_item_metadata(&storeanditem_id);
+        require!(metadata.owner == store_id, "StoreHub: item already sold");
+        let price_per_hour = self
+            .item_rental_rates
+            .get(&storeanditem_id)
+            .unwrap_or_else(|| panic_with(StoreHubError::ItemNotForRent));
+
+        let rental_price = price_per_hour.0.saturating_mul(duration_seconds as u128) / 3_600;
+        let deposit = env::attached_deposit();
+        if deposit < rental_price {
+            panic_with(StoreHubError::InsufficientDeposit(deposit, rental_price))
+        }
+
+        let renter = env::signer_account_id();
+        let rental_id = format!(
+            "{}{}{}{}{}",
+            storeanditem_id,
+            DELIMETER,
+            renter,
+            DELIMETER,
+            env::block_timestamp()
+        );
+        require!(
+            self.active_rentals.get(&rental_id).is_none(),
+            "StoreHub: rental already exists"
+        );
+
+        self.internal_credit_store_balance(&store_id, deposit);
+        self.internal_add_store_revenue(&store_id, deposit);
+
+        let renter_key = (storeanditem_id, renter.clone());
+        let mut rental_ids = self
+            .rentals_by_item_and_renter
+            .get(&renter_key)
+            .unwrap_or_default();
+        rental_ids.push(rental_id.clone());
+        self.rentals_by_item_and_renter
+            .insert(&renter_key, &rental_ids);
+
+        self.active_rentals.insert(
+            &rental_id,
+            &ItemRental {
+                item_id,
+                store_id,
+                renter,
+                rental_price: U128(deposit),
+                duration_seconds,
+                started_at: env::block_timestamp(),
+            },
+        );
+
+        rental_id
+    }
+
+    /// Retrieve a rental by its id
+    pub fn get_rental(&self, rental_id: String) -> Option<ItemRental> {
+        self.active_rentals.get(&rental_id)
+    }
+
+    /// Check if an account has an unexpired rental of an item
+    pub fn is_item_rented_by(
+        &self,
+        item_id: ItemId,
+        store_id: AccountId,
+        account_id: AccountId,
+    ) -> bool {
+        self.rentals_by_item_and_renter
+            .get(&(store_and_item_id(&store_id, &item_id), account_id))
+            .unwrap_or_default()
+            .iter()
+            .filter_map(|rental_id| self.active_rentals.get(rental_id))
+            .any(|rental| !rental_expired(&rental))
+    }
+
+    /// Clear an expired rental. Callable by anyone
+    pub fn expire_rental(&mut self, rental_id: String) {
+        let rental = self
+            .active_rentals
+            .get(&rental_id)
+            .unwrap_or_else(|| panic_with(StoreHubError::RentalNotFound));
+        require!(rental_expired(&rental), "StoreHub: rental has not expired");
+
+        self.active_rentals.remove(&rental_id);
+        let renter_key = (
+            store_and_item_id(&rental.store_id, &rental.item_id),
+            rental.renter,
+        );
+        let mut rental_ids = self
+            .rentals_by_item_and_renter
+            .get(&renter_key)
+            .unwrap_or_default();
+        rental_ids.retain(|id| *id != rental_id);
+        if rental_ids.is_empty() {
+            self.rentals_by_item_and_renter.remove(&renter_key);
+        } else {
+            self.rentals_by_item_and_renter
+                .insert(&renter_key, &rental_ids);
+        }
+    }
+
+    /// Retrieve an item by its normalized name (see `normalize_item_name`) within a store
+    pub fn get_item_by_name_and_store(
+        &self,
+        store_id: AccountId,
+        normalized_name: String,
+    ) -> Option<ItemMetadata> {
+        let item_id = self.get_item_id_by_name(store_id.clone(), normalized_name)?;
+        self.get_item_metadata(item_id, store_id)
+    }
+
+    /// Retrieve the id of an item by its normalized name within a store
+    pub fn get_item_id_by_name(
+        &self,
+        store_id: AccountId,
+        normalized_name: String,
+    ) -> Option<ItemId> {
+        self.item_name_index.get(&(store_id, normalized_name))
+    }
+
+    /// Add an image to an item's gallery, the first one being its primary thumbnail
+    pub fn add_item_image(&mut self, item_id: ItemId, store_id: AccountId, url: String) {
+        self.assert_store_owner(&store_id);
+        require!(is_https_url(&url), "StoreHub: invalid image url");
+
+        let storeanditem_id = store_and_item_id(&store_id, &item_id);
+        self.internal_get_item_metadata(&storeanditem_id);
+        let mut images = self
+            .item_extra_images
+            .get(&storeanditem_id)
+            .unwrap_or_default();
+        require!(
+            images.len() < MAX_ITEM_IMAGES,
+            format!("StoreHub: cannot have more than {} images", MAX_ITEM_IMAGES)
+        );
+
+        images.push(url);
+        self.item_extra_images.insert(&storeanditem_id, &images);
+    }
+
+    /// Remove the image at `index` from an item's gallery
+    pub fn remove_item_image(&mut self, item_id: ItemId, store_id: AccountId, index: u32) {
+        self.assert_store_owner(&store_id);
+
+        let storeanditem_id = store_and_item_id(&store_id, &item_id);
+        let mut images = self
+            .item_extra_images
+            .get(&storeanditem_id)
+            .unwrap_or_default();
+        require!(
+            (index as usize) < images.len(),
+            "StoreHub: image doesn't exist"
+        );
+
+        images.remove(index as usize);
+        if images.is_empty() {
+            self.item_extra_images.remove(&storeanditem_id);
+        } else {
+            self.item_extra_images.insert(&storeanditem_id, &images);
+        }
+    }
+
+    /// Retrieve an item's gallery images, the first one being its primary thumbnail
+    pub fn get_item_images(&self, item_id: ItemId, store_id: AccountId) -> Vec<String> {
+        self.item_extra_images
+            .get(&store_and_item_id(&store_id, &item_id))
+            .unwrap_or_default()
+    }
+
+    /// Record what an item cost the store and the margin its price must keep over it.
+    /// Owner price changes below `cost * (10000 + min_margin_bps) / 10000` are rejected
+    pub fn set_item_cost_basis(
+        &mut self,
+        item_id: ItemId,
+        store_id: AccountId,
+        cost: U128,
+        min_margin_bps: u16,
+    ) {
+        self.assert_store_owner(&store_id);
+        require!(cost.0 > 0, "StoreHub: cost must be positive");
+
+        let storeanditem_id = store_and_item_id(&store_id, &item_id);
+        self.internal_get_item_metadata(&storeanditem_id);
+        self.item_cost_basis.insert(&storeanditem_id, &cost.0);
+        self.item_min_margin_bps
+            .insert(&storeanditem_id, &min_margin_bps);
+    }
+
+    /// Retrieve an item's current margin over its cost, in basis points,
+    /// `None` without a cost basis. Margins beyond `i64::MAX` are capped
+    pub fn get_item_effective_margin_bps(
+        &self,
+        item_id: ItemId,
+        store_id: AccountId,
+    ) -> Option<i64> {
+        let storeanditem_id = store_and_item_id(&store_id, &item_id);
+        let cost = self.item_cost_basis.get(&storeanditem_id)?;
+        let price = self.internal_get_item_metadata(&storeanditem_id).price.0;
+
+        // a loss is at most the whole cost, so only a gain can overflow
+        if price >= cost {
+            let margin = (price - cost)
+                .checked_mul(10_000)
+                .map_or(u128::MAX, |gain| gain / cost);
+            Some(i64::try_from(margin).unwrap_or(i64::MAX))
+        } else {
+            Some(-(((cost - price) * 10_000 / cost) as i64))
+        }
+    }
+
+    /// Retrieve an item's reviews that come with photos or videos
+    pub fn get_item_reviews_with_media(
+        &self,
+        item_id: ItemId,
+        store_id: AccountId,
+    ) -> Vec<ItemRating> {
+        self.item_ratings
+            .values()
+            .filter(|rating| {
+                rating.item_id == item_id && rating.store_id == store_id && !rating.media.is_empty()
+            })
+            .collect()
+    }
+
+    /// Flag a review for moderation, once per account. Review ids are
+    /// `STOREID + DELIMITER + ITEM_ID + DELIMITER + REVIEWER`
+    pub fn report_review(&mut self, review_id: String, reason: String) {
+        if self.item_ratings.get(&review_id).is_none() {
+            panic_with(StoreHubError::ReviewNotFound)
+        }
+        require!(!reason.is_empty(), "StoreHub: reason can't be empty");
+
+        let reporter = env::signer_account_id();
+        let mut reports = self.review_reports.get(&review_id).unwrap_or_default();
+        require!(
+            reports
+                .iter()
+                .all(|(account_id, _)| account_id != &reporter),
+            "StoreHub: review already reported"
+        );
+        reports.push((reporter, reason));
+        self.review_reports.insert(&review_id, &reports);
+    }
+
+    /// Retrieve the reviews flagged for moderation, along with their reports
+    pub fn get_reported_reviews(
+        &self,
+        from_index: Option<u64>,
+        limit: Option<u64>,
+    ) -> Vec<(String, Vec<(AccountId, String)>)> {
+        self.review_reports
+            .iter()
+            .skip(from_index.unwrap_or(0) as usize)
+            .take(limit.unwrap_or(self.review_reports.len()) as usize)
+            .collect()
+    }
+
+    /// Remove a review and its reports, barring its author from reviewing
+    /// the item again. Only callable by the overseer
+    pub fn remove_review(&mut self, review_id: String) {
+        self.assert_overseer();
+        let rating = self
+            .item_ratings
+            .remove(&review_id)
+            .unwrap_or_else(|| panic_with(StoreHubError::ReviewNotFound));
+        let reports = self.review_reports.remove(&review_id).unwrap_or_default();
+        self.removed_reviews
+            .insert(&review_id, &env::block_timestamp());
+
+        self.add_log_internal(
+            "remove_review".to_string(),
+            env::signer_account_id().to_string(),
+            review_id,
+            json!({ "reviewer": rating.reviewer, "reports": reports.len() }).to_string(),
+        );
+    }
+
+    /// Set the URL indexers notify of the store's sales, `None` removing it.
+    /// The URL is public in the `store_sale` events emitted on every sale
+    pub fn set_store_webhook_url(&mut self, store_id: AccountId, url: Option<String>) {
+        self.assert_store_owner(&store_id);
+        match url {
+            Some(url) => {
+                require!(is_https_url(&url), "StoreHub: invalid webhook url");
+                self.store_webhook_urls.insert(&store_id, &url);
+            }
+            None => {
+                self.store_webhook_urls.remove(&store_id);
+            }
+        }
+    }
+
+    /// Retrieve the URL indexers notify of the store's sales
+    pub fn get_store_webhook_url(&self, store_id: AccountId) -> Option<String> {
+        self.store_webhook_urls.get(&store_id)
+    }
+
+    /// Retrieve an item's previous prices, oldest first, with the time they were replaced
+    pub fn get_item_price_history(
+        &self,
+        item_id: ItemId,
+        store_id: AccountId,
+        from_index: Option<u64>,
+        limit: Option<u64>,
+    ) -> Vec<(U128, u64)> {
+        let history = self
+            .item_price_history
+            .get(&store_and_item_id(&store_id, &item_id))
+            .unwrap_or_default();
+        let limit = limit.unwrap_or(history.len() as u64);
+        history
+            .into_iter()
+            .skip(from_index.unwrap_or(0) as usize)
+            .take(limit as usize)
+            .map(|(price, replaced_at)| (U128(price), replaced_at))
+            .collect()
+    }
+
+    /// Retrieve a store's items added at or after `since_timestamp`, newest first
+    pub fn get_items_added_since(
+        &self,
+        store_id: AccountId,
+        since_timestamp: u64,
+        from_index: Option<u64>,
+        limit: Option<u64>,
+    ) -> Vec<(ItemId, ItemMetadata)> {
+        let items = self.internal_store_items_by_age(&store_id, since_timestamp);
+        let limit = limit.unwrap_or(items.len() as u64);
+        items
+            .into_iter()
+            .skip(from_index.unwrap_or(0) as usize)
+            .take(limit as usize)
+            .collect()
+    }
+
+    /// Retrieve a store's most recently added items, newest first
+    pub fn get_newest_items_by_store(
+        &self,
+        store_id: AccountId,
+        limit: u64,
+    ) -> Vec<(ItemId, ItemMetadata)> {
+        self.internal_store_items_by_age(&store_id, 0)
+            .into_iter()
+            .take(limit as usize)
+            .collect()
+    }
+
+    /// Check if payment method exists
+    pub fn is_ft_approved(&self, ft_account_id: AccountId) -> bool {
+        self.approved_ft_token_ids.contains(&ft_account_id)
+    }
+}
+
+/// Item metadata layout as deployed before items were graded
+#[derive(BorshDeserialize, BorshSerialize)]
+pub struct ItemMetadataV1 {
+    pub name: String,
+    pub price: U128,
+    pub img_url: String,
+    pub owner: AccountId,
+}
+
+/// Audit log layout as deployed before logs were hash-chained
+#[derive(BorshDeserialize, BorshSerialize)]
+pub struct LogV1 {
+    pub id: String,
+    pub timestamp: u64,
+    pub action: String,
+    pub actor: String,
+    pub entity: String,
+    pub extra: String,
+}
+
+/// Contract state layout as deployed before `state_version` was tracked
+#[derive(BorshDeserialize, BorshSerialize)]
+pub struct ContractV1 {
+    pub overseer_id: AccountId,
+    pub stores_by_account_id: Option<LookupMap<AccountId, UnorderedSet<StoreId>>>,
+    pub owners_per_store_id: Option<LookupMap<StoreId, UnorderedSet<AccountId>>>,
+    pub item_by_store_id: TreeMap<ItemId, StoreId>,
+    pub metadata_by_storeanditem_ids: Option<UnorderedMap<StoreAndItemIds, ItemMetadataV1>>,
+    pub audit_logs: UnorderedSet<LogV1>,
+    pub approved_ft_token_ids: UnorderedSet<AccountId>,
+}
+
+/// State migrations applied by the overseer after upgrading the contract code
+pub trait MigrateState {
+    fn migrate_from_v1() -> Self;
+}
+
+#[near_bindgen]
+impl MigrateState for Contract {
+    /// Re-serialize a v1 state under the current layout, keeping the existing
+    /// collections, rebuilding the store and item indices from them and giving
+    /// every other field added since its default value.
+    /// The v1 state can't be deserialized as `Contract`, so this reads the raw
+    /// state instead of taking `&mut self`.
+    #[init(ignore_state)]
+    fn migrate_from_v1() -> Self {
+        let old_state = env::storage_read(b"STATE")
+            .unwrap_or_else(|| panic_with(StoreHubError::NotInitialized));
+        let old = ContractV1::try_from_slice(&old_state)
+            .unwrap_or_else(|_| panic_with(StoreHubError::AlreadyMigrated));
+        require!(
+            env::signer_account_id().eq(&old.overseer_id),
+            "StoreHub: access denied"
+        );
+
+        let mut this = Self::internal_new(old.overseer_id);
+        this.stores_by_account_id = old.stores_by_account_id;
+        this.owners_per_store_id = old.owners_per_store_id;
+        this.item_by_store_id = old.item_by_store_id;
+        this.approved_ft_token_ids = old.approved_ft_token_ids;
+
+        // v1 items are re-inserted under the current layout, ungraded
+        if let Some(mut old_metadata) = old.metadata_by_storeanditem_ids {
+            let items = old_metadata.to_vec();
+            old_metadata.clear();
+            for (storeanditem_id, old_item) in items {
+                let metadata = ItemMetadata {
+                    name: old_item.name,
+                    price: old_item.price,
+                    img_url: old_item.img_url,
+                    owner: old_item.owner,
+                    condition: None,
+                    upc: None,
+                    video_url: None,
+                };
+                this.internal_set_item_metadata(&storeanditem_id, &metadata);
+            }
+        }
+
+        // v1 only tracked items by id, so the store and item indices are rebuilt from it.
+        // Listing times weren't recorded, migrated items count as listed at the migration
+        let listed_items = this.item_by_store_id.to_vec();
+        for (item_id, store_id) in listed_items {
+            let storeanditem_id = store_and_item_id(&store_id, &item_id);
+            this.all_stores.insert(&store_id);
+            this.all_items.insert(&storeanditem_id);
+            this.item_created_at
+                .insert(&storeanditem_id, &env::block_timestamp());
+
+            let mut item_ids = this.items_per_store.get(&store_id).unwrap_or_else(|| {
+                UnorderedSet::new(StorageKey::ItemsPerStoreInner {
+                    store_hash: env::sha256(store_id.as_bytes()),
+                })
+            });
+            item_ids.insert(&item_id);
+            this.items_per_store.insert(&store_id, &item_ids);
+
+            let mut store_ids = this.stores_by_item.get(&item_id).unwrap_or_else(|| {
+                UnorderedSet::new(StorageKey::StoresByItemInner {
+                    item_hash: env::sha256(item_id.as_bytes()),
+                })
+            });
+            store_ids.insert(&store_id);
+            this.stores_by_item.insert(&item_id, &store_ids);
+        }
+
+        // v1 logs are re-inserted under the current layout, chaining them in order
+        let mut old_logs = old.audit_logs;
+        let logs = old_logs.to_vec();
+        old_logs.clear();
+        for old_log in logs {
+            let log = Log {
+                id: old_log.id,
+                timestamp: old_log.timestamp,
+                action: old_log.action,
+                actor: old_log.actor,
+                entity: old_log.entity,
+                extra: old_log.extra,
+                prev_hash: this.log_chain_head,
+            };
+            this.log_chain_head = Some(log_chain_hash(&log.prev_hash, &log.id));
+            this.audit_logs.insert(&log);
+        }
+
+        this
+    }
+}
+
+impl Contract {
+    /// Build a fresh contract state without writing to storage
+    fn internal_new(overseer_id: AccountId) -> Self {
+        Self {
+            overseer_id,
+            stores_by_account_id: Some(LookupMap::new(
+                StorageKey::StoresByAccountId.into_storage_key(),
+            )),
+            owners_per_store_id: Some(LookupMap::new(
+                StorageKey::OwnersByStoreId.into_storage_key(),
+            )),
+            item_by_store_id: TreeMap::new(StorageKey::ItemByStoreId.into_storage_key()),
+            metadata_by_storeanditem_ids: Some(UnorderedMap::new(
+                StorageKey::TransactionsByStoreAndItemIds.into_storage_key(),
+            )),
+            audit_logs: UnorderedSet::new(StorageKey::AuditLogs.into_storage_key()),
+            approved_ft_token_ids: UnorderedSet::new(
+                StorageKey::FungibleTokenIds.into_storage_key(),
+            ),
+            buyer_purchases: LookupMap::new(StorageKey::BuyerPurchases.into_storage_key()),
+            store_minimum_deposits: LookupMap::new(
+                StorageKey::StoreMinimumDeposits.into_storage_key(),
+            ),
+            items_per_store: LookupMap::new(StorageKey::ItemsPerStore.into_storage_key()),
+            item_purchase_counts: LookupMap::new(StorageKey::ItemPurchaseCounts.into_storage_key()),
+            affiliate_codes: UnorderedMap::new(StorageKey::AffiliateCodes.into_storage_key()),
+            affiliate_earnings: LookupMap::new(StorageKey::AffiliateEarnings.into_storage_key()),
+            log_nonce: 0,
+            scheduled_price_changes: UnorderedMap::new(
+                StorageKey::ScheduledPriceChanges.into_storage_key(),
+            ),
+            group_buys: UnorderedMap::new(StorageKey::GroupBuys.into_storage_key()),
+            next_item_sequence: LookupMap::new(StorageKey::NextItemSequence.into_storage_key()),
+            state_version: STATE_VERSION,
+            all_stores: UnorderedSet::new(StorageKey::AllStores.into_storage_key()),
+            store_revenue: LookupMap::new(StorageKey::StoreRevenue.into_storage_key()),
+            store_balances: LookupMap::new(StorageKey::StoreBalances.into_storage_key()),
+            protocol_fee_bps: 0,
+            treasury_balance: 0,
+            store_currency: LookupMap::new(StorageKey::StoreCurrencies.into_storage_key()),
+            item_currency: LookupMap::new(StorageKey::ItemCurrencies.into_storage_key()),
+            giveaways: UnorderedMap::new(StorageKey::Giveaways.into_storage_key()),
+            proposals: UnorderedMap::new(StorageKey::GovernanceProposals.into_storage_key()),
+            store_ratings: UnorderedMap::new(StorageKey::StoreRatings.into_storage_key()),
+            item_ratings: UnorderedMap::new(StorageKey::ItemRatings.into_storage_key()),
+            store_royalty_bps: LookupMap::new(StorageKey::StoreRoyalties.into_storage_key()),
+            store_royalties_earned: LookupMap::new(
+                StorageKey::StoreRoyaltiesEarned.into_storage_key(),
+            ),
+            item_royalties_earned: LookupMap::new(
+                StorageKey::ItemRoyaltiesEarned.into_storage_key(),
+            ),
+            stores_by_item: LookupMap::new(StorageKey::StoresByItem.into_storage_key()),
+            all_items: UnorderedSet::new(StorageKey::AllItems.into_storage_key()),
+            item_purchase_limits: LookupMap::new(StorageKey::ItemPurchaseLimits.into_storage_key()),
+            buyer_item_purchase_counts: LookupMap::new(
+                StorageKey::BuyerItemPurchaseCounts.into_storage_key(),
+            ),
+            store_geo_policies: LookupMap::new(StorageKey::StoreGeoPolicies.into_storage_key()),
+            log_chain_head: None,
+            item_ft_requirements: LookupMap::new(StorageKey::ItemFtRequirements.into_storage_key()),
+            item_shipping: LookupMap::new(StorageKey::ItemShipping.into_storage_key()),
+            locked_items: UnorderedSet::new(StorageKey::LockedItems.into_storage_key()),
+            subscription_plans: UnorderedMap::new(StorageKey::SubscriptionPlans.into_storage_key()),
+            subscriptions: UnorderedMap::new(StorageKey::Subscriptions.into_storage_key()),
+            platform_min_items_for_active_store: None,
+            store_tax_rates: LookupMap::new(StorageKey::StoreTaxRates.into_storage_key()),
+            store_sale_periods: LookupMap::new(StorageKey::StoreSalePeriods.into_storage_key()),
+            store_roles: UnorderedMap::new(StorageKey::StoreRoles.into_storage_key()),
+            item_history: UnorderedMap::new(StorageKey::ItemHistory.into_storage_key()),
+            caller_contracts: UnorderedSet::new(StorageKey::CallerContracts.into_storage_key()),
+            store_metadata: LookupMap::new(StorageKey::StoreMetadataById.into_storage_key()),
+            require_item_approval: false,
+            items_pending_approval: UnorderedSet::new(
+                StorageKey::ItemsPendingApproval.into_storage_key(),
+            ),
+            stores_closed_for_listing: UnorderedSet::new(
+                StorageKey::ClosedForListing.into_storage_key(),
+            ),
+            buyer_store_spending: LookupMap::new(StorageKey::BuyerStoreSpending.into_storage_key()),
+            referral_programs: UnorderedMap::new(StorageKey::ReferralPrograms.into_storage_key()),
+            referral_earnings: LookupMap::new(StorageKey::ReferralEarnings.into_storage_key()),
+            item_cooldowns: LookupMap::new(StorageKey::ItemCooldowns.into_storage_key()),
+            buyer_last_purchase_time: LookupMap::new(
+                StorageKey::BuyerLastPurchaseTime.into_storage_key(),
+            ),
+            item_created_at: LookupMap::new(StorageKey::ItemCreatedAt.into_storage_key()),
+            store_terms: LookupMap::new(StorageKey::StoreTerms.into_storage_key()),
+            terms_acceptances: LookupMap::new(StorageKey::TermsAcceptances.into_storage_key()),
+            age_restricted_items: UnorderedSet::new(
+                StorageKey::AgeRestrictedItems.into_storage_key(),
+            ),
+            age_attestations: LookupMap::new(StorageKey::AgeAttestations.into_storage_key()),
+            dynamic_pricing: LookupMap::new(StorageKey::DynamicPricing.into_storage_key()),
+            platform_announcements: vec![],
+            store_stakes: LookupMap::new(StorageKey::StoreStakes.into_storage_key()),
+            item_waitlists: LookupMap::new(StorageKey::ItemWaitlists.into_storage_key()),
+            item_certificates: UnorderedMap::new(StorageKey::ItemCertificates.into_storage_key()),
+            certified_issuers: UnorderedSet::new(StorageKey::CertifiedIssuers.into_storage_key()),
+            loyalty_tiers: vec![],
+            buyer_lifetime_spend: LookupMap::new(StorageKey::BuyerLifetimeSpend.into_storage_key()),
+            store_partnerships: UnorderedMap::new(StorageKey::StorePartnerships.into_storage_key()),
+            barcode_index: UnorderedMap::new(StorageKey::BarcodeIndex.into_storage_key()),
+            featured_banners: vec![],
+            banner_fee: 0,
+            item_video_views: LookupMap::new(StorageKey::ItemVideoViews.into_storage_key()),
+            last_video_views: LookupMap::new(StorageKey::LastVideoViews.into_storage_key()),
+            item_crosschain_links: UnorderedMap::new(
+                StorageKey::ItemCrosschainLinks.into_storage_key(),
+            ),
+            approved_chains: UnorderedSet::new(StorageKey::ApprovedChains.into_storage_key()),
+            tag_to_items: LookupMap::new(StorageKey::TagIndex.into_storage_key()),
+            item_tags: LookupMap::new(StorageKey::ItemTags.into_storage_key()),
+            ft_exchange_rates: LookupMap::new(StorageKey::FtExchangeRates.into_storage_key()),
+            overseer_multisig: None,
+            dao_controlled_stores: LookupMap::new(
+                StorageKey::DaoControlledStores.into_storage_key(),
+            ),
+            item_nft_requirements: LookupMap::new(
+                StorageKey::ItemNftRequirements.into_storage_key(),
+            ),
+            store_created_at: LookupMap::new(StorageKey::StoreCreatedAt.into_storage_key()),
+            item_supply_caps: LookupMap::new(StorageKey::ItemSupplyCaps.into_storage_key()),
+            item_charity_splits: LookupMap::new(StorageKey::ItemCharitySplits.into_storage_key()),
+            item_charity_donations: LookupMap::new(
+                StorageKey::ItemCharityDonations.into_storage_key(),
+            ),
+            item_rental_rates: LookupMap::new(StorageKey::ItemRentalRates.into_storage_key()),
+            active_rentals: UnorderedMap::new(StorageKey::ActiveRentals.into_storage_key()),
+            item_name_index: UnorderedMap::new(StorageKey::ItemNameIndex.into_storage_key()),
+            item_extra_images: LookupMap::new(StorageKey::ItemExtraImages.into_storage_key()),
+            item_cost_basis: LookupMap::new(StorageKey::ItemCostBasis.into_storage_key()),
+            item_min_margin_bps: LookupMap::new(StorageKey::ItemMinMarginBps.into_storage_key()),
+            review_reports: UnorderedMap::new(StorageKey::ReviewReports.into_storage_key()),
+            store_webhook_urls: LookupMap::new(StorageKey::StoreWebhookUrls.into_storage_key()),
+            item_price_history: UnorderedMap::new(StorageKey::ItemPriceHistory.into_storage_key()),
+            accepted_partnerships_by_store: LookupMap::new(
+                StorageKey::AcceptedPartnershipsByStore.into_storage_key(),
+            ),
+            rentals_by_item_and_renter: LookupMap::new(
+                StorageKey::RentalsByItemAndRenter.into_storage_key(),
+            ),
+            removed_reviews: LookupMap::new(StorageKey::RemovedReviews.into_storage_key()),
+        }
+    }
+
+    /// Shared purchase flow of `buy` and `proxy_buy`: the signer pays
+    /// and `recipient` becomes the item's new owner
+    fn internal_buy(
+        &mut self,
+        item_id: ItemId,
+        store_id: AccountId,
+        recipient: AccountId,
+        affiliate_code: Option<AffiliateCode>,
+        referrer: Option<AccountId>,
+        buyer_region: Option<String>,
+    ) -> BuyReceipt {
+        let quote = self
+            .internal_check_buy(
+                &item_id,
+                &store_id,
+                &recipient,
+                affiliate_code.as_ref(),
+                referrer.as_ref(),
+                buyer_region,
+                None,
+            )
+            .unwrap_or_else(|err| env::panic_str(&err));
+
+        self.internal_complete_buy(
+            item_id,
+            store_id,
+            recipient,
+            affiliate_code,
+            referrer,
+            quote,
+        )
+    }
+
+    /// Check a purchase paid with the attached deposit can go through, pricing it.
+    /// Errors are returned rather than raised so gated purchases can refund from their callback
+    fn internal_check_buy(
+        &self,
+        item_id: &ItemId,
+        store_id: &AccountId,
+        recipient: &AccountId,
+        affiliate_code: Option<&AffiliateCode>,
+        referrer: Option<&AccountId>,
+        buyer_region: Option<String>,
+        checked_gate: Option<PurchaseGate>,
+    ) -> Result<BuyQuote, String> {
+        // check both item and store exist, and be right places
+        match self.item_by_store_id.get(item_id) {
+            Some(returned_store_id) => {
+                if returned_store_id.ne(store_id) {
+                    return Err(StoreHubError::ItemNotInStore.to_string());
+                }
+            }
+            None => return Err(StoreHubError::ItemNotFound.to_string()),
+        }
+
+        self.internal_check_region(store_id, buyer_region)?;
+
+        // gated items are only sold once their gate's cross-contract check passed
+        let storeanditem_id = store_and_item_id(store_id, item_id);
+        ensure(
+            self.item_ft_requirements.get(&storeanditem_id).is_none()
+                || checked_gate == Some(PurchaseGate::Ft),
+            "StoreHub: item is token gated, use buy_with_ft_gate",
+        )?;
+        ensure(
+            self.item_nft_requirements.get(&storeanditem_id).is_none()
+                || checked_gate == Some(PurchaseGate::Nft),
+            "StoreHub: item is NFT gated, use buy_nft_gated",
+        )?;
+
+        let signer_id = env::signer_account_id();
+        let deposit = env::attached_deposit();
+        let store_minimum_deposit = self.store_minimum_deposits.get(store_id).unwrap_or(0);
+
+        ensure(
+            !self.locked_items.contains(&storeanditem_id),
+            "StoreHub: item locked in auction",
+        )?;
+
+        ensure(
+            !self.items_pending_approval.contains(&storeanditem_id),
+            "StoreHub: item pending approval",
+        )?;
+
+        // FT-denominated items can't be paid for with NEAR
+        if let Some(ft_account_id) = self.item_currency.get(&storeanditem_id).flatten() {
+            return Err(StoreHubError::ItemPricedInFt(ft_account_id).to_string());
+        }
+
+        // check deposit, ownership and update contract's state
+        let metadata = self.internal_get_item_metadata(&storeanditem_id);
+        let sale_discount = self.internal_sale_discount(store_id, &metadata);
+        let mut price = metadata.price.0 - sale_discount;
+
+        // buyers of a partner store get the partnership's discount on the store's own listings
+        let mut partner_discount = 0;
+        if metadata.owner == *store_id {
+            let discount_bps = self.internal_partner_discount_bps(&signer_id, store_id);
+            partner_discount = price * discount_bps as u128 / 10_000;
+        }
+        price -= partner_discount;
+
+        // referred buyers get the program's discount on the store's own listings
+        let referral_program = match referrer {
+            Some(_) => Some(
+                self.referral_programs
+                    .get(store_id)
+                    .ok_or_else(|| StoreHubError::ReferralProgramNotFound.to_string())?,
+            ),
+            None => None,
+        };
+        let mut referral_discount = 0;
+        if let Some(program) = &referral_program {
+            ensure(program.active, "StoreHub: referral program is inactive")?;
+            ensure(
+                metadata.owner == *store_id,
+                "StoreHub: referrals only apply to store listings",
+            )?;
+            referral_discount = price * program.referred_discount_bps as u128 / 10_000;
+            price -= referral_discount;
+        }
+
+        let shipping_fee = self.internal_shipping_fee(&storeanditem_id, price);
+        if deposit < price + shipping_fee {
+            return Err(
+                StoreHubError::InsufficientDeposit(deposit, price + shipping_fee).to_string(),
+            );
+        }
+        ensure(
+            deposit >= store_minimum_deposit,
+            "StoreHub: deposit below store minimum",
+        )?;
+        ensure(
+            recipient.ne(&metadata.owner),
+            "StoreHub: can't buy owned item",
+        )?;
+        if let Some(limit) = self.item_purchase_limits.get(&storeanditem_id) {
+            let buyer_count = self
+                .buyer_item_purchase_counts
+                .get(&(signer_id.clone(), storeanditem_id.clone()))
+                .unwrap_or(0);
+            ensure(buyer_count < limit, "StoreHub: purchase limit reached")?;
+        }
+        if let Some(cap) = self.item_supply_caps.get(&storeanditem_id) {
+            let total_sold = self.item_purchase_counts.get(&storeanditem_id).unwrap_or(0);
+            ensure(total_sold < cap, "StoreHub: item supply exhausted")?;
+        }
+        ensure(
+            self.internal_cooldown_remaining(&signer_id, &storeanditem_id) == 0,
+            "StoreHub: purchase cooldown active",
+        )?;
+        ensure(
+            self.store_terms.get(store_id).is_none()
+                || self.has_accepted_terms(store_id.clone(), signer_id.clone()),
+            "StoreHub: must acknowledge terms first",
+        )?;
+        ensure(
+            !self.age_restricted_items.contains(&storeanditem_id)
+                || self.has_attested_age(signer_id.clone(), MIN_RESTRICTED_AGE),
+            "StoreHub: age attestation required",
+        )?;
+
+        // every cut is paid out of the deposit, so they can't add up to more than it
+        let mut fees = price * self.protocol_fee_bps as u128 / 10_000;
+        if let Some(code) = affiliate_code {
+            fees += price * code.commission_bps as u128 / 10_000;
+        }
+        if let Some(program) = &referral_program {
+            fees += price * program.referral_reward_bps as u128 / 10_000;
+        }
+        if metadata.owner != *store_id {
+            fees += price * self.store_royalty_bps.get(store_id).unwrap_or(0) as u128 / 10_000;
+        }
+        if let Some((_, split_bps)) = self.item_charity_splits.get(&storeanditem_id) {
+            fees += price * split_bps as u128 / 10_000;
+        }
+        ensure(
+            fees <= deposit,
+            &StoreHubError::FeesExceedDeposit.to_string(),
+        )?;
+
+        Ok(BuyQuote {
+            price,
+            sale_discount,
+            partner_discount,
+            referral_discount,
+            shipping_fee,
+            referral_program,
+        })
+    }
+
+    /// Carry out a purchase `internal_check_buy` allowed
+    fn internal_complete_buy(
+        &mut self,
+        item_id: ItemId,
+        store_id: AccountId,
+        recipient: AccountId,
+        affiliate_code: Option<AffiliateCode>,
+        referrer: Option<AccountId>,
+        quote: BuyQuote,
+    ) -> BuyReceipt {
+        let BuyQuote {
+            price,
+            sale_discount,
+            partner_discount,
+            referral_discount,
+            shipping_fee,
+            referral_program,
+        } = quote;
+        let signer_id = env::signer_account_id();
+        let deposit = env::attached_deposit();
+        let storeanditem_id = store_and_item_id(&store_id, &item_id);
+        let mut metadata = self.internal_get_item_metadata(&storeanditem_id);
+
+        // the protocol fee is kept in the treasury, out of the seller's proceeds
+        let protocol_fee = price * self.protocol_fee_bps as u128 / 10_000;
+        let mut seller_proceeds = deposit - protocol_fee;
+        self.treasury_balance += protocol_fee;
+
+        // the affiliate's commission is taken out of the seller's proceeds
+        let mut commission = 0;
+        if let Some(code) = &affiliate_code {
+            commission = price * code.commission_bps as u128 / 10_000;
+            seller_proceeds = seller_proceeds
+                .checked_sub(commission)
+                .unwrap_or_else(|| panic_with(StoreHubError::FeesExceedDeposit));
+
+            let earnings = self.affiliate_earnings.get(&code.affiliate).unwrap_or(0);
+            self.affiliate_earnings
+                .insert(&code.affiliate, &(earnings + commission));
+            Promise::new(code.affiliate.clone()).transfer(commission);
+        }
+
+        // the referrer's reward is taken out of the seller's proceeds
+        let mut referral_reward = 0;
+        if let (Some(referrer), Some(program)) = (&referrer, &referral_program) {
+            referral_reward = price * program.referral_reward_bps as u128 / 10_000;
+            seller_proceeds = seller_proceeds
+                .checked_sub(referral_reward)
+                .unwrap_or_else(|| panic_with(StoreHubError::FeesExceedDeposit));
+
+            let earnings = self.referral_earnings.get(referrer).unwrap_or(0);
+            self.referral_earnings
+                .insert(referrer, &(earnings + referral_reward));
+            if referral_reward > 0 {
+                Promise::new(referrer.clone()).transfer(referral_reward);
+            }
+        }
+
+        // resales pay the store its royalty, out of the reseller's proceeds
+        let mut royalty = 0;
+        if metadata.owner != store_id {
+            royalty = price * self.store_royalty_bps.get(&store_id).unwrap_or(0) as u128 / 10_000;
+        }
+        if royalty > 0 {
+            seller_proceeds = seller_proceeds
+                .checked_sub(royalty)
+                .unwrap_or_else(|| panic_with(StoreHubError::FeesExceedDeposit));
+
+            let store_earned = self.store_royalties_earned.get(&store_id).unwrap_or(0);
+            self.store_royalties_earned
+                .insert(&store_id, &(store_earned + royalty));
+            let item_earned = self
+                .item_royalties_earned
+                .get(&storeanditem_id)
+                .unwrap_or(0);
+            self.item_royalties_earned
+                .insert(&storeanditem_id, &(item_earned + royalty));
+            Promise::new(store_id.clone()).transfer(royalty);
+
+            let extra = json!({
+                "royalty": U128(royalty),
+                "seller": metadata.owner,
+            });
+            self.add_log_internal(
+                "royalty_transfer".to_string(),
+                signer_id.to_string(),
+                storeanditem_id.clone(),
+                extra.to_string(),
+            );
+        }
+
+        // cause-marketing items donate their share out of the seller's proceeds
+        if let Some((charity, split_bps)) = self.item_charity_splits.get(&storeanditem_id) {
+            let donation = price * split_bps as u128 / 10_000;
+            seller_proceeds = seller_proceeds
+                .checked_sub(donation)
+                .unwrap_or_else(|| panic_with(StoreHubError::FeesExceedDeposit));
+
+            let donated = self
+                .item_charity_donations
+                .get(&storeanditem_id)
+                .unwrap_or(0);
+            self.item_charity_donations
+                .insert(&storeanditem_id, &(donated + donation));
+            Promise::new(charity.clone()).transfer(donation);
+
+            let extra = json!({
+                "charity": charity,
+                "donation": U128(donation),
+                "seller": metadata.owner,
+                "seller_proceeds": U128(seller_proceeds),
+            });
+            self.add_log_internal(
+                "charity_transfer".to_string(),
+                signer_id.to_string(),
+                storeanditem_id.clone(),
+                extra.to_string(),
+            );
+        }
+
+        Promise::new(metadata.owner.clone()).transfer(seller_proceeds);
+        let seller_id = metadata.owner.clone();
+        metadata.owner = recipient.clone();
+
+        // scarcity pricing bumps the price after every purchase, up to its cap
+        let mut new_price = None;
+        if let Some(config) = self.dynamic_pricing.get(&storeanditem_id) {
+            let mut bumped =
+                metadata.price.0 * (10_000 + config.price_increment_bps as u128) / 10_000;
+            if let Some(max_price) = config.max_price {
+                bumped = bumped.min(max_price.0);
+            }
+            new_price = Some(U128(bumped));
+            self.internal_record_price_change(&storeanditem_id, metadata.price);
+            metadata.price = U128(bumped);
+        }
+        self.internal_set_item_metadata(&storeanditem_id, &metadata);
+
+        // purchases are recorded under the account that paid
+        self.internal_record_purchase(&signer_id, &storeanditem_id);
+        self.internal_add_buyer_store_spending(&signer_id, &store_id, deposit);
+        self.internal_add_store_revenue(&store_id, deposit);
+
+        // add new buy transaction log to state, keeping what its invoice needs as of the sale
+        let mut extra = json!({
+            "paid": deposit,
+            "previous_owner": store_id,
+            "seller": seller_id,
+            "buyer": signer_id,
+            "recipient": recipient,
+            "item_name": metadata.name,
+            "tax_rate_bps": self.get_tax_rate(store_id.clone()),
+        });
+        if protocol_fee > 0 {
+            extra["protocol_fee"] = json!(U128(protocol_fee));
+        }
+        if shipping_fee > 0 {
+            extra["item_price"] = json!(U128(price));
+            extra["shipping_fee"] = json!(U128(shipping_fee));
+        }
+        if sale_discount > 0 {
+            extra["sale_discount"] = json!(U128(sale_discount));
+        }
+        if partner_discount > 0 {
+            extra["partner_discount"] = json!(U128(partner_discount));
+        }
+        if let Some(code) = &affiliate_code {
+            extra["affiliate_code"] = json!(code.code);
+            extra["commission"] = json!(U128(commission));
+        }
+        if let Some(new_price) = new_price {
+            extra["new_price"] = json!(new_price);
+        }
+        if let Some(referrer) = &referrer {
+            extra["referrer"] = json!(referrer);
+            extra["referral_discount"] = json!(U128(referral_discount));
+            extra["referral_reward"] = json!(U128(referral_reward));
+        }
+        let tx_id = self.add_log_internal(
+            "buy".to_string(),
+            signer_id.to_string(),
+            storeanditem_id,
+            extra.to_string(),
+        );
+
+        // indexers relay the sale to the store's backend, the contract can't make HTTP calls
+        if let Some(webhook_url) = self.store_webhook_urls.get(&store_id) {
+            emit_event(
+                "store_sale",
+                json!({
+                    "transaction_id": tx_id,
+                    "item_id": item_id,
+                    "store_id": store_id,
+                    "buyer": signer_id,
+                    "price_paid": U128(deposit),
+                    "webhook_url": webhook_url,
+                }),
+            );
+        }
+
+        BuyReceipt {
+            message: "your purchase is ready".to_string(),
+            transaction_id: tx_id,
+            item_id,
+            store_id,
+            buyer: signer_id,
+            price_paid: U128(deposit),
+            block_timestamp: env::block_timestamp(),
+        }
+    }
+
+    /// Record a purchase in the buyer's history and the item's purchase count
+    fn internal_record_purchase(&mut self, buyer: &AccountId, storeanditem_id: &StoreAndItemIds) {
+        let mut purchases = self.buyer_purchases.get(buyer).unwrap_or_else(|| {
+            UnorderedSet::new(StorageKey::BuyerPurchasesInner {
+                account_hash: env::sha256(buyer.as_bytes()),
+            })
+        });
+        purchases.insert(storeanditem_id);
+        self.buyer_purchases.insert(buyer, &purchases);
+
+        let buyer_key = (buyer.clone(), storeanditem_id.clone());
+        let buyer_count = self.buyer_item_purchase_counts.get(&buyer_key).unwrap_or(0);
+        self.buyer_item_purchase_counts
+            .insert(&buyer_key, &(buyer_count + 1));
+        self.buyer_last_purchase_time
+            .insert(&buyer_key, &env::block_timestamp());
+
+        let purchase_count = self.item_purchase_counts.get(storeanditem_id).unwrap_or(0);
+        self.item_purchase_counts
+            .insert(storeanditem_id, &(purchase_count + 1));
+    }
+
+    /// Add to the amount a buyer spent at a store and across the platform
+    fn internal_add_buyer_store_spending(
+        &mut self,
+        buyer: &AccountId,
+        store_id: &AccountId,
+        amount: u128,
+    ) {
+        let key = (buyer.clone(), store_id.clone());
+        let spent = self.buyer_store_spending.get(&key).unwrap_or(0);
+        self.buyer_store_spending.insert(&key, &(spent + amount));
+
+        let lifetime_spend = self.buyer_lifetime_spend.get(buyer).unwrap_or(0);
+        self.buyer_lifetime_spend
+            .insert(buyer, &(lifetime_spend + amount));
+    }
+
+    /// Highest discount a buyer gets at a store from purchases at its partner stores
+    fn internal_partner_discount_bps(&self, buyer: &AccountId, store_id: &AccountId) -> u16 {
+        self.accepted_partnerships_by_store
+            .get(store_id)
+            .unwrap_or_default()
+            .iter()
+            .filter_map(|partnership_id| self.store_partnerships.get(partnership_id))
+            .filter_map(|partnership| {
+                let partner_id = if partnership.store_a == *store_id {
+                    &partnership.store_b
+                } else if partnership.store_b == *store_id {
+                    &partnership.store_a
+                } else {
+                    return None;
+                };
+                self.internal_has_purchased_from_store(buyer, partner_id)
+                    .then_some(partnership.cross_discount_bps)
+            })
+            .max()
+            .unwrap_or(0)
+    }
+
+    /// Time left before a buyer can buy an item again
+    fn internal_cooldown_remaining(
+        &self,
+        buyer: &AccountId,
+        storeanditem_id: &StoreAndItemIds,
+    ) -> u64 {
+        let cooldown = match self.item_cooldowns.get(storeanditem_id) {
+            Some(cooldown) => cooldown,
+            None => return 0,
+        };
+        match self
+            .buyer_last_purchase_time
+            .get(&(buyer.clone(), storeanditem_id.clone()))
+        {
+            Some(last_purchase) => {
+                (last_purchase + cooldown).saturating_sub(env::block_timestamp())
+            }
+            None => 0,
+        }
+    }
+
+    /// Check whether a buyer purchased any item of a store
+    fn internal_has_purchased_from_store(&self, buyer: &AccountId, store_id: &AccountId) -> bool {
+        let purchases = match self.buyer_purchases.get(buyer) {
+            Some(purchases) => purchases,
+            None => return false,
+        };
+        let prefix = format!("{}{}", store_id, DELIMETER);
+
+        // store ids contain the delimiter, so confirm the remainder is an item of this store
+        let purchased = purchases.iter().any(|storeanditem_id| {
+            storeanditem_id
+                .strip_prefix(&prefix)
+                .map_or(false, |item_id| {
+                    self.item_by_store_id.get(&item_id.to_string()).as_ref() == Some(store_id)
+                })
+        });
+
+        purchased
+    }
+
+    /// Discount an active store-wide sale takes off an item, resales keeping their price
+    fn internal_sale_discount(&self, store_id: &AccountId, metadata: &ItemMetadata) -> u128 {
+        if metadata.owner != *store_id {
+            return 0;
+        }
+        match self.store_sale_periods.get(store_id) {
+            Some(sale_period) if is_sale_active(&sale_period) => {
+                metadata.price.0 * sale_period.discount_bps as u128 / 10_000
+            }
+            _ => 0,
+        }
+    }
+
+    /// Shipping fee owed on top of an item's price, waived above the free shipping threshold
+    fn internal_shipping_fee(&self, storeanditem_id: &StoreAndItemIds, price: u128) -> u128 {
+        match self.item_shipping.get(storeanditem_id) {
+            Some(shipping) => match shipping.free_shipping_threshold {
+                Some(threshold) if price >= threshold.0 => 0,
+                _ => shipping.flat_shipping_fee.0,
+            },
+            None => 0,
+        }
+    }
+
+    /// Validate a UPC and point it at an item
+    fn internal_index_barcode(&mut self, upc: &String, storeanditem_id: &StoreAndItemIds) {
+        require!(is_valid_upc(upc), "StoreHub: invalid barcode");
+        require!(
+            self.barcode_index.get(upc).is_none(),
+            "StoreHub: barcode already in use"
+        );
+        self.barcode_index.insert(upc, storeanditem_id);
+    }
+
+    /// Keep an item's replaced price in its price history, dropping the oldest once full
+    fn internal_record_price_change(&mut self, storeanditem_id: &StoreAndItemIds, old_price: U128) {
+        let mut history = self
+            .item_price_history
+            .get(storeanditem_id)
+            .unwrap_or_default();
+        if history.len() == MAX_PRICE_HISTORY_SIZE {
+            history.remove(0);
+        }
+        history.push((old_price.0, env::block_timestamp()));
+        self.item_price_history.insert(storeanditem_id, &history);
+    }
+
+    /// Retrieve a store's items added at or after `since_timestamp`, newest first.
+    /// Items listed before creation times were recorded are left out
+    fn internal_store_items_by_age(
+        &self,
+        store_id: &AccountId,
+        since_timestamp: u64,
+    ) -> Vec<(ItemId, ItemMetadata)> {
+        let mut items: Vec<(u64, ItemId)> = self
+            .internal_store_item_ids(store_id)
+            .into_iter()
+            .filter_map(|item_id| {
+                let created_at = self
+                    .item_created_at
+                    .get(&store_and_item_id(store_id, &item_id))?;
+                (created_at >= since_timestamp).then_some((created_at, item_id))
+            })
+            .collect();
+        items.sort_by(|a, b| b.0.cmp(&a.0).then_with(|| a.1.cmp(&b.1)));
+
+        items
+            .into_iter()
+            .map(|(_, item_id)| {
+                let metadata =
+                    self.internal_get_item_metadata(&store_and_item_id(store_id, &item_id));
+                (item_id, metadata)
+            })
+            .collect()
+    }
+
+    /// Retrieve the lowest price an item's cost basis allows, 0 without one
+    fn internal_price_floor(&self, storeanditem_id: &StoreAndItemIds) -> u128 {
+        let cost = self.item_cost_basis.get(storeanditem_id).unwrap_or(0);
+        let min_margin_bps = self.item_min_margin_bps.get(storeanditem_id).unwrap_or(0);
+        cost * (10_000 + min_margin_bps as u128) / 10_000
+    }
+
+    /// Map an item's normalized name to its id, names being unique within a store
+    fn internal_index_item_name(&mut self, store_id: &AccountId, name: &str, item_id: &ItemId) {
+        let normalized_name = normalize_item_name(name);
+        if normalized_name.is_empty() {
+            return;
+        }
+
+        let key = (store_id.clone(), normalized_name);
+        require!(
+            self.item_name_index
+                .get(&key)
+                .map_or(true, |indexed_id| &indexed_id == item_id),
+            "StoreHub: item name already in use"
+        );
+        self.item_name_index.insert(&key, item_id);
+    }
+
+    /// Drop an item's name from the name index
+    fn internal_unindex_item_name(&mut self, store_id: &AccountId, name: &str, item_id: &ItemId) {
+        let key = (store_id.clone(), normalize_item_name(name));
+        if self.item_name_index.get(&key).as_ref() == Some(item_id) {
+            self.item_name_index.remove(&key);
+        }
+    }
+
+    /// Post a platform announcement, dropping expired ones to make room
+    fn internal_post_announcement(
+        &mut self,
+        title: String,
+        body: String,
+        expires_at: Option<u64>,
+    ) -> String {
+        let now = env::block_timestamp();
+        self.platform_announcements.retain(|announcement| {
+            announcement
+                .expires_at
+                .map_or(true, |expires_at| expires_at > now)
+        });
+        require!(
+            self.platform_announcements.len() < MAX_ANNOUNCEMENTS,
+            format!(
+                "StoreHub: cannot have more than {} announcements",
+                MAX_ANNOUNCEMENTS
+            )
+        );
+
+        let id = format!("announcement{}{}", DELIMETER, now);
+        require!(
+            self.platform_announcements
+                .iter()
+                .all(|announcement| announcement.id != id),
+            "StoreHub: announcement already exists"
+        );
+
+        self.platform_announcements.push(PlatformAnnouncement {
+            id: id.clone(),
+            title,
+            body,
+            posted_at: now,
+            expires_at,
+        });
+
+        id
+    }
+
+    /// Price a checkout line the way `buy` would, with any store sale and shipping applied.
+    /// Items are single units and discount codes aren't supported
+    fn internal_checkout_line(
+        &self,
+        item_id: ItemId,
+        store_id: AccountId,
+        quantity: u32,
+        discount_code: Option<String>,
+    ) -> LineItem {
+        require!(quantity == 1, "StoreHub: items are single units");
+        require!(
+            discount_code.is_none(),
+            "StoreHub: discount codes are not supported"
+        );
+
+        let storeanditem_id = store_and_item_id(&store_id, &item_id);
+        let metadata = self.internal_get_item_metadata(&storeanditem_id);
+        let discount = self.internal_sale_discount(&store_id, &metadata);
+        let price = metadata.price.0 - discount;
+        let shipping = self.internal_shipping_fee(&storeanditem_id, price);
+
+        LineItem {
+            item_id,
+            store_id,
+            quantity,
+            unit_price: metadata.price,
+            discount: U128(discount),
+            shipping: U128(shipping),
+            total: U128(price + shipping),
+        }
+    }
+
+    /// Retrieve a partnership by id or panic
+    fn internal_get_partnership(&self, partnership_id: &String) -> Partnership {
+        self.store_partnerships
+            .get(partnership_id)
+            .unwrap_or_else(|| panic_with(StoreHubError::PartnershipNotFound))
+    }
+
+    /// Retrieve a store's referral program or panic
+    fn internal_get_referral_program(&self, store_id: &AccountId) -> ReferralProgram {
+        self.referral_programs
+            .get(store_id)
+            .unwrap_or_else(|| panic_with(StoreHubError::ReferralProgramNotFound))
+    }
+
+    /// Retrieve a subscription plan by id or panic
+    fn internal_get_subscription_plan(&self, plan_id: &String) -> SubscriptionPlan {
+        self.subscription_plans
+            .get(plan_id)
+            .unwrap_or_else(|| panic_with(StoreHubError::SubscriptionPlanNotFound))
+    }
+
+    /// Credit the attached deposit for one subscription period to the plan's store
+    fn internal_pay_subscription_period(&mut self, plan: &SubscriptionPlan) {
+        let deposit = env::attached_deposit();
+        if deposit < plan.price_per_period.0 {
+            panic_with(StoreHubError::InsufficientDeposit(
+                deposit,
+                plan.price_per_period.0,
+            ))
+        }
+
+        self.internal_credit_store_balance(&plan.store_id, deposit);
+        self.internal_add_store_revenue(&plan.store_id, deposit);
+    }
+
+    /// Retrieve a group buy by id or panic
+    fn internal_get_group_buy(&self, group_buy_id: &String) -> GroupBuy {
+        self.group_buys
+            .get(group_buy_id)
+            .unwrap_or_else(|| panic_with(StoreHubError::GroupBuyNotFound))
+    }
+
+    /// Retrieve a governance proposal by id or panic
+    fn internal_get_proposal(&self, proposal_id: &String) -> GovernanceProposal {
+        self.proposals
+            .get(proposal_id)
+            .unwrap_or_else(|| panic_with(StoreHubError::ProposalNotFound))
+    }
+
+    /// Retrieve a giveaway by id or panic
+    fn internal_get_giveaway(&self, giveaway_id: &String) -> Giveaway {
+        self.giveaways
+            .get(giveaway_id)
+            .unwrap_or_else(|| panic_with(StoreHubError::GiveawayNotFound))
+    }
+
+    /// Hand an item over to a new owner, without payment or access checks
+    fn internal_transfer_item(
+        &mut self,
+        store_id: &AccountId,
+        item_id: &ItemId,
+        new_owner_id: &AccountId,
+    ) {
+        let storeanditem_id = store_and_item_id(store_id, item_id);
+        let mut metadata = self.internal_get_item_metadata(&storeanditem_id);
+        metadata.owner = new_owner_id.clone();
+        self.internal_set_item_metadata(&storeanditem_id, &metadata);
+    }
+
+    /// Insert an item and its metadata under a store, without access checks
+    fn add_store_item_internal(
+        &mut self,
+        item_id: String,
+        store_id: AccountId,
+        item_name: String,
+        item_price: U128,
+        item_img_url: String,
+        item_condition: Option<ItemCondition>,
+        item_upc: Option<String>,
+        item_video_url: Option<String>,
+    ) {
+        require!(
+            !self.stores_closed_for_listing.contains(&store_id),
+            "StoreHub: store not accepting new items"
+        );
+        if let Some(video_url) = &item_video_url {
+            require!(is_https_url(video_url), "StoreHub: invalid video url");
+        }
+
+        let storeanditem_id = store_and_item_id(&store_id, &item_id);
+        if let Some(upc) = &item_upc {
+            self.internal_index_barcode(upc, &storeanditem_id);
+        }
+        self.internal_index_item_name(&store_id, &item_name, &item_id);
+
+        self.item_by_store_id.insert(&item_id, &store_id);
+
+        let mut item_ids = self.items_per_store.get(&store_id).unwrap_or_else(|| {
+            UnorderedSet::new(StorageKey::ItemsPerStoreInner {
+                store_hash: env::sha256(store_id.as_bytes()),
+            })
+        });
+        item_ids.insert(&item_id);
+        self.items_per_store.insert(&store_id, &item_ids);
+
+        let mut store_ids = self.stores_by_item.get(&item_id).unwrap_or_else(|| {
+            UnorderedSet::new(StorageKey::StoresByItemInner {
+                item_hash: env::sha256(item_id.as_bytes()),
+            })
+        });
+        store_ids.insert(&store_id);
+        self.stores_by_item.insert(&item_id, &store_ids);
+
+        let item_metadata = ItemMetadata {
+            name: item_name,
+            price: item_price,
+            img_url: item_img_url,
+            owner: store_id.clone(),
+            condition: item_condition,
+            upc: item_upc,
+            video_url: item_video_url,
+        };
+
+        // the price is denominated in the store's currency at listing time
+        let currency = self.store_currency.get(&store_id).flatten();
+        self.item_currency.insert(&storeanditem_id, &currency);
+        self.all_items.insert(&storeanditem_id);
+        self.item_created_at
+            .insert(&storeanditem_id, &env::block_timestamp());
+
+        // curated platforms keep new items unlisted until a moderator approves them
+        if self.require_item_approval {
+            self.items_pending_approval.insert(&storeanditem_id);
+        }
+
+        self.metadata_by_storeanditem_ids
+            .as_mut()
+            .and_then(|by_id| by_id.insert(&storeanditem_id, &item_metadata));
+    }
+
+    /// Remove an item and its metadata from a store, without access checks
+    fn remove_store_item_internal(&mut self, item_id: &ItemId, store_id: &AccountId) {
+        if self.item_by_store_id.get(item_id).as_ref() == Some(store_id) {
+            self.item_by_store_id.remove(item_id);
+        }
+
+        if let Some(mut item_ids) = self.items_per_store.get(store_id) {
+            item_ids.remove(item_id);
+            self.items_per_store.insert(store_id, &item_ids);
+        }
+
+        if let Some(mut store_ids) = self.stores_by_item.get(item_id) {
+            store_ids.remove(store_id);
+            if store_ids.is_empty() {
+                self.stores_by_item.remove(item_id);
+            } else {
+                self.stores_by_item.insert(item_id, &store_ids);
+            }
+        }
+
+        let storeanditem_id = store_and_item_id(store_id, item_id);
+        self.item_currency.remove(&storeanditem_id);
+        self.all_items.remove(&storeanditem_id);
+        self.item_created_at.remove(&storeanditem_id);
+        if let Some(upc) = self
+            .metadata_by_storeanditem_ids
+            .as_ref()
+            .and_then(|by_id| by_id.get(&storeanditem_id))
+            .and_then(|metadata| metadata.upc)
+        {
+            self.barcode_index.remove(&upc);
+        }
+        self.items_pending_approval.remove(&storeanditem_id);
+        self.internal_untag_item(&storeanditem_id);
+        self.item_extra_images.remove(&storeanditem_id);
+        self.item_price_history.remove(&storeanditem_id);
+
+        // an item relisted under the same id starts without the old listing's settings
+        self.item_charity_splits.remove(&storeanditem_id);
+        self.item_ft_requirements.remove(&storeanditem_id);
+        self.item_nft_requirements.remove(&storeanditem_id);
+        self.item_shipping.remove(&storeanditem_id);
+        self.locked_items.remove(&storeanditem_id);
+        self.age_restricted_items.remove(&storeanditem_id);
+        self.dynamic_pricing.remove(&storeanditem_id);
+        self.item_cost_basis.remove(&storeanditem_id);
+        self.item_min_margin_bps.remove(&storeanditem_id);
+        self.item_rental_rates.remove(&storeanditem_id);
+        self.item_crosschain_links.remove(&storeanditem_id);
+        self.item_purchase_limits.remove(&storeanditem_id);
+        self.item_cooldowns.remove(&storeanditem_id);
+        self.item_supply_caps.remove(&storeanditem_id);
+        self.item_purchase_counts.remove(&storeanditem_id);
+        if let Some(metadata) = self
+            .metadata_by_storeanditem_ids
+            .as_ref()
+            .and_then(|by_id| by_id.get(&storeanditem_id))
+        {
+            self.internal_unindex_item_name(store_id, &metadata.name, item_id);
+        }
+        self.metadata_by_storeanditem_ids
+            .as_mut()
+            .and_then(|by_id| by_id.remove(&storeanditem_id));
+    }
+
+    /// Add a new audit log to the contract's state
+    fn add_log_internal(
+        &mut self,
+        action: String,
+        actor: String,
+        entity: String,
+        extra: String,
+    ) -> String {
+        // the nonce keeps ids unique when an entity logs twice in the same block
+        let log_id = format!(
+            "{}{}{}{}{}",
+            entity,
+            DELIMETER,
+            env::block_timestamp(),
+            DELIMETER,
+            self.log_nonce
+        );
+        self.log_nonce += 1;
+
+        let log = Log {
+            id: log_id.clone(),
+            timestamp: env::block_timestamp(),
+            action,
+            actor,
+            entity,
+            extra,
+            prev_hash: self.log_chain_head,
+        };
+
+        require!(
+            !self.audit_logs.contains(&log),
+            "StoreHub: log already exists"
+        );
+        self.audit_logs.insert(&log);
+        self.log_chain_head = Some(log_chain_hash(&log.prev_hash, &log_id));
+
+        log_id
+    }
+
+    /// Add a sale amount to a store's total revenue
+    fn internal_add_store_revenue(&mut self, store_id: &AccountId, amount: u128) {
+        let revenue = self.store_revenue.get(store_id).unwrap_or(0);
+        self.store_revenue.insert(store_id, &(revenue + amount));
+    }
+
+    /// Credit funds held by the contract on behalf of a store
+    fn internal_credit_store_balance(&mut self, store_id: &AccountId, amount: u128) {
+        let balance = self.store_balances.get(store_id).unwrap_or(0);
+        self.store_balances.insert(store_id, &(balance + amount));
+    }
+
+    /// Describe a store, without access checks
+    fn internal_set_store_metadata(
+        &mut self,
+        store_id: &AccountId,
+        name: String,
+        description: String,
+        logo_url: String,
+        website_url: String,
+    ) {
+        require!(!name.is_empty(), "StoreHub: store name can't be empty");
+
+        let metadata = StoreMetadata {
+            name,
+            description,
+            logo_url,
+            website_url,
+        };
+        self.store_metadata.insert(store_id, &metadata);
+    }
+
+    /// Drop an item from the tag index
+    fn internal_untag_item(&mut self, storeanditem_id: &StoreAndItemIds) {
+        for tag in self.item_tags.remove(storeanditem_id).unwrap_or_default() {
+            if let Some(mut tagged) = self.tag_to_items.get(&tag) {
+                tagged.remove(storeanditem_id);
+                if tagged.is_empty() {
+                    self.tag_to_items.remove(&tag);
+                } else {
+                    self.tag_to_items.insert(&tag, &tagged);
+                }
+            }
+        }
+    }
+
+    /// Take the overseer multisig out of the state to update it, or panic
+    fn internal_take_overseer_multisig(&mut self) -> OverseerMultisig {
+        self.overseer_multisig
+            .take()
+            .unwrap_or_else(|| panic_with(StoreHubError::MultisigNotFound))
+    }
+
+    /// Add a pending admin action approved by the signer, returning its id
+    fn internal_propose_admin_action(
+        &mut self,
+        action_type: String,
+        action_data: String,
+    ) -> String {
+        let signer_id = env::signer_account_id();
+        let mut multisig = self.internal_take_overseer_multisig();
+        require!(
+            multisig.accounts.contains(&signer_id),
+            "StoreHub: not a multisig member"
+        );
+
+        let action_id = format!("admin_action{}{}", DELIMETER, multisig.next_action_id);
+        multisig.next_action_id += 1;
+        multisig.pending_actions.insert(
+            &action_id,
+            &MultiSigAction {
+                id: action_id.clone(),
+                action_type,
+                action_data,
+                proposer: signer_id.clone(),
+                approvals: vec![],
+            },
+        );
+        self.overseer_multisig = Some(multisig);
+
+        self.approve_admin_action(action_id.clone());
+
+        action_id
+    }
+
+    /// Apply an admin action approved by the multisig
+    fn internal_execute_admin_action(&mut self, action: &MultiSigAction) {
+        match action.action_type.as_str() {
+            "set_protocol_fee" => self.internal_set_protocol_fee(
+                action
+                    .action_data
+                    .parse()
+                    .unwrap_or_else(|_| panic_with(StoreHubError::InternalError)),
+            ),
+            "add_ft" => {
+                let ft_account_id: AccountId = action
+                    .action_data
+                    .parse()
+                    .unwrap_or_else(|_| panic_with(StoreHubError::InternalError));
+                self.approved_ft_token_ids.insert(&ft_account_id);
+            }
+            "set_overseer" => {
+                self.overseer_id = action
+                    .action_data
+                    .parse()
+                    .unwrap_or_else(|_| panic_with(StoreHubError::InternalError));
+            }
+            "withdraw_treasury" => self.internal_withdraw_treasury(
+                action
+                    .action_data
+                    .parse()
+                    .unwrap_or_else(|_| panic_with(StoreHubError::InternalError)),
+            ),
+            _ => panic_with(StoreHubError::InternalError),
+        }
+
+        self.add_log_internal(
+            "execute_admin_action".to_string(),
+            env::signer_account_id().to_string(),
+            action.id.clone(),
+            json!({
+                "action_type": action.action_type,
+                "action_data": action.action_data,
+                "approvals": action.approvals,
+            })
+            .to_string(),
+        );
+    }
+
+    /// Set the protocol fee, in basis points
+    fn internal_set_protocol_fee(&mut self, fee_bps: u16) {
+        require!(
+            fee_bps <= MAX_PROTOCOL_FEE_BPS,
+            "StoreHub: protocol fee too high"
+        );
+        self.protocol_fee_bps = fee_bps;
+    }
+
+    /// Send accumulated protocol fees to the overseer
+    fn internal_withdraw_treasury(&mut self, amount: u128) {
+        require!(
+            amount <= self.treasury_balance,
+            "StoreHub: insufficient treasury balance"
+        );
+        self.treasury_balance -= amount;
+
+        Promise::new(self.overseer_id.clone()).transfer(amount);
+    }
+
+    /// Panic unless the signer is the overseer, or a multisig member once the multisig is set up
+    fn assert_governance_admin(&self) {
+        match &self.overseer_multisig {
+            Some(multisig) => require!(
+                multisig.accounts.contains(&env::signer_account_id()),
+                "StoreHub: not a multisig member"
+            ),
+            None => self.assert_overseer(),
+        }
+    }
+
+    /// Panic if critical overseer actions must go through the multisig
+    fn assert_no_overseer_multisig(&self) {
+        require!(
+            self.overseer_multisig.is_none(),
+            "StoreHub: action requires multisig approval"
+        );
+    }
+
+    /// Check a store still lists an item and hasn't sold it
+    fn internal_store_owns_item(&self, store_id: &AccountId, item_id: &ItemId) -> bool {
+        self.item_by_store_id.get(item_id).as_ref() == Some(store_id)
+            && self
+                .internal_get_item_metadata(&store_and_item_id(store_id, item_id))
+                .owner
+                == *store_id
+    }
+
+    /// Refund every participant of a group buy and close it
+    fn internal_refund_group_buy(&mut self, group_buy_id: &String, group_buy: &GroupBuy) {
+        for (participant, deposit) in group_buy.participants.iter().zip(group_buy.deposits.iter()) {
+            Promise::new(participant.clone()).transfer(*deposit);
+        }
+        self.group_buys.remove(group_buy_id);
+    }
+
+    /// Retrieve an item's metadata by its `STOREID + DELIMITER + ITEM_ID` key
+    fn internal_get_item_metadata(&self, storeanditem_id: &StoreAndItemIds) -> ItemMetadata {
+        match &self.metadata_by_storeanditem_ids {
+            Some(by_id) => by_id
+                .get(storeanditem_id)
+                .unwrap_or_else(|| panic_with(StoreHubError::ItemNotFound)),
+            None => panic_with(StoreHubError::InternalError),
+        }
+    }
+
+    /// Persist an item's metadata under its `STOREID + DELIMITER + ITEM_ID` key
+    fn internal_set_item_metadata(
+        &mut self,
+        storeanditem_id: &StoreAndItemIds,
+        metadata: &ItemMetadata,
+    ) {
+        match &mut self.metadata_by_storeanditem_ids {
+            Some(by_id) => {
+                by_id.insert(storeanditem_id, metadata);
+            }
+            None => panic_with(StoreHubError::InternalError),
+        }
+    }
+
+    /// Retrieve the ids of all items listed under a store
+    fn internal_store_item_ids(&self, store_id: &AccountId) -> Vec<ItemId> {
+        match self.items_per_store.get(store_id) {
+            Some(item_ids) => item_ids.iter().collect(),
+            None => vec![],
+        }
+    }
+
+    /// Retrieve the number of items a store lists
+    fn internal_store_item_count(&self, store_id: &AccountId) -> u64 {
+        self.items_per_store
+            .get(store_id)
+            .map_or(0, |item_ids| item_ids.len())
+    }
+
+    /// Retrieve all items of a store along with their purchase counts
+    fn internal_store_items_with_sales(&self, store_id: &AccountId) -> Vec<(ItemId, u64)> {
+        self.internal_store_item_ids(store_id)
+            .into_iter()
+            .map(|item_id| {
+                let count = self
+                    .item_purchase_counts
+                    .get(&store_and_item_id(store_id, &item_id))
+                    .unwrap_or(0);
+                (item_id, count)
+            })
+            .collect()
+    }
+
+    /// Panic unless the predecessor is one of the store's owners
+    fn assert_store_owner(&self, store_id: &AccountId) {
+        let signer_id = env::predecessor_account_id();
+        self.assert_dao_caller(store_id, &signer_id);
+        match &self.owners_per_store_id {
+            Some(owners_per_store_id) => {
+                let is_owner = owners_per_store_id
+                    .get(store_id)
+                    .map_or(false, |owners_set| owners_set.contains(&signer_id));
+                if !is_owner {
+                    panic_with(StoreHubError::AccessDenied)
+                }
+            }
+            None => panic_with(StoreHubError::InternalError),
+        }
+    }
+
+    /// Retrieve an account's role in a store, owners being tracked in the owners set
+    fn internal_store_role(
+        &self,
+        store_id: &AccountId,
+        account_id: &AccountId,
+    ) -> Option<StoreRole> {
+        let is_owner = self
+            .owners_per_store_id
+            .as_ref()
+            .map_or(false, |owners_per_store_id| {
+                owners_per_store_id
+                    .get(store_id)
+                    .map_or(false, |owners_set| owners_set.contains(account_id))
+            });
+        if is_owner {
+            return Some(StoreRole::Owner);
+        }
+
+        self.store_roles
+            .get(&(store_id.clone(), account_id.clone()))
+    }
+
+    /// Panic if the store is controlled by a DAO contract other than the caller
+    fn assert_dao_caller(&self, store_id: &AccountId, caller_id: &AccountId) {
+        if let Some(dao_contract) = self.dao_controlled_stores.get(store_id) {
+            if &dao_contract != caller_id {
+                panic_with(StoreHubError::AccessDenied)
+            }
+        }
+    }
+
+    /// Panic unless the predecessor is one of the store's owners or managers
+    fn assert_store_manager(&self, store_id: &AccountId) {
+        self.assert_dao_caller(store_id, &env::predecessor_account_id());
+        match self.internal_store_role(store_id, &env::predecessor_account_id()) {
+            Some(StoreRole::Owner) | Some(StoreRole::Manager) => {}
+            _ => panic_with(StoreHubError::AccessDenied),
+        }
+    }
+
+    /// Check the store's geo policy accepts the buyer's attested region
+    fn internal_check_region(
+        &self,
+        store_id: &AccountId,
+        buyer_region: Option<String>,
+    ) -> Result<(), String> {
+        if let Some(policy) = self.store_geo_policies.get(store_id) {
+            let buyer_region =
+                buyer_region.ok_or_else(|| StoreHubError::BuyerRegionRequired.to_string())?;
+            let listed = policy.regions.contains(&buyer_region);
+            let allowed = match policy.mode {
+                GeoMode::Allow => listed,
+                GeoMode::Deny => !listed,
+            };
+            ensure(allowed, "StoreHub: region not allowed")?;
+        }
+        Ok(())
+    }
+
+    /// Panic unless the signer is the contract's overseer
+    fn assert_overseer(&self) {
+        require!(
+            env::signer_account_id().eq(&self.overseer_id),
+            "StoreHub: access denied"
+        );
+    }
+}
+
+/// Build the `STOREID + DELIMITER + ITEM_ID` key of an item
+pub fn store_and_item_id(store_id: &AccountId, item_id: &str) -> StoreAndItemIds {
+    format!("{}{}{}", store_id, DELIMETER, item_id)
+}
+
+/// Check whether a log entity is the store itself or one of its `STOREID + DELIMITER + ITEM_ID`
+pub fn is_store_entity(entity: &str, store_id: &AccountId) -> bool {
+    match entity.strip_prefix(store_id.as_str()) {
+        Some(rest) => rest.is_empty() || rest.starts_with(DELIMETER),
+        None => false,
+    }
+}
+
+/// Normalize an item name for URLs: lowercase, with whitespace runs turned into dashes
+pub fn normalize_item_name(name: &str) -> String {
+    name.to_lowercase()
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join("-")
+}
+
+/// Check whether a rental's duration has elapsed
+fn rental_expired(rental: &ItemRental) -> bool {
+    let duration = rental.duration_seconds.saturating_mul(1_000_000_000);
+    env::block_timestamp() >= rental.started_at.saturating_add(duration)
+}
+
+/// Check whether a barcode is a 12-digit UPC-A or 13-digit EAN-13 code
+fn is_valid_upc(upc: &str) -> bool {
+    (upc.len() == 12 || upc.len() == 13) && upc.bytes().all(|b| b.is_ascii_digit())
+}
+
+/// Check whether a URL is served over https
+fn is_https_url(url: &str) -> bool {
+    url.strip_prefix("https://")
+        .map_or(false, |rest| !rest.is_empty())
+}
+
+/// Check whether a review photo or video is served over https
+fn is_valid_review_media(media: &ReviewMedia) -> bool {
+    is_https_url(&media.url) && matches!(media.media_type.as_str(), "image" | "video")
+}
+
+/// Check whether a store's sale covers the current block
+fn is_sale_active(sale_period: &StoreSalePeriod) -> bool {
+    let now = env::block_timestamp();
+    sale_period.start_time <= now && now < sale_period.end_time
+}
+
+/// Read a yoctoNEAR amount logged either as a JSON number or as a `U128` string
+fn json_amount(value: &serde_json::Value) -> u128 {
+    match value {
+        serde_json::Value::String(amount) => amount.parse().ok(),
+        serde_json::Value::Number(amount) => amount.as_u64().map(u128::from),
+        _ => None,
+    }
+    .unwrap_or_else(|| panic_with(StoreHubError::InternalError))
+}
+
+/// Abort execution with a typed contract error
+pub fn panic_with(err: StoreHubError) -> ! {
+    env::panic_str(&err.to_string())
+}
+
+/// `require!` for checks whose failure is handled by the caller
+fn ensure(condition: bool, message: &str) -> Result<(), String> {
+    if condition {
+        Ok(())
+    } else {
+        Err(message.to_string())
+    }
+}
+
+/// Hash linking a log to the one recorded before it
+fn log_chain_hash(prev_hash: &Option<[u8; 32]>, log_id: &str) -> [u8; 32] {
+    let mut bytes = prev_hash.map_or(vec![], |hash| hash.to_vec());
+    bytes.extend_from_slice(log_id.as_bytes());
+
+    env::sha256_array(&bytes)
+}
+
+/// Emit a NEP-297 event log that indexers and monitoring systems can pick up
+fn emit_event(event: &str, data: serde_json::Value) {
+    let event = json!({
+        "standard": EVENT_STANDARD,
+        "version": EVENT_VERSION,
+        "event": event,
+        "data": [data],
+    });
+    env::log_str(&format!("EVENT_JSON:{}", event));
+}
+
+pub fn test_account() -> AccountId {
+    AccountId::new_unchecked("testnet".to_string())
+}
+
+#[cfg(all(test, not(target_arch = "wasm32")))]
+mod tests {
+    use super::*;
+    use near_sdk::json_types::U128;
+    use near_sdk::mock::VmAction;
+    use near_sdk::test_utils::{accounts, get_created_receipts, get_logs, VMContextBuilder};
+    use near_sdk::testing_env;
+
+    #[test]
+    fn test_init_contract() {
+        let context = VMContextBuilder::new();
+        testing_env!(context.build());
+
+        let contract = Contract::new(accounts(0));
+
+        assert_eq!(contract.overseer_id, accounts(0));
+    }
+
+    #[test]
+    fn test_create_store() {
+        let mut context = VMContextBuilder::new();
+        testing_env!(context.signer_account_id(accounts(1)).build());
+
+        let mut contract = Contract::new(accounts(0));
+        contract.create_store(accounts(2));
+
+        assert_eq!(contract.get_stores_by_account_id(), vec![accounts(2)]);
+    }
+
+    #[test]
+    fn test_add_store_owners() {
+        let mut context = VMContextBuilder::new();
+        testing_env!(context.signer_account_id(accounts(1)).build());
+
+        let mut contract = Contract::new(accounts(0));
+        contract.create_store(accounts(2));
+        contract.add_store_owners(accounts(2), accounts(3));
+
+        let owners = contract.get_owners_by_store_id(accounts(2));
+
+        assert!(owners.contains(&accounts(3)));
+    }
+
+    #[test]
+    fn test_add_store_owners_batch() {
+        let mut context = VMContextBuilder::new();
+        testing_env!(context.predecessor_account_id(accounts(1)).build());
+
+        let mut contract = Contract::new(accounts(0));
+        contract.create_store(accounts(2));
+        contract.add_store_owners_batch(accounts(2), vec![accounts(3), accounts(4), accounts(5)]);
+
+        let owners = contract.get_owners_by_store_id(accounts(2));
+
+        for owner in [1, 3, 4, 5] {
+            assert!(owners.contains(&accounts(owner)));
+        }
+    }
+
+    #[test]
+    fn test_add_store_item() {
+        let mut context = VMContextBuilder::new();
+        testing_env!(context.signer_account_id(accounts(1)).build());
+
+        let mut contract = Contract::new(accounts(0));
+        contract.create_store(accounts(2));
+
+        let item_id = "item1".to_string();
+        let item_name = "item_name".to_string();
+        let item_price = U128(1000);
+        let item_img_url = "http://image.url".to_string();
+
+        contract.add_store_item(
+            item_id.clone(),
+            accounts(2),
+            item_name.clone(),
+            item_price,
+            item_img_url.clone(),
+            None,
+            None,
+            None,
+        );
+
+        let item = contract.get_item_metadata(item_id.clone(), accounts(2));
+        match item {
+            Some(metadata) => {
+                assert_eq!(metadata.name, item_name);
+                assert_eq!(metadata.price, item_price);
+                assert_eq!(metadata.img_url, item_img_url);
+                assert_eq!(metadata.owner, accounts(2));
+            }
+            None => {
+                panic!("item doesn't exist");
+            }
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "StoreHub: item doesn't exist")]
+    fn test_buy_nonexistent_item() {
+        let mut context = VMContextBuilder::new();
+        testing_env!(context
+            .signer_account_id(accounts(1))
+            .attached_deposit(1000)
+            .build());
+
+        let mut contract = Contract::new(accounts(0));
+        contract.create_store(accounts(2));
+
+        contract.buy("item1".to_string(), accounts(2));
+    }
+
+    #[test]
+    #[should_panic(expected = "StoreHub: this item doesn't exist for this store")]
+    fn test_buy_existent_wrong_store_item() {
+        let mut context = VMContextBuilder::new();
+        testing_env!(context
+            .signer_account_id(accounts(1))
+            .attached_deposit(1000)
+            .build());
+
+        let mut contract = Contract::new(accounts(0));
+        contract.create_store(accounts(2));
+
+        let item_id = "item1".to_string();
+        let item_name = "item_name".to_string();
+        let item_price = U128(1000);
+        let item_img_url = "http://image.url".to_string();
+
+        contract.add_store_item(
+            item_id.clone(),
+            accounts(2),
+            item_name.clone(),
+            item_price,
+            item_img_url.clone(),
+            None,
+            None,
+            None,
+        );
+
+        contract.buy("item1".to_string(), accounts(3));
+    }
+
+    #[test]
+    #[should_panic(expected = "StoreHub: can't buy owned item")]
+    fn test_buy_owned_item() {
+        let mut context = VMContextBuilder::new();
+        testing_env!(context.signer_account_id(accounts(1)).build());
+
+        let mut contract = Contract::new(accounts(0));
+        contract.create_store(accounts(2));
+        contract.add_store_item(
+            "item1".to_string(),
+            accounts(2),
+            "item_name".to_string(),
+            U128(1000),
+            "http://image.url".to_string(),
+            None,
+            None,
+            None,
+        );
+
+        testing_env!(context
+            .signer_account_id(accounts(2))
+            .attached_deposit(2000)
+            .build());
+
+        contract.buy("item1".to_string(), accounts(2));
+    }
+
+    #[test]
+    #[should_panic(expected = "StoreHub: deposit is below price")]
+    fn test_buy_with_insufficient_deposit() {
+        let mut context = VMContextBuilder::new();
+        testing_env!(context
+            .signer_account_id(accounts(3))
+            .attached_deposit(500)
+            .build());
+
+        let mut contract = Contract::new(accounts(0));
+        contract.create_store(accounts(2));
+        contract.add_store_item(
+            "item1".to_string(),
+            accounts(2),
+            "item_name".to_string(),
+            U128(1000),
+            "http://image.url".to_string(),
+            None,
+            None,
+            None,
+        );
+
+        contract.buy("item1".to_string(), accounts(2));
+    }
+
+    #[test]
+    fn test_buy_item() {
+        let mut context = VMContextBuilder::new();
+        testing_env!(context
+            .signer_account_id(accounts(3))
+            .attached_deposit(2000)
+            .build());
+
+        let mut contract = Contract::new(accounts(0));
+        contract.create_store(accounts(2));
+        contract.add_store_item(
+            "item1".to_string(),
+            accounts(2),
+            "item_name".to_string(),
+            U128(1000),
+            "http://image.url".to_string(),
+            None,
+            None,
+            None,
+        );
+
+        let receipt = contract.buy("item1".to_string(), accounts(2));
+
+        assert_eq!(receipt.message, "your purchase is ready");
+        assert_eq!(
+            receipt.transaction_id,
+            format!("{}.0.0", store_and_item_id(&accounts(2), "item1"))
+        );
+        assert_eq!(receipt.item_id, "item1");
+        assert_eq!(receipt.store_id, accounts(2));
+        assert_eq!(receipt.buyer, accounts(3));
+        assert_eq!(receipt.price_paid, U128(2000));
+    }
+
+    #[test]
+    #[should_panic(expected = "StoreHub: deposit below store minimum")]
+    fn test_buy_below_store_minimum_deposit() {
+        let mut context = VMContextBuilder::new();
+        testing_env!(context
+            .signer_account_id(accounts(3))
+            .attached_deposit(2000)
+            .build());
+
+        let mut contract = Contract::new(accounts(0));
+        contract.create_store(accounts(2));
+        contract.add_store_item(
+            "item1".to_string(),
+            accounts(2),
+            "item_name".to_string(),
+            U128(1000),
+            "http://image.url".to_string(),
+            None,
+            None,
+            None,
+        );
+        contract.set_store_minimum_deposit(accounts(2), U128(5000));
+
+        assert_eq!(contract.get_store_minimum_deposit(accounts(2)), U128(5000));
+
+        contract.buy("item1".to_string(), accounts(2));
+    }
+
+    #[test]
+    fn test_proxy_buy() {
+        let mut context = VMContextBuilder::new();
+        testing_env!(context
+            .signer_account_id(accounts(3))
+            .attached_deposit(2000)
+            .build());
+
+        let mut contract = Contract::new(accounts(0));
+        contract.create_store(accounts(2));
+        contract.add_store_item(
+            "item1".to_string(),
+            accounts(2),
+            "item_name".to_string(),
+            U128(1000),
+            "http://image.url".to_string(),
+            None,
+            None,
+            None,
+        );
+
+        contract.proxy_buy("item1".to_string(), accounts(2), accounts(4));
+
+        let item = contract
+            .get_item_metadata("item1".to_string(), accounts(2))
+            .unwrap();
+        assert_eq!(item.owner, accounts(4));
+        assert_eq!(
+            contract.get_buyer_purchases(accounts(3), None, None),
+            vec![store_and_item_id(&accounts(2), "item1")]
+        );
+        assert!(contract
+            .get_buyer_purchases(accounts(4), None, None)
+            .is_empty());
+    }
+
+    #[test]
+    fn test_store_items_by_sales() {
+        let mut context = VMContextBuilder::new();
+        testing_env!(context.signer_account_id(accounts(1)).build());
+
+        let mut contract = Contract::new(accounts(0));
+        contract.create_store(accounts(2));
+        for item_id in ["item1", "item2", "item3"] {
+            contract.add_store_item(
+                item_id.to_string(),
+                accounts(2),
+                item_id.to_string(),
+                U128(1000),
+                "http://image.url".to_string(),
+                None,
+                None,
+                None,
+            );
+        }
+
+        for (buyer, item_id) in [(3, "item2"), (4, "item2"), (3, "item3")] {
+            testing_env!(context
+                .signer_account_id(accounts(buyer))
+                .attached_deposit(1000)
+                .build());
+            contract.buy(item_id.to_string(), accounts(2));
+        }
+
+        assert_eq!(
+            contract.get_store_items_by_sales(accounts(2), 2),
+            vec![("item2".to_string(), 2), ("item3".to_string(), 1)]
+        );
+        assert_eq!(
+            contract.get_item_sales_rank("item2".to_string(), accounts(2)),
+            1
+        );
+        assert_eq!(
+            contract.get_item_sales_rank("item3".to_string(), accounts(2)),
+            2
+        );
+        assert_eq!(
+            contract.get_item_sales_rank("item1".to_string(), accounts(2)),
+            3
+        );
+    }
+
+    #[test]
+    fn test_buy_with_affiliate() {
+        let mut context = VMContextBuilder::new();
+        testing_env!(context.signer_account_id(accounts(1)).build());
+
+        let mut contract = Contract::new(accounts(0));
+        contract.create_store(accounts(2));
+        contract.add_store_item(
+            "item1".to_string(),
+            accounts(2),
+            "item_name".to_string(),
+            U128(1000),
+            "http://image.url".to_string(),
+            None,
+            None,
+            None,
+        );
+        contract.create_affiliate_code("PROMO".to_string(), accounts(2), accounts(5), 500);
+
+        testing_env!(context
+            .signer_account_id(accounts(3))
+            .attached_deposit(1000)
+            .build());
+        contract.buy_with_affiliate("item1".to_string(), accounts(2), "PROMO".to_string());
+
+        assert_eq!(contract.get_affiliate_earnings(accounts(5)), U128(50));
+    }
+
+    #[test]
+    #[should_panic(expected = "StoreHub: affiliate code is inactive")]
+    fn test_buy_with_deactivated_affiliate() {
+        let mut context = VMContextBuilder::new();
+        testing_env!(context.signer_account_id(accounts(1)).build());
+
+        let mut contract = Contract::new(accounts(0));
+        contract.create_store(accounts(2));
+        contract.add_store_item(
+            "item1".to_string(),
+            accounts(2),
+            "item_name".to_string(),
+            U128(1000),
+            "http://image.url".to_string(),
+            None,
+            None,
+            None,
+        );
+        contract.create_affiliate_code("PROMO".to_string(), accounts(2), accounts(5), 500);
+        contract.deactivate_affiliate_code("PROMO".to_string());
+
+        testing_env!(context
+            .signer_account_id(accounts(3))
+            .attached_deposit(1000)
+            .build());
+        contract.buy_with_affiliate("item1".to_string(), accounts(2), "PROMO".to_string());
+    }
+
+    #[test]
+    fn test_add_transaction() {
+        let mut context = VMContextBuilder::new();
+        testing_env!(context.signer_account_id(accounts(1)).build());
+
+        let mut contract = Contract::new(accounts(0));
+        let log_id = contract.add_log_internal(
+            "action".to_string(),
+            "actor".to_string(),
+            "entity".to_string(),
+            "extra".to_string(),
+        );
+
+        let log = contract.get_log(log_id.clone());
+
+        assert_eq!(log.id, log_id);
+        assert_eq!(log.action, "action");
+        assert_eq!(log.actor, "actor");
+        assert_eq!(log.entity, "entity");
+        assert_eq!(log.extra, "extra");
+    }
+
+    #[test]
+    #[should_panic(expected = "StoreHub: access denied")]
+    fn test_add_ft_denied() {
+        let mut context = VMContextBuilder::new();
+        testing_env!(context.signer_account_id(accounts(1)).build());
+
+        let mut contract = Contract::new(accounts(0));
+        contract.add_ft(accounts(2));
+    }
+
+    #[test]
+    fn test_add_ft_approved() {
+        let mut context = VMContextBuilder::new();
+        testing_env!(context.signer_account_id(accounts(0)).build());
+
+        let mut contract = Contract::new(accounts(0));
+        contract.add_ft(accounts(2));
+
+        assert!(contract.is_ft_approved(accounts(2)));
+    }
+
+    #[test]
+    fn test_overseer_override_item_price() {
+        let mut context = VMContextBuilder::new();
+        testing_env!(context.signer_account_id(accounts(1)).build());
+
+        let mut contract = Contract::new(accounts(0));
+        contract.create_store(accounts(2));
+        contract.add_store_item(
+            "item1".to_string(),
+            accounts(2),
+            "item_name".to_string(),
+            U128(1000),
+            "http://image.url".to_string(),
+            None,
+            None,
+            None,
+        );
+
+        testing_env!(context.signer_account_id(accounts(0)).build());
+        contract.overseer_override_item_price("item1".to_string(), accounts(2), U128(10));
+
+        let item = contract
+            .get_item_metadata("item1".to_string(), accounts(2))
+            .unwrap();
+        assert_eq!(item.price, U128(10));
+
+        let logs = get_logs();
+        assert!(logs[0].starts_with("EVENT_JSON:"));
+        assert!(logs[0].contains("\"event\":\"overseer_price_override\""));
+    }
+
+    #[test]
+    #[should_panic(expected = "StoreHub: access denied")]
+    fn test_overseer_override_item_price_denied() {
+        let mut context = VMContextBuilder::new();
+        testing_env!(context.signer_account_id(accounts(1)).build());
+
+        let mut contract = Contract::new(accounts(0));
+        contract.create_store(accounts(2));
+        contract.add_store_item(
+            "item1".to_string(),
+            accounts(2),
+            "item_name".to_string(),
+            U128(1000),
+            "http://image.url".to_string(),
+            None,
+            None,
+            None,
+        );
+
+        contract.overseer_override_item_price("item1".to_string(), accounts(2), U128(10));
+    }
+
+    #[test]
+    fn test_get_stores_for_account() {
+        let mut context = VMContextBuilder::new();
+        testing_env!(context.predecessor_account_id(accounts(1)).build());
+
+        let mut contract = Contract::new(accounts(0));
+        contract.create_store(accounts(2));
+        contract.create_store(accounts(3));
+
+        testing_env!(context.predecessor_account_id(accounts(4)).build());
+
+        assert!(contract.get_stores_by_account_id().is_empty());
+        assert_eq!(
+            contract.get_stores_for_account(accounts(1), None, None),
+            vec![accounts(2), accounts(3)]
+        );
+        assert_eq!(
+            contract.get_stores_for_account(accounts(1), Some(1), Some(1)),
+            vec![accounts(3)]
+        );
+        assert_eq!(contract.get_store_count_for_account(accounts(1)), 2);
+        assert_eq!(contract.get_store_count_for_account(accounts(4)), 0);
+    }
+
+    #[test]
+    fn test_add_log_same_block_unique_ids() {
+        let context = VMContextBuilder::new();
+        testing_env!(context.build());
+
+        let mut contract = Contract::new(accounts(0));
+        let first_id = contract.add_log_internal(
+            "action".to_string(),
+            "actor".to_string(),
+            "entity".to_string(),
+            "extra".to_string(),
+        );
+        let second_id = contract.add_log_internal(
+            "action".to_string(),
+            "actor".to_string(),
+            "entity".to_string(),
+            "extra".to_string(),
+        );
+
+        assert_eq!(first_id, "entity.0.0");
+        assert_eq!(second_id, "entity.0.1");
+        assert_eq!(contract.get_log(second_id).id, "entity.0.1");
+    }
+
+    #[test]
+    fn test_get_logs_by_store_and_action() {
+        let context = VMContextBuilder::new();
+        testing_env!(context.build());
+
+        let mut contract = Contract::new(accounts(0));
+        let store_item = store_and_item_id(&accounts(2), "item1");
+        let other_store_item = store_and_item_id(&accounts(3), "item1");
+        for (action, entity) in [
+            ("buy", &store_item),
+            ("refund", &store_item),
+            ("buy", &other_store_item),
+            ("buy", &store_item),
+        ] {
+            contract.add_log_internal(
+                action.to_string(),
+                "actor".to_string(),
+                entity.clone(),
+                "extra".to_string(),
+            );
+        }
+
+        let logs =
+            contract.get_logs_by_store_and_action(accounts(2), "buy".to_string(), None, None);
+        assert_eq!(logs.len(), 2);
+        assert!(logs
+            .iter()
+            .all(|log| log.action == "buy" && log.entity == store_item));
+        assert_eq!(
+            contract.get_logs_count_by_store_and_action(accounts(2), "buy".to_string()),
+            2
+        );
+        assert_eq!(
+            contract
+                .get_logs_by_store_and_action(accounts(2), "buy".to_string(), Some(1), Some(5))
+                .len(),
+            1
+        );
+    }
+
+    #[test]
+    fn test_scheduled_price_change() {
+        let mut context = VMContextBuilder::new();
+        testing_env!(context.signer_account_id(accounts(1)).build());
+
+        let mut contract = Contract::new(accounts(0));
+        contract.create_store(accounts(2));
+        contract.add_store_item(
+            "item1".to_string(),
+            accounts(2),
+            "item_name".to_string(),
+            U128(1000),
+            "http://image.url".to_string(),
+            None,
+            None,
+            None,
+        );
+
+        let schedule_id =
+            contract.schedule_price_change("item1".to_string(), accounts(2), U128(500), 100);
+        assert_eq!(
+            contract
+                .get_pending_price_changes_by_store(accounts(2))
+                .len(),
+            1
+        );
+
+        testing_env!(context
+            .predecessor_account_id(accounts(5))
+            .block_timestamp(100)
+            .build());
+        contract.execute_scheduled_price_change(schedule_id);
+
+        let item = contract
+            .get_item_metadata("item1".to_string(), accounts(2))
+            .unwrap();
+        assert_eq!(item.price, U128(500));
+        assert!(contract
+            .get_pending_price_changes_by_store(accounts(2))
+            .is_empty());
+    }
+
+    #[test]
+    #[should_panic(expected = "StoreHub: scheduled price change not yet due")]
+    fn test_scheduled_price_change_not_due() {
+        let mut context = VMContextBuilder::new();
+        testing_env!(context.signer_account_id(accounts(1)).build());
+
+        let mut contract = Contract::new(accounts(0));
+        contract.create_store(accounts(2));
+        contract.add_store_item(
+            "item1".to_string(),
+            accounts(2),
+            "item_name".to_string(),
+            U128(1000),
+            "http://image.url".to_string(),
+            None,
+            None,
+            None,
+        );
+
+        let schedule_id =
+            contract.schedule_price_change("item1".to_string(), accounts(2), U128(500), 100);
+        contract.execute_scheduled_price_change(schedule_id);
+    }
+
+    #[test]
+    fn test_cancel_scheduled_price_change() {
+        let mut context = VMContextBuilder::new();
+        testing_env!(context.signer_account_id(accounts(1)).build());
+
+        let mut contract = Contract::new(accounts(0));
+        contract.create_store(accounts(2));
+        contract.add_store_item(
+            "item1".to_string(),
+            accounts(2),
+            "item_name".to_string(),
+            U128(1000),
+            "http://image.url".to_string(),
+            None,
+            None,
+            None,
+        );
+
+        let schedule_id =
+            contract.schedule_price_change("item1".to_string(), accounts(2), U128(500), 100);
+        contract.cancel_scheduled_price_change(schedule_id);
+
+        assert!(contract
+            .get_pending_price_changes_by_store(accounts(2))
+            .is_empty());
+    }
+
+    fn setup_group_buy(context: &mut VMContextBuilder) -> (Contract, String) {
+        testing_env!(context
+            .signer_account_id(accounts(1))
+            .predecessor_account_id(accounts(1))
+            .build());
+
+        let mut contract = Contract::new(accounts(0));
+        contract.create_store(accounts(2));
+        contract.add_store_item(
+            "item1".to_string(),
+            accounts(2),
+            "item_name".to_string(),
+            U128(1000),
+            "http://image.url".to_string(),
+            None,
+            None,
+            None,
+        );
+        let group_buy_id =
+            contract.create_group_buy("item1".to_string(), accounts(2), 2, U128(800), 100);
+
+        (contract, group_buy_id)
+    }
+
+    #[test]
+    fn test_execute_group_buy() {
+        let mut context = VMContextBuilder::new();
+        let (mut contract, group_buy_id) = setup_group_buy(&mut context);
+
+        for buyer in [3, 4] {
+            testing_env!(context
+                .signer_account_id(accounts(buyer))
+                .attached_deposit(800)
+                .build());
+            contract.join_group_buy(group_buy_id.clone());
+        }
+
+        testing_env!(context.attached_deposit(0).build());
+        contract.execute_group_buy(group_buy_id.clone());
+
+        assert!(contract.get_group_buy(group_buy_id).unwrap().executed);
+        assert_eq!(contract.get_store_balance(accounts(2)), U128(1600));
+        assert_eq!(
+            contract.get_buyer_purchases(accounts(4), None, None),
+            vec![store_and_item_id(&accounts(2), "item1")]
+        );
+    }
+
+    #[test]
+    fn test_execute_group_buy_of_sold_item() {
+        let mut context = VMContextBuilder::new();
+        let (mut contract, group_buy_id) = setup_group_buy(&mut context);
+
+        for buyer in [3, 4] {
+            testing_env!(context
+                .signer_account_id(accounts(buyer))
+                .attached_deposit(800)
+                .build());
+            contract.join_group_buy(group_buy_id.clone());
+        }
+        testing_env!(context
+            .signer_account_id(accounts(5))
+            .attached_deposit(1000)
+            .build());
+        contract.buy("item1".to_string(), accounts(2));
+
+        testing_env!(context.attached_deposit(0).build());
+        contract.execute_group_buy(group_buy_id.clone());
+
+        assert!(contract.get_group_buy(group_buy_id).is_none());
+        assert_eq!(contract.get_store_balance(accounts(2)), U128(0));
+        let receipts = get_created_receipts();
+        assert_eq!(receipts.len(), 2);
+        assert_eq!(
+            receipts[1].actions,
+            vec![VmAction::Transfer { deposit: 800 }]
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "StoreHub: target exceeds remaining supply")]
+    fn test_create_group_buy_above_supply_cap() {
+        let mut context = VMContextBuilder::new();
+        let (mut contract, _) = setup_group_buy(&mut context);
+
+        contract.set_item_supply_cap("item1".to_string(), accounts(2), 1);
+        testing_env!(context.block_timestamp(1).build());
+        contract.create_group_buy("item1".to_string(), accounts(2), 2, U128(800), 100);
+    }
+
+    #[test]
+    #[should_panic(expected = "StoreHub: item supply exhausted")]
+    fn test_join_group_buy_beyond_supply_cap() {
+        let mut context = VMContextBuilder::new();
+        let (mut contract, group_buy_id) = setup_group_buy(&mut context);
+
+        contract.set_item_supply_cap("item1".to_string(), accounts(2), 1);
+        for buyer in [3, 4] {
+            testing_env!(context
+                .signer_account_id(accounts(buyer))
+                .attached_deposit(800)
+                .build());
+            contract.join_group_buy(group_buy_id.clone());
+        }
+    }
+
+    #[test]
+    fn test_execute_group_buy_beyond_remaining_supply() {
+        let mut context = VMContextBuilder::new();
+        let (mut contract, group_buy_id) = setup_group_buy(&mut context);
+
+        contract.set_item_supply_cap("item1".to_string(), accounts(2), 2);
+        for buyer in [3, 4] {
+            testing_env!(context
+                .signer_account_id(accounts(buyer))
+                .attached_deposit(800)
+                .build());
+            contract.join_group_buy(group_buy_id.clone());
+        }
+        contract
+            .item_purchase_counts
+            .insert(&store_and_item_id(&accounts(2), "item1"), &1);
+
+        testing_env!(context.attached_deposit(0).build());
+        contract.execute_group_buy(group_buy_id.clone());
+
+        assert!(contract.get_group_buy(group_buy_id).is_none());
+        assert_eq!(contract.get_store_balance(accounts(2)), U128(0));
+        assert_eq!(get_created_receipts().len(), 2);
+    }
+
+    #[test]
+    fn test_cancel_group_buy_refunds() {
+        let mut context = VMContextBuilder::new();
+        let (mut contract, group_buy_id) = setup_group_buy(&mut context);
+
+        testing_env!(context
+            .signer_account_id(accounts(3))
+            .attached_deposit(900)
+            .build());
+        contract.join_group_buy(group_buy_id.clone());
+        let receipts = get_created_receipts();
+        assert_eq!(receipts[0].receiver_id, accounts(3));
+        assert_eq!(
+            receipts[0].actions,
+            vec![VmAction::Transfer { deposit: 100 }]
+        );
+
+        testing_env!(context.attached_deposit(0).block_timestamp(100).build());
+        contract.cancel_group_buy(group_buy_id.clone());
+
+        let receipts = get_created_receipts();
+        assert_eq!(receipts.len(), 1);
+        assert_eq!(receipts[0].receiver_id, accounts(3));
+        assert_eq!(
+            receipts[0].actions,
+            vec![VmAction::Transfer { deposit: 800 }]
+        );
+        assert!(contract.get_group_buy(group_buy_id).is_none());
+    }
+
+    #[test]
+    #[should_panic(expected = "StoreHub: group buy deadline not reached")]
+    fn test_cancel_group_buy_before_deadline() {
+        let mut context = VMContextBuilder::new();
+        let (mut contract, group_buy_id) = setup_group_buy(&mut context);
+
+        contract.cancel_group_buy(group_buy_id);
+    }
+
+    #[test]
+    fn test_add_store_item_auto() {
+        let mut context = VMContextBuilder::new();
+        testing_env!(context.signer_account_id(accounts(1)).build());
+
+        let mut contract = Contract::new(accounts(0));
+        contract.create_store(accounts(2));
+        contract.add_store_item(
+            "charlie-1".to_string(),
+            accounts(2),
+            "item_name".to_string(),
+            U128(1000),
+            "http://image.url".to_string(),
+            None,
+            None,
+            None,
+        );
+
+        let item_id = contract.add_store_item_auto(
+            accounts(2),
+            "other_item_name".to_string(),
+            U128(500),
+            "http://image.url".to_string(),
+        );
+        assert_eq!(item_id, "charlie-2");
+
+        let item = contract.get_item_metadata(item_id, accounts(2)).unwrap();
+        assert_eq!(item.price, U128(500));
+        assert_eq!(item.owner, accounts(2));
+    }
+
+    #[test]
+    fn test_update_item_price_batch() {
+        let mut context = VMContextBuilder::new();
+        testing_env!(context.signer_account_id(accounts(1)).build());
+
+        let mut contract = Contract::new(accounts(0));
+        contract.create_store(accounts(2));
+        for item_id in ["item1", "item2"] {
+            contract.add_store_item(
+                item_id.to_string(),
+                accounts(2),
+                item_id.to_string(),
+                U128(1000),
+                "http://image.url".to_string(),
+                None,
+                None,
+                None,
+            );
+        }
+
+        let results = contract.update_item_price_batch(
+            accounts(2),
+            vec![
+                ("item1".to_string(), U128(10)),
+                ("missing".to_string(), U128(20)),
+                ("item2".to_string(), U128(30)),
+            ],
+            false,
+        );
+
+        assert_eq!(
+            results,
+            vec![
+                ("item1".to_string(), true),
+                ("missing".to_string(), false),
+                ("item2".to_string(), true)
+            ]
+        );
+        assert_eq!(
+            contract
+                .get_item_metadata("item2".to_string(), accounts(2))
+                .unwrap()
+                .price,
+            U128(30)
+        );
+        assert_eq!(
+            contract.get_logs_count_by_store_and_action(accounts(2), "price_update".to_string()),
+            2
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "StoreHub: this item doesn't exist for this store")]
+    fn test_update_item_price_batch_abort_on_error() {
+        let mut context = VMContextBuilder::new();
+        testing_env!(context.signer_account_id(accounts(1)).build());
+
+        let mut contract = Contract::new(accounts(0));
+        contract.create_store(accounts(2));
+
+        contract.update_item_price_batch(
+            accounts(2),
+            vec![("missing".to_string(), U128(20))],
+            true,
+        );
+    }
+
+    #[test]
+    fn test_get_item_metadata() {
+        let mut context = VMContextBuilder::new();
+        testing_env!(context.signer_account_id(accounts(1)).build());
+
+        let mut contract = Contract::new(accounts(0));
+        contract.create_store(accounts(2));
+        contract.add_store_item(
+            "item1".to_string(),
+            accounts(2),
+            "item_name".to_string(),
+            U128(1000),
+            "http://image.url".to_string(),
+            None,
+            None,
+            None,
+        );
+
+        let item = contract.get_item_metadata("item1".to_string(), accounts(2));
+        assert_eq!(item.unwrap().name, "item_name");
+        assert!(contract
+            .get_item_metadata("item1".to_string(), accounts(3))
+            .is_none());
+        assert_eq!(
+            contract
+                .get_item_by_store_id("item1".to_string())
+                .unwrap()
+                .name,
+            "item_name"
+        );
+    }
+
+    fn write_v1_state() {
+        let mut stores_by_account_id = LookupMap::new(StorageKey::StoresByAccountId);
+        let mut store_ids = UnorderedSet::new(StorageKey::StoresByAccountIdInner {
+            account_hash: env::sha256(accounts(1).as_bytes()),
+        });
+        store_ids.insert(&accounts(2));
+        stores_by_account_id.insert(&accounts(1), &store_ids);
+
+        let mut metadata_by_storeanditem_ids =
+            UnorderedMap::new(StorageKey::TransactionsByStoreAndItemIds);
+        metadata_by_storeanditem_ids.insert(
+            &store_and_item_id(&accounts(2), "item1"),
+            &ItemMetadataV1 {
+                name: "item_name".to_string(),
+                price: U128(1000),
+                img_url: "http://image.url".to_string(),
+                owner: accounts(2),
+            },
+        );
+
+        let mut audit_logs = UnorderedSet::new(StorageKey::AuditLogs);
+        audit_logs.insert(&LogV1 {
+            id: "bob.0".to_string(),
+            timestamp: 0,
+            action: "buy".to_string(),
+            actor: "alice".to_string(),
+            entity: "bob".to_string(),
+            extra: "{}".to_string(),
+        });
+
+        let mut item_by_store_id = TreeMap::new(StorageKey::ItemByStoreId);
+        item_by_store_id.insert(&"item1".to_string(), &accounts(2));
+
+        let v1 = ContractV1 {
+            overseer_id: accounts(0),
+            stores_by_account_id: Some(stores_by_account_id),
+            owners_per_store_id: Some(LookupMap::new(StorageKey::OwnersByStoreId)),
+            item_by_store_id,
+            metadata_by_storeanditem_ids: Some(metadata_by_storeanditem_ids),
+            audit_logs,
+            approved_ft_token_ids: UnorderedSet::new(StorageKey::FungibleTokenIds),
+        };
+        env::state_write(&v1);
+    }
+
+    #[test]
+    fn test_migrate_from_v1() {
+        let mut context = VMContextBuilder::new();
+        testing_env!(context.signer_account_id(accounts(0)).build());
+        write_v1_state();
+
+        let contract = Contract::migrate_from_v1();
+
+        assert_eq!(contract.state_version, STATE_VERSION);
+        assert_eq!(contract.overseer_id, accounts(0));
+        assert_eq!(contract.log_nonce, 0);
+        assert_eq!(
+            contract.get_stores_for_account(accounts(1), None, None),
+            vec![accounts(2)]
+        );
+        assert!(contract.group_buys.is_empty());
+        assert_eq!(contract.get_log("bob.0".to_string()).prev_hash, None);
+        let metadata = contract
+            .get_item_metadata("item1".to_string(), accounts(2))
+            .unwrap();
+        assert_eq!(metadata.price, U128(1000));
+        assert!(metadata.condition.is_none());
+        assert!(contract.verify_log_chain());
+
+        assert!(contract.all_stores.contains(&accounts(2)));
+        assert_eq!(contract.get_all_items(None, None).len(), 1);
+        assert_eq!(
+            contract.get_stores_by_item("item1".to_string()),
+            vec![accounts(2)]
+        );
+        assert_eq!(contract.get_newest_items_by_store(accounts(2), 10).len(), 1);
+    }
+
+    #[test]
+    #[should_panic(expected = "StoreHub: access denied")]
+    fn test_migrate_from_v1_denied() {
+        let mut context = VMContextBuilder::new();
+        testing_env!(context.signer_account_id(accounts(1)).build());
+        write_v1_state();
+
+        Contract::migrate_from_v1();
+    }
+
+    #[test]
+    #[should_panic(expected = "StoreHub: state already migrated")]
+    fn test_migrate_from_v1_twice() {
+        let mut context = VMContextBuilder::new();
+        testing_env!(context.signer_account_id(accounts(0)).build());
+
+        env::state_write(&Contract::new(accounts(0)));
+
+        Contract::migrate_from_v1();
+    }
+
+    #[test]
+    fn test_admin_add_log() {
+        let mut context = VMContextBuilder::new();
+        testing_env!(context.signer_account_id(accounts(0)).build());
+
+        let mut contract = Contract::new(accounts(0));
+        let log_id = contract.admin_add_log(
+            "annotation".to_string(),
+            "entity".to_string(),
+            "extra".to_string(),
+        );
+
+        let log = contract.get_log(log_id);
+        assert_eq!(log.action, "annotation");
+        assert_eq!(log.actor, accounts(0).to_string());
+    }
+
+    #[test]
+    #[should_panic(expected = "StoreHub: access denied")]
+    fn test_admin_add_log_denied() {
+        let mut context = VMContextBuilder::new();
+        testing_env!(context.signer_account_id(accounts(1)).build());
+
+        let mut contract = Contract::new(accounts(0));
+        contract.admin_add_log(
+            "annotation".to_string(),
+            "entity".to_string(),
+            "extra".to_string(),
+        );
+    }
+
+    #[test]
+    fn test_top_stores() {
+        let mut context = VMContextBuilder::new();
+        testing_env!(context.signer_account_id(accounts(1)).build());
+
+        let mut contract = Contract::new(accounts(0));
+        contract.create_store(accounts(2));
+        contract.create_store(accounts(3));
+        for (item_id, store_id) in [("item1", 2), ("item2", 3), ("item3", 3)] {
+            contract.add_store_item(
+                item_id.to_string(),
+                accounts(store_id),
+                item_id.to_string(),
+                U128(1000),
+                "http://image.url".to_string(),
+                None,
+                None,
+                None,
+            );
+        }
+
+        testing_env!(context
+            .signer_account_id(accounts(4))
+            .attached_deposit(3000)
+            .build());
+        contract.buy("item1".to_string(), accounts(2));
+        testing_env!(context.attached_deposit(1000).build());
+        contract.buy("item2".to_string(), accounts(3));
+
+        assert_eq!(contract.get_store_total_revenue(accounts(2)), U128(3000));
+        assert_eq!(
+            contract.get_top_stores_by_revenue(10),
+            vec![(accounts(2), U128(3000)), (accounts(3), U128(1000))]
+        );
+        assert_eq!(
+            contract.get_top_stores_by_item_count(1),
+            vec![(accounts(3), 2)]
+        );
+    }
+
+    #[test]
+    fn test_withdraw_store_balance() {
+        let mut context = VMContextBuilder::new();
+        let (mut contract, group_buy_id) = setup_group_buy(&mut context);
+
+        for buyer in [3, 4] {
+            testing_env!(context
+                .signer_account_id(accounts(buyer))
+                .attached_deposit(800)
+                .build());
+            contract.join_group_buy(group_buy_id.clone());
+        }
+        testing_env!(context.attached_deposit(0).build());
+        contract.execute_group_buy(group_buy_id);
+
+        testing_env!(context.build());
+        contract.withdraw_store_balance(accounts(2), U128(600));
+
+        assert_eq!(contract.get_store_balance(accounts(2)), U128(1000));
+        let receipts = get_created_receipts();
+        assert_eq!(receipts[0].receiver_id, accounts(1));
+        assert_eq!(
+            receipts[0].actions,
+            vec![VmAction::Transfer { deposit: 600 }]
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "StoreHub: insufficient store balance")]
+    fn test_withdraw_store_balance_insufficient() {
+        let mut context = VMContextBuilder::new();
+        testing_env!(context.predecessor_account_id(accounts(1)).build());
+
+        let mut contract = Contract::new(accounts(0));
+        contract.create_store(accounts(2));
+        contract.withdraw_store_balance(accounts(2), U128(1));
+    }
+
+    #[test]
+    fn test_treasury_accumulates_and_withdraws() {
+        let mut context = VMContextBuilder::new();
+        testing_env!(context.signer_account_id(accounts(0)).build());
+
+        let mut contract = Contract::new(accounts(0));
+        contract.set_protocol_fee(250);
+        contract.create_store(accounts(2));
+        for item_id in ["item1", "item2"] {
+            contract.add_store_item(
+                item_id.to_string(),
+                accounts(2),
+                item_id.to_string(),
+                U128(1000),
+                "http://image.url".to_string(),
+                None,
+                None,
+                None,
+            );
+        }
+
+        testing_env!(context
+            .signer_account_id(accounts(3))
+            .attached_deposit(1000)
+            .build());
+        contract.buy("item1".to_string(), accounts(2));
+        let receipts = get_created_receipts();
+        assert_eq!(receipts[0].receiver_id, accounts(2));
+        assert_eq!(
+            receipts[0].actions,
+            vec![VmAction::Transfer { deposit: 975 }]
+        );
+
+        testing_env!(context.attached_deposit(2000).build());
+        contract.buy("item2".to_string(), accounts(2));
+        assert_eq!(contract.get_treasury_balance(), U128(50));
+
+        testing_env!(context
+            .signer_account_id(accounts(0))
+            .attached_deposit(0)
+            .build());
+        contract.withdraw_treasury(U128(30));
+        assert_eq!(contract.get_treasury_balance(), U128(20));
+        assert_eq!(get_created_receipts()[0].receiver_id, accounts(0));
+    }
+
+    #[test]
+    #[should_panic(expected = "StoreHub: insufficient treasury balance")]
+    fn test_withdraw_treasury_above_balance() {
+        let mut context = VMContextBuilder::new();
+        testing_env!(context.signer_account_id(accounts(0)).build());
+
+        let mut contract = Contract::new(accounts(0));
+        contract.withdraw_treasury(U128(1));
+    }
+
+    #[test]
+    #[should_panic(expected = "StoreHub: item is priced in testnet, use buy_with_ft")]
+    fn test_buy_ft_priced_item_with_near() {
+        let mut context = VMContextBuilder::new();
+        testing_env!(context
+            .signer_account_id(accounts(0))
+            .predecessor_account_id(accounts(0))
+            .build());
+
+        let mut contract = Contract::new(accounts(0));
+        contract.create_store(accounts(2));
+        contract.set_store_currency(accounts(2), Some(test_account()));
+        assert_eq!(
+            contract.get_store_currency(accounts(2)),
+            Some(test_account())
+        );
+        contract.add_store_item(
+            "item1".to_string(),
+            accounts(2),
+            "item_name".to_string(),
+            U128(1000),
+            "http://image.url".to_string(),
+            None,
+            None,
+            None,
+        );
+
+        testing_env!(context
+            .signer_account_id(accounts(3))
+            .attached_deposit(1000)
+            .build());
+        contract.buy("item1".to_string(), accounts(2));
+    }
+
+    #[test]
+    #[should_panic(expected = "StoreHub: store already has items")]
+    fn test_set_store_currency_with_items() {
+        let mut context = VMContextBuilder::new();
+        testing_env!(context
+            .signer_account_id(accounts(0))
+            .predecessor_account_id(accounts(0))
+            .build());
+
+        let mut contract = Contract::new(accounts(0));
+        contract.create_store(accounts(2));
+        contract.add_store_item(
+            "item1".to_string(),
+            accounts(2),
+            "item_name".to_string(),
+            U128(1000),
+            "http://image.url".to_string(),
+            None,
+            None,
+            None,
+        );
+        contract.set_store_currency(accounts(2), Some(test_account()));
+    }
+
+    #[test]
+    fn test_execute_giveaway_of_sold_item() {
+        let mut context = VMContextBuilder::new();
+        let (mut contract, _) = setup_group_buy(&mut context);
+        let giveaway_id = contract.create_giveaway("item1".to_string(), accounts(2), 2, 100);
+
+        testing_env!(context
+            .signer_account_id(accounts(3))
+            .attached_deposit(1000)
+            .build());
+        contract.enter_giveaway(giveaway_id.clone());
+        contract.buy("item1".to_string(), accounts(2));
+
+        testing_env!(context
+            .signer_account_id(accounts(1))
+            .attached_deposit(0)
+            .block_timestamp(100)
+            .build());
+        assert_eq!(contract.execute_giveaway(giveaway_id.clone()), None);
+        assert_eq!(contract.get_giveaway_result(giveaway_id), None);
+        assert_eq!(
+            contract
+                .get_item_metadata("item1".to_string(), accounts(2))
+                .unwrap()
+                .owner,
+            accounts(3)
+        );
+    }
+
+    #[test]
+    fn test_execute_giveaway() {
+        let mut context = VMContextBuilder::new();
+        let (mut contract, _) = setup_group_buy(&mut context);
+        let giveaway_id = contract.create_giveaway("item1".to_string(), accounts(2), 2, 100);
+
+        for entrant in [3, 4] {
+            testing_env!(context.signer_account_id(accounts(entrant)).build());
+            contract.enter_giveaway(giveaway_id.clone());
+        }
+
+        testing_env!(context
+            .signer_account_id(accounts(1))
+            .block_timestamp(100)
+            .random_seed([1; 32])
+            .build());
+        assert_eq!(
+            contract.get_giveaway_entries(giveaway_id.clone()),
+            vec![accounts(3), accounts(4)]
+        );
+        let winner = contract.execute_giveaway(giveaway_id.clone()).unwrap();
+
+        assert!(winner == accounts(3) || winner == accounts(4));
+        assert_eq!(
+            contract.get_giveaway_result(giveaway_id),
+            Some(winner.clone())
+        );
+        assert_eq!(
+            contract
+                .get_item_metadata("item1".to_string(), accounts(2))
+                .unwrap()
+                .owner,
+            winner
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "StoreHub: already entered giveaway")]
+    fn test_enter_giveaway_twice() {
+        let mut context = VMContextBuilder::new();
+        let (mut contract, _) = setup_group_buy(&mut context);
+        let giveaway_id = contract.create_giveaway("item1".to_string(), accounts(2), 2, 100);
+
+        testing_env!(context.signer_account_id(accounts(3)).build());
+        contract.enter_giveaway(giveaway_id.clone());
+        contract.enter_giveaway(giveaway_id);
+    }
+
+    #[test]
+    fn test_execute_overseer_proposal() {
+        let mut context = VMContextBuilder::new();
+        testing_env!(context.signer_account_id(accounts(0)).build());
+
+        let mut contract = Contract::new(accounts(0));
+        let proposal_id = contract.create_proposal(
+            "hand over to the dao".to_string(),
+            "set_overseer".to_string(),
+            accounts(5).to_string(),
+            MIN_PROPOSAL_VOTING_PERIOD,
+        );
+
+        for (voter, approve) in [(1, true), (2, true), (3, false)] {
+            testing_env!(context.signer_account_id(accounts(voter)).build());
+            contract.vote_on_proposal(proposal_id.clone(), approve);
+        }
+
+        testing_env!(context
+            .signer_account_id(accounts(0))
+            .block_timestamp(MIN_PROPOSAL_VOTING_PERIOD)
+            .build());
+        contract.execute_proposal(proposal_id.clone());
+
+        assert_eq!(contract.overseer_id, accounts(5));
+        assert!(contract.proposals.get(&proposal_id).unwrap().executed);
+    }
+
+    #[test]
+    #[should_panic(expected = "StoreHub: already voted")]
+    fn test_vote_on_proposal_twice() {
+        let mut context = VMContextBuilder::new();
+        testing_env!(context.signer_account_id(accounts(0)).build());
+
+        let mut contract = Contract::new(accounts(0));
+        let proposal_id = contract.create_proposal(
+            "hand over to the dao".to_string(),
+            "set_overseer".to_string(),
+            accounts(5).to_string(),
+            MIN_PROPOSAL_VOTING_PERIOD,
+        );
+        contract.vote_on_proposal(proposal_id.clone(), true);
+        contract.vote_on_proposal(proposal_id, false);
+    }
+
+    #[test]
+    fn test_multisig_member_cannot_seize_overseer() {
+        let mut context = VMContextBuilder::new();
+        testing_env!(context.signer_account_id(accounts(0)).build());
+
+        let mut contract = Contract::new(accounts(0));
+        contract.setup_overseer_multisig(vec![accounts(1), accounts(2), accounts(3)], 2);
+
+        testing_env!(context.signer_account_id(accounts(1)).build());
+        let proposal_id = contract.create_proposal(
+            "take over".to_string(),
+            "set_overseer".to_string(),
+            accounts(1).to_string(),
+            MIN_PROPOSAL_VOTING_PERIOD,
+        );
+        for voter in [1, 4, 5] {
+            testing_env!(context.signer_account_id(accounts(voter)).build());
+            contract.vote_on_proposal(proposal_id.clone(), true);
+        }
+
+        testing_env!(context
+            .signer_account_id(accounts(1))
+            .block_timestamp(MIN_PROPOSAL_VOTING_PERIOD)
+            .build());
+        contract.execute_proposal(proposal_id);
+        assert_eq!(contract.overseer_id, accounts(0));
+        let pending = contract.get_pending_admin_actions();
+        assert_eq!(pending.len(), 1);
+        assert_eq!(pending[0].action_type, "set_overseer");
+
+        testing_env!(context.signer_account_id(accounts(2)).build());
+        contract.approve_admin_action(pending[0].id.clone());
+        assert_eq!(contract.overseer_id, accounts(1));
+    }
+
+    #[test]
+    #[should_panic(expected = "StoreHub: access denied")]
+    fn test_create_proposal_not_overseer() {
+        let mut context = VMContextBuilder::new();
+        testing_env!(context.signer_account_id(accounts(1)).build());
+
+        let mut contract = Contract::new(accounts(0));
+        contract.create_proposal(
+            "take over".to_string(),
+            "set_overseer".to_string(),
+            accounts(1).to_string(),
+            MIN_PROPOSAL_VOTING_PERIOD,
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "StoreHub: proposal quorum not reached")]
+    fn test_execute_proposal_without_quorum() {
+        let mut context = VMContextBuilder::new();
+        testing_env!(context.signer_account_id(accounts(0)).build());
+
+        let mut contract = Contract::new(accounts(0));
+        let proposal_id = contract.create_proposal(
+            "hand over to the dao".to_string(),
+            "set_overseer".to_string(),
+            accounts(5).to_string(),
+            MIN_PROPOSAL_VOTING_PERIOD,
+        );
+        contract.vote_on_proposal(proposal_id.clone(), true);
+
+        testing_env!(context.block_timestamp(MIN_PROPOSAL_VOTING_PERIOD).build());
+        contract.execute_proposal(proposal_id);
+    }
+
+    #[test]
+    fn test_rate_store() {
+        let mut context = VMContextBuilder::new();
+        testing_env!(context.signer_account_id(accounts(0)).build());
+
+        let mut contract = Contract::new(accounts(0));
+        contract.create_store(accounts(2));
+        contract.add_store_item(
+            "item1".to_string(),
+            accounts(2),
+            "item_name".to_string(),
+            U128(1000),
+            "http://image.url".to_string(),
+            None,
+            None,
+            None,
+        );
+
+        testing_env!(context
+            .signer_account_id(accounts(3))
+            .attached_deposit(1000)
+            .build());
+        contract.buy("item1".to_string(), accounts(2));
+        contract.rate_store(accounts(2), 2, "slow".to_string());
+        contract.rate_store(accounts(2), 4, "fine after all".to_string());
+
+        let ratings = contract.get_store_ratings(accounts(2), None, None);
+        assert_eq!(ratings.len(), 1);
+        assert_eq!(ratings[0].comment, "fine after all");
+        assert_eq!(contract.get_store_average_rating(accounts(2)), Some(4.0));
+        assert_eq!(contract.get_store_average_rating(accounts(4)), None);
+    }
+
+    #[test]
+    #[should_panic(expected = "StoreHub: no purchase from this store")]
+    fn test_rate_store_without_purchase() {
+        let mut context = VMContextBuilder::new();
+        testing_env!(context.signer_account_id(accounts(0)).build());
+
+        let mut contract = Contract::new(accounts(0));
+        contract.create_store(accounts(2));
+
+        testing_env!(context.signer_account_id(accounts(3)).build());
+        contract.rate_store(accounts(2), 5, "great".to_string());
+    }
+
+    #[test]
+    fn test_rate_item() {
+        let mut context = VMContextBuilder::new();
+        testing_env!(context.signer_account_id(accounts(0)).build());
+
+        let mut contract = Contract::new(accounts(0));
+        contract.create_store(accounts(2));
+        contract.add_store_item(
+            "item1".to_string(),
+            accounts(2),
+            "item_name".to_string(),
+            U128(1000),
+            "http://image.url".to_string(),
+            None,
+            None,
+            None,
+        );
+
+        for (buyer, score) in [(3, 4), (4, 5)] {
+            testing_env!(context
+                .signer_account_id(accounts(buyer))
+                .attached_deposit(1000)
+                .build());
+            contract.buy("item1".to_string(), accounts(2));
+            contract.rate_item("item1".to_string(), accounts(2), 1, "meh".to_string(), None);
+            contract.rate_item(
+                "item1".to_string(),
+                accounts(2),
+                score,
+                "good".to_string(),
+                None,
+            );
+        }
+
+        assert_eq!(
+            contract
+                .get_item_ratings("item1".to_string(), accounts(2), None, None)
+                .len(),
+            2
+        );
+        assert_eq!(
+            contract.get_item_average_rating("item1".to_string(), accounts(2)),
+            Some(5)
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "StoreHub: item not purchased")]
+    fn test_rate_item_without_purchase() {
+        let mut context = VMContextBuilder::new();
+        testing_env!(context.signer_account_id(accounts(3)).build());
+
+        let mut contract = Contract::new(accounts(0));
+        contract.rate_item(
+            "item1".to_string(),
+            accounts(2),
+            5,
+            "great".to_string(),
+            None,
+        );
+    }
+
+    #[test]
+    fn test_resale_pays_store_royalty() {
+        let mut context = VMContextBuilder::new();
+        testing_env!(context
+            .signer_account_id(accounts(0))
+            .predecessor_account_id(accounts(0))
+            .build());
+
+        let mut contract = Contract::new(accounts(0));
+        contract.create_store(accounts(2));
+        contract.set_store_royalty(accounts(2), 1_000);
+        contract.add_store_item(
+            "item1".to_string(),
+            accounts(2),
+            "item_name".to_string(),
+            U128(1000),
+            "http://image.url".to_string(),
+            None,
+            None,
+            None,
+        );
+
+        testing_env!(context
+            .signer_account_id(accounts(3))
+            .attached_deposit(1000)
+            .build());
+        contract.buy("item1".to_string(), accounts(2));
+        assert_eq!(
+            contract.get_total_royalties_earned_by_store(accounts(2)),
+            U128(0)
+        );
+
+        testing_env!(context.signer_account_id(accounts(4)).build());
+        contract.buy("item1".to_string(), accounts(2));
+
+        let receipts = get_created_receipts();
+        assert_eq!(receipts[0].receiver_id, accounts(2));
+        assert_eq!(
+            receipts[0].actions,
+            vec![VmAction::Transfer { deposit: 100 }]
+        );
+        assert_eq!(receipts[1].receiver_id, accounts(3));
+        assert_eq!(
+            receipts[1].actions,
+            vec![VmAction::Transfer { deposit: 900 }]
+        );
+        assert_eq!(
+            contract.get_total_royalties_earned_by_store(accounts(2)),
+            U128(100)
+        );
+        assert_eq!(
+            contract.get_item_royalties_earned("item1".to_string(), accounts(2)),
+            U128(100)
+        );
+        assert_eq!(
+            contract
+                .get_royalty_history_by_store(accounts(2), None, None)
+                .len(),
+            1
+        );
+    }
+
+    #[test]
+    fn test_stores_by_item_index() {
+        let mut context = VMContextBuilder::new();
+        testing_env!(context.predecessor_account_id(accounts(1)).build());
+
+        let mut contract = Contract::new(accounts(0));
+        for store in [2, 3] {
+            contract.create_store(accounts(store));
+            contract.add_store_item(
+                "item1".to_string(),
+                accounts(store),
+                "item_name".to_string(),
+                U128(1000),
+                "http://image.url".to_string(),
+                None,
+                None,
+                None,
+            );
+        }
+        assert_eq!(contract.get_item_store_count("item1".to_string()), 2);
+
+        contract.remove_store_item("item1".to_string(), accounts(2));
+        assert_eq!(
+            contract.get_stores_by_item("item1".to_string()),
+            vec![accounts(3)]
+        );
+        assert!(contract
+            .get_item_metadata("item1".to_string(), accounts(2))
+            .is_none());
+
+        assert_eq!(contract.get_total_item_count(), 1);
+        let all_items = contract.get_all_items(None, None);
+        assert_eq!(all_items[0].0, store_and_item_id(&accounts(3), "item1"));
+
+        contract.remove_store_item("item1".to_string(), accounts(3));
+        assert_eq!(contract.get_item_store_count("item1".to_string()), 0);
+        assert_eq!(contract.get_total_item_count(), 0);
+        assert!(contract.get_item_by_store_id("item1".to_string()).is_none());
+    }
+
+    #[test]
+    #[should_panic(expected = "StoreHub: purchase limit reached")]
+    fn test_buy_above_purchase_limit() {
+        let mut context = VMContextBuilder::new();
+        testing_env!(context.predecessor_account_id(accounts(1)).build());
+
+        let mut contract = Contract::new(accounts(0));
+        contract.create_store(accounts(2));
+        contract.add_store_item(
+            "item1".to_string(),
+            accounts(2),
+            "item_name".to_string(),
+            U128(1000),
+            "http://image.url".to_string(),
+            None,
+            None,
+            None,
+        );
+        contract.set_item_purchase_limit("item1".to_string(), accounts(2), 1);
+
+        // the buyer gifts the item, so they can try buying it again from the recipient
+        testing_env!(context
+            .signer_account_id(accounts(3))
+            .attached_deposit(1000)
+            .build());
+        contract.proxy_buy("item1".to_string(), accounts(2), accounts(4));
+        assert_eq!(
+            contract.get_buyer_item_purchase_count(accounts(3), "item1".to_string(), accounts(2)),
+            1
+        );
+        contract.buy("item1".to_string(), accounts(2));
+    }
+
+    #[test]
+    #[should_panic(expected = "StoreHub: region not allowed")]
+    fn test_buy_from_denied_region() {
+        let mut context = VMContextBuilder::new();
+        testing_env!(context.predecessor_account_id(accounts(1)).build());
+
+        let mut contract = Contract::new(accounts(0));
+        contract.create_store(accounts(2));
+        contract.add_store_item(
+            "item1".to_string(),
+            accounts(2),
+            "item_name".to_string(),
+            U128(1000),
+            "http://image.url".to_string(),
+            None,
+            None,
+            None,
+        );
+        contract.set_store_geo_restriction(
+            accounts(2),
+            Some(StoreGeoPolicy {
+                mode: GeoMode::Deny,
+                regions: vec!["FR".to_string()],
+            }),
+        );
+
+        testing_env!(context
+            .signer_account_id(accounts(3))
+            .attached_deposit(1000)
+            .build());
+        contract.buy_with_region("item1".to_string(), accounts(2), "FR".to_string());
+    }
+
+    #[test]
+    fn test_cross_store_prices_for_item() {
+        let mut context = VMContextBuilder::new();
+        testing_env!(context.predecessor_account_id(accounts(1)).build());
+
+        let mut contract = Contract::new(accounts(0));
+        for (store, price) in [(2, 1500), (3, 900), (4, 1200)] {
+            contract.create_store(accounts(store));
+            contract.add_store_item(
+                "item1".to_string(),
+                accounts(store),
+                "item_name".to_string(),
+                U128(price),
+                "http://image.url".to_string(),
+                None,
+                None,
+                None,
+            );
+        }
+
+        assert_eq!(
+            contract.get_cross_store_prices_for_item("item1".to_string()),
+            vec![
+                (accounts(3), U128(900)),
+                (accounts(4), U128(1200)),
+                (accounts(2), U128(1500)),
+            ]
+        );
+        assert_eq!(
+            contract.get_cheapest_store_for_item("item1".to_string()),
+            Some((accounts(3), U128(900)))
+        );
+        assert_eq!(
+            contract.get_cheapest_store_for_item("item2".to_string()),
+            None
+        );
+    }
+
+    #[test]
+    fn test_verify_log_chain() {
+        let mut context = VMContextBuilder::new();
+        testing_env!(context.signer_account_id(accounts(0)).build());
+
+        let mut contract = Contract::new(accounts(0));
+        assert!(contract.verify_log_chain());
+        for action in ["one", "two", "three"] {
+            contract.admin_add_log(
+                action.to_string(),
+                accounts(2).to_string(),
+                "{}".to_string(),
+            );
+        }
+        assert!(contract.verify_log_chain());
+
+        // a rolled back head no longer matches the recorded logs
+        contract.log_chain_head = None;
+        assert!(!contract.verify_log_chain());
+    }
+
+    fn setup_ft_gated_item(context: &mut VMContextBuilder) -> Contract {
+        testing_env!(context.predecessor_account_id(accounts(1)).build());
+
+        let mut contract = Contract::new(accounts(0));
+        contract.create_store(accounts(2));
+        contract.add_store_item(
+            "item1".to_string(),
+            accounts(2),
+            "item_name".to_string(),
+            U128(1000),
+            "http://image.url".to_string(),
+            None,
+            None,
+            None,
+        );
+        contract.set_item_ft_requirement(
+            "item1".to_string(),
+            accounts(2),
+            test_account(),
+            U128(50),
+        );
+
+        contract
+    }
+
+    #[test]
+    fn test_ft_gate_callback() {
+        let mut context = VMContextBuilder::new();
+        let mut contract = setup_ft_gated_item(&mut context);
+
+        testing_env!(context
+            .current_account_id(accounts(5))
+            .predecessor_account_id(accounts(5))
+            .signer_account_id(accounts(3))
+            .attached_deposit(1000)
+            .build());
+        let receipt =
+            contract.on_ft_gate_balance("item1".to_string(), accounts(2), None, Ok(U128(10)));
+        assert!(receipt.is_none());
+        let receipts = get_created_receipts();
+        assert_eq!(receipts[0].receiver_id, accounts(3));
+        assert_eq!(
+            receipts[0].actions,
+            vec![VmAction::Transfer { deposit: 1000 }]
+        );
+
+        let receipt =
+            contract.on_ft_gate_balance("item1".to_string(), accounts(2), None, Ok(U128(50)));
+        assert_eq!(receipt.unwrap().buyer, accounts(3));
+    }
+
+    #[test]
+    fn test_ft_gate_callback_refunds_failed_purchase() {
+        let mut context = VMContextBuilder::new();
+        let mut contract = setup_ft_gated_item(&mut context);
+        contract.set_store_geo_restriction(
+            accounts(2),
+            Some(StoreGeoPolicy {
+                mode: GeoMode::Allow,
+                regions: vec!["FR".to_string()],
+            }),
+        );
+
+        testing_env!(context
+            .current_account_id(accounts(5))
+            .predecessor_account_id(accounts(5))
+            .signer_account_id(accounts(3))
+            .attached_deposit(999)
+            .build());
+        let receipt = contract.on_ft_gate_balance(
+            "item1".to_string(),
+            accounts(2),
+            Some("FR".to_string()),
+            Ok(U128(50)),
+        );
+        assert!(receipt.is_none());
+        let receipts = get_created_receipts();
+        assert_eq!(receipts[0].receiver_id, accounts(3));
+        assert_eq!(
+            receipts[0].actions,
+            vec![VmAction::Transfer { deposit: 999 }]
+        );
+
+        testing_env!(context.attached_deposit(1000).build());
+        let receipt = contract.on_ft_gate_balance(
+            "item1".to_string(),
+            accounts(2),
+            Some("FR".to_string()),
+            Ok(U128(50)),
+        );
+        assert_eq!(receipt.unwrap().buyer, accounts(3));
+    }
+
+    #[test]
+    #[should_panic(expected = "StoreHub: deposit is below price (999 < 1000)")]
+    fn test_buy_with_ft_gate_checks_purchase_first() {
+        let mut context = VMContextBuilder::new();
+        let mut contract = setup_ft_gated_item(&mut context);
+
+        testing_env!(context
+            .signer_account_id(accounts(3))
+            .attached_deposit(999)
+            .build());
+        contract.buy_with_ft_gate("item1".to_string(), accounts(2), None);
+    }
+
+    #[test]
+    #[should_panic(expected = "StoreHub: item is token gated, use buy_with_ft_gate")]
+    fn test_buy_ft_gated_item_directly() {
+        let mut context = VMContextBuilder::new();
+        let mut contract = setup_ft_gated_item(&mut context);
+
+        testing_env!(context
+            .signer_account_id(accounts(3))
+            .attached_deposit(1000)
+            .build());
+        contract.buy("item1".to_string(), accounts(2));
+    }
+
+    #[test]
+    fn test_nft_gate_callback() {
+        let mut context = VMContextBuilder::new();
+        testing_env!(context.predecessor_account_id(accounts(1)).build());
+
+        let mut contract = Contract::new(accounts(0));
+        contract.create_store(accounts(2));
+        contract.add_store_item(
+            "item1".to_string(),
+            accounts(2),
+            "item_name".to_string(),
+            U128(1000),
+            "http://image.url".to_string(),
+            None,
+            None,
+            None,
+        );
+        contract.set_item_nft_requirement("item1".to_string(), accounts(2), accounts(4), 1);
+
+        testing_env!(context
+            .current_account_id(accounts(5))
+            .predecessor_account_id(accounts(5))
+            .signer_account_id(accounts(3))
+            .attached_deposit(1000)
+            .build());
+        let receipt =
+            contract.on_nft_check_complete("item1".to_string(), accounts(2), None, Ok(vec![]));
+        assert!(receipt.is_none());
+        let receipts = get_created_receipts();
+        assert_eq!(receipts[0].receiver_id, accounts(3));
+        assert_eq!(
+            receipts[0].actions,
+            vec![VmAction::Transfer { deposit: 1000 }]
+        );
+
+        let token = Token {
+            token_id: "1".to_string(),
+            owner_id: accounts(3),
+            metadata: None,
+            approved_account_ids: None,
+        };
+        testing_env!(context.attached_deposit(999).build());
+        let receipt = contract.on_nft_check_complete(
+            "item1".to_string(),
+            accounts(2),
+            None,
+            Ok(vec![token.clone()]),
+        );
+        assert!(receipt.is_none());
+        let receipts = get_created_receipts();
+        assert_eq!(receipts[0].receiver_id, accounts(3));
+        assert_eq!(
+            receipts[0].actions,
+            vec![VmAction::Transfer { deposit: 999 }]
+        );
+
+        testing_env!(context.attached_deposit(1000).build());
+        let receipt =
+            contract.on_nft_check_complete("item1".to_string(), accounts(2), None, Ok(vec![token]));
+        assert_eq!(receipt.unwrap().buyer, accounts(3));
+    }
+
+    #[test]
+    #[should_panic(expected = "StoreHub: deposit is below price (999 < 1000)")]
+    fn test_buy_insufficient_deposit_error() {
+        let mut context = VMContextBuilder::new();
+        testing_env!(context.predecessor_account_id(accounts(1)).build());
+
+        let mut contract = Contract::new(accounts(0));
+        contract.create_store(accounts(2));
+        contract.add_store_item(
+            "item1".to_string(),
+            accounts(2),
+            "item_name".to_string(),
+            U128(1000),
+            "http://image.url".to_string(),
+            None,
+            None,
+            None,
+        );
+
+        testing_env!(context
+            .signer_account_id(accounts(3))
+            .attached_deposit(999)
+            .build());
+        contract.buy("item1".to_string(), accounts(2));
+    }
+
+    #[test]
+    fn test_buy_with_shipping_fee() {
+        let mut context = VMContextBuilder::new();
+        testing_env!(context.predecessor_account_id(accounts(1)).build());
+
+        let mut contract = Contract::new(accounts(0));
+        contract.create_store(accounts(2));
+        contract.add_store_item(
+            "item1".to_string(),
+            accounts(2),
+            "item_name".to_string(),
+            U128(1000),
+            "http://image.url".to_string(),
+            None,
+            None,
+            None,
+        );
+        contract.set_item_shipping_info(
+            "item1".to_string(),
+            accounts(2),
+            ShippingInfo {
+                ships_from_region: "NG".to_string(),
+                estimated_days_min: 2,
+                estimated_days_max: 5,
+                flat_shipping_fee: U128(50),
+                free_shipping_threshold: Some(U128(5000)),
+            },
+        );
+
+        testing_env!(context
+            .signer_account_id(accounts(3))
+            .attached_deposit(1050)
+            .build());
+        contract.buy("item1".to_string(), accounts(2));
+
+        let receipts = get_created_receipts();
+        assert_eq!(receipts[0].receiver_id, accounts(2));
+        assert_eq!(
+            receipts[0].actions,
+            vec![VmAction::Transfer { deposit: 1050 }]
+        );
+        let log = contract.audit_logs.iter().last().unwrap();
+        let extra: serde_json::Value = serde_json::from_str(&log.extra).unwrap();
+        assert_eq!(extra["shipping_fee"], "50");
+    }
+
+    #[test]
+    fn test_batch_get_items() {
+        let mut context = VMContextBuilder::new();
+        testing_env!(context.predecessor_account_id(accounts(1)).build());
+
+        let mut contract = Contract::new(accounts(0));
+        contract.create_store(accounts(2));
+        for item_id in ["item1", "item2", "item3", "item4"] {
+            contract.add_store_item(
+                item_id.to_string(),
+                accounts(2),
+                item_id.to_string(),
+                U128(1000),
+                "http://image.url".to_string(),
+                None,
+                None,
+                None,
+            );
+        }
+
+        let items = contract.batch_get_items(vec![
+            ("item1".to_string(), accounts(2)),
+            ("item2".to_string(), accounts(2)),
+            ("missing".to_string(), accounts(2)),
+            ("item3".to_string(), accounts(2)),
+            ("item4".to_string(), accounts(2)),
+        ]);
+
+        assert_eq!(items.len(), 5);
+        assert!(items[2].is_none());
+        assert_eq!(items[3].as_ref().unwrap().name, "item3");
+        assert_eq!(items[4].as_ref().unwrap().name, "item4");
+    }
+
+    #[test]
+    fn test_get_items_by_condition() {
+        let mut context = VMContextBuilder::new();
+        testing_env!(context.predecessor_account_id(accounts(1)).build());
+
+        let mut contract = Contract::new(accounts(0));
+        contract.create_store(accounts(2));
+        let conditions = [
+            ItemCondition::New,
+            ItemCondition::LikeNew,
+            ItemCondition::VeryGood,
+            ItemCondition::Good,
+            ItemCondition::Acceptable,
+        ];
+        for (index, condition) in conditions.iter().enumerate() {
+            contract.add_store_item(
+                format!("item{}", index),
+                accounts(2),
+                format!("item{}", index),
+                U128(1000),
+                "http://image.url".to_string(),
+                Some(*condition),
+                None,
+                None,
+            );
+        }
+        contract.add_store_item(
+            "ungraded".to_string(),
+            accounts(2),
+            "item_name".to_string(),
+            U128(1000),
+            "http://image.url".to_string(),
+            None,
+            None,
+            None,
+        );
+
+        for (index, condition) in conditions.iter().enumerate() {
+            let items = contract.get_items_by_condition(accounts(2), *condition, None, None);
+            assert_eq!(items.len(), 1);
+            assert_eq!(items[0].0, format!("item{}", index));
+            assert_eq!(items[0].1.condition, Some(*condition));
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "StoreHub: item locked in auction")]
+    fn test_buy_locked_item() {
+        let mut context = VMContextBuilder::new();
+        testing_env!(context.predecessor_account_id(accounts(1)).build());
+
+        let mut contract = Contract::new(accounts(0));
+        contract.create_store(accounts(2));
+        contract.add_store_item(
+            "item1".to_string(),
+            accounts(2),
+            "item_name".to_string(),
+            U128(1000),
+            "http://image.url".to_string(),
+            None,
+            None,
+            None,
+        );
+        contract.lock_item_for_auction("item1".to_string(), accounts(2));
+        assert!(contract.is_item_locked("item1".to_string(), accounts(2)));
+
+        testing_env!(context
+            .signer_account_id(accounts(3))
+            .attached_deposit(1000)
+            .build());
+        contract.buy("item1".to_string(), accounts(2));
+    }
+
+    #[test]
+    fn test_subscription_lifecycle() {
+        let mut context = VMContextBuilder::new();
+        let (mut contract, _) = setup_group_buy(&mut context);
+        let plan_id = contract.create_subscription_plan(
+            "item1".to_string(),
+            accounts(2),
+            U128(100),
+            10,
+            Some(2),
+        );
+
+        testing_env!(context
+            .signer_account_id(accounts(3))
+            .attached_deposit(100)
+            .build());
+        contract.subscribe(plan_id.clone());
+        assert!(contract.is_subscription_active(plan_id.clone(), accounts(3)));
+
+        testing_env!(context.block_timestamp(10_000_000_000).build());
+        assert!(!contract.is_subscription_active(plan_id.clone(), accounts(3)));
+        contract.renew_subscription(plan_id.clone());
+        assert!(contract.is_subscription_active(plan_id.clone(), accounts(3)));
+        assert_eq!(contract.get_store_balance(accounts(2)), U128(200));
+
+        let subscriptions = contract.get_subscriptions_by_store(accounts(2), None, None);
+        assert_eq!(subscriptions[0].periods_paid, 2);
+
+        contract.cancel_subscription(plan_id.clone());
+        assert!(!contract.is_subscription_active(plan_id, accounts(3)));
+    }
+
+    #[test]
+    #[should_panic(expected = "StoreHub: subscription reached its last period")]
+    fn test_renew_subscription_past_max_periods() {
+        let mut context = VMContextBuilder::new();
+        let (mut contract, _) = setup_group_buy(&mut context);
+        let plan_id = contract.create_subscription_plan(
+            "item1".to_string(),
+            accounts(2),
+            U128(100),
+            10,
+            Some(1),
+        );
+
+        testing_env!(context
+            .signer_account_id(accounts(3))
+            .attached_deposit(100)
+            .build());
+        contract.subscribe(plan_id.clone());
+        contract.renew_subscription(plan_id);
+    }
+
+    #[test]
+    #[should_panic(expected = "StoreHub: subscription period too long")]
+    fn test_create_subscription_plan_period_too_long() {
+        let mut context = VMContextBuilder::new();
+        let (mut contract, _) = setup_group_buy(&mut context);
+        contract.create_subscription_plan(
+            "item1".to_string(),
+            accounts(2),
+            U128(100),
+            MAX_SUBSCRIPTION_PERIOD + 1,
+            None,
+        );
+    }
+
+    #[test]
+    fn test_platform_min_items() {
+        let mut context = VMContextBuilder::new();
+        testing_env!(context.predecessor_account_id(accounts(1)).build());
+
+        let mut contract = Contract::new(accounts(0));
+        contract.create_store(accounts(2));
+        contract.create_store(accounts(3));
+        for item_id in ["item1", "item2"] {
+            contract.add_store_item(
+                item_id.to_string(),
+                accounts(2),
+                item_id.to_string(),
+                U128(1000),
+                "http://image.url".to_string(),
+                None,
+                None,
+                None,
+            );
+        }
+
+        testing_env!(context.signer_account_id(accounts(0)).build());
+        contract.set_platform_min_items(Some(2));
+        assert_eq!(contract.get_inactive_stores(None, None), vec![accounts(3)]);
+
+        // the overseer may go below the minimum
+        contract.remove_store_item("item1".to_string(), accounts(2));
+        assert_eq!(
+            contract.get_inactive_stores(None, None),
+            vec![accounts(2), accounts(3)]
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "StoreHub: cannot have fewer than 2 items")]
+    fn test_remove_store_item_below_min_items() {
+        let mut context = VMContextBuilder::new();
+        testing_env!(context.signer_account_id(accounts(0)).build());
+
+        let mut contract = Contract::new(accounts(0));
+        contract.set_platform_min_items(Some(2));
+
+        testing_env!(context
+            .signer_account_id(accounts(1))
+            .predecessor_account_id(accounts(1))
+            .build());
+        contract.create_store(accounts(2));
+        for item_id in ["item1", "item2"] {
+            contract.add_store_item(
+                item_id.to_string(),
+                accounts(2),
+                item_id.to_string(),
+                U128(1000),
+                "http://image.url".to_string(),
+                None,
+                None,
+                None,
+            );
+        }
+        contract.remove_store_item("item1".to_string(), accounts(2));
+    }
+
+    #[test]
+    fn test_generate_invoice() {
+        let mut context = VMContextBuilder::new();
+        testing_env!(context.signer_account_id(accounts(0)).build());
+
+        let mut contract = Contract::new(accounts(0));
+        contract.set_tax_rate(accounts(2), 2_500);
+
+        testing_env!(context.predecessor_account_id(accounts(1)).build());
+        contract.create_store(accounts(2));
+        contract.add_store_item(
+            "item1".to_string(),
+            accounts(2),
+            "item_name".to_string(),
+            U128(1000),
+            "http://image.url".to_string(),
+            None,
+            None,
+            None,
+        );
+
+        testing_env!(context
+            .signer_account_id(accounts(3))
+            .attached_deposit(1000)
+            .build());
+        let receipt = contract.buy("item1".to_string(), accounts(2));
+
+        // later changes don't rewrite the invoice of a past sale
+        testing_env!(context
+            .signer_account_id(accounts(0))
+            .attached_deposit(0)
+            .build());
+        contract.set_tax_rate(accounts(2), 1_000);
+        contract.update_item_metadata(
+            "item1".to_string(),
+            accounts(2),
+            Some("renamed".to_string()),
+            None,
+            None,
+            None,
+            None,
+        );
+
+        let invoice = contract.generate_invoice(receipt.transaction_id.clone());
+        assert_eq!(invoice.invoice_id, receipt.transaction_id);
+        assert_eq!(invoice.buyer, accounts(3));
+        assert_eq!(invoice.seller, accounts(2));
+        assert_eq!(invoice.item_name, "item_name");
+        assert_eq!(invoice.subtotal, U128(800));
+        assert_eq!(invoice.tax_amount, U128(200));
+        assert_eq!(invoice.total, U128(1000));
+    }
+
+    #[test]
+    fn test_buy_during_store_sale() {
+        let mut context = VMContextBuilder::new();
+        testing_env!(context.predecessor_account_id(accounts(1)).build());
+
+        let mut contract = Contract::new(accounts(0));
+        contract.create_store(accounts(2));
+        contract.add_store_item(
+            "item1".to_string(),
+            accounts(2),
+            "item_name".to_string(),
+            U128(1000),
+            "http://image.url".to_string(),
+            None,
+            None,
+            None,
+        );
+        contract.set_store_sale_period(accounts(2), 2_000, 10, 20);
+        assert!(!contract.is_store_on_sale(accounts(2)));
+
+        testing_env!(context
+            .signer_account_id(accounts(3))
+            .attached_deposit(800)
+            .block_timestamp(10)
+            .build());
+        assert!(contract.is_store_on_sale(accounts(2)));
+        contract.buy("item1".to_string(), accounts(2));
+
+        let log = contract.audit_logs.iter().last().unwrap();
+        let extra: serde_json::Value = serde_json::from_str(&log.extra).unwrap();
+        assert_eq!(extra["sale_discount"], "200");
+    }
+
+    #[test]
+    fn test_store_manager_adds_items() {
+        let mut context = VMContextBuilder::new();
+        testing_env!(context.predecessor_account_id(accounts(1)).build());
+
+        let mut contract = Contract::new(accounts(0));
+        contract.create_store(accounts(2));
+        contract.set_store_role(accounts(2), accounts(3), StoreRole::Manager);
+        assert_eq!(
+            contract.get_store_role(accounts(2), accounts(1)),
+            Some(StoreRole::Owner)
+        );
+        assert_eq!(
+            contract.get_store_role(accounts(2), accounts(3)),
+            Some(StoreRole::Manager)
+        );
+
+        testing_env!(context.predecessor_account_id(accounts(3)).build());
+        contract.add_store_item(
+            "item1".to_string(),
+            accounts(2),
+            "item_name".to_string(),
+            U128(1000),
+            "http://image.url".to_string(),
+            None,
+            None,
+            None,
+        );
+        contract.remove_store_item("item1".to_string(), accounts(2));
+    }
+
+    #[test]
+    #[should_panic(expected = "StoreHub: access denied")]
+    fn test_store_viewer_cannot_add_items() {
+        let mut context = VMContextBuilder::new();
+        testing_env!(context.predecessor_account_id(accounts(1)).build());
+
+        let mut contract = Contract::new(accounts(0));
+        contract.create_store(accounts(2));
+        contract.set_store_role(accounts(2), accounts(3), StoreRole::Viewer);
+
+        testing_env!(context.predecessor_account_id(accounts(3)).build());
+        contract.add_store_item(
+            "item1".to_string(),
+            accounts(2),
+            "item_name".to_string(),
+            U128(1000),
+            "http://image.url".to_string(),
+            None,
+            None,
+            None,
+        );
+    }
+
+    #[test]
+    fn test_item_metadata_history() {
+        let mut context = VMContextBuilder::new();
+        testing_env!(context.predecessor_account_id(accounts(1)).build());
+
+        let mut contract = Contract::new(accounts(0));
+        contract.create_store(accounts(2));
+        contract.add_store_item(
+            "item1".to_string(),
+            accounts(2),
+            "name0".to_string(),
+            U128(1000),
+            "http://image.url".to_string(),
+            None,
+            None,
+            None,
+        );
+
+        for version in 1..=25 {
+            testing_env!(context.block_timestamp(version).build());
+            contract.update_item_metadata(
+                "item1".to_string(),
+                accounts(2),
+                Some(format!("name{}", version)),
+                None,
+                None,
+                None,
+                None,
+            );
+        }
+
+        assert_eq!(
+            contract.get_item_metadata_version_count("item1".to_string(), accounts(2)),
+            20
+        );
+        let history =
+            contract.get_item_metadata_history("item1".to_string(), accounts(2), None, Some(2));
+        assert_eq!(history[0].0.name, "name5");
+        assert_eq!(history[0].1, 6);
+        assert_eq!(history[1].0.name, "name6");
+        assert_eq!(
+            contract
+                .get_item_metadata("item1".to_string(), accounts(2))
+                .unwrap()
+                .name,
+            "name25"
+        );
+    }
+
+    #[test]
+    fn test_cross_contract_buy() {
+        let mut context = VMContextBuilder::new();
+        testing_env!(context
+            .signer_account_id(accounts(0))
+            .predecessor_account_id(accounts(1))
+            .build());
+
+        let mut contract = Contract::new(accounts(0));
+        contract.approve_caller_contract(accounts(5));
+        contract.create_store(accounts(2));
+        contract.add_store_item(
+            "item1".to_string(),
+            accounts(2),
+            "item_name".to_string(),
+            U128(1000),
+            "http://image.url".to_string(),
+            None,
+            None,
+            None,
+        );
+
+        testing_env!(context
+            .signer_account_id(accounts(3))
+            .predecessor_account_id(accounts(5))
+            .attached_deposit(1000)
+            .build());
+        contract.cross_contract_buy("item1".to_string(), accounts(2), accounts(4), None);
+
+        assert_eq!(
+            contract
+                .get_item_metadata("item1".to_string(), accounts(2))
+                .unwrap()
+                .owner,
+            accounts(4)
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "StoreHub: caller contract not approved")]
+    fn test_cross_contract_buy_from_unapproved_contract() {
+        let mut context = VMContextBuilder::new();
+        testing_env!(context
+            .predecessor_account_id(accounts(5))
+            .attached_deposit(1000)
+            .build());
+
+        let mut contract = Contract::new(accounts(0));
+        contract.cross_contract_buy("item1".to_string(), accounts(2), accounts(4), None);
+    }
+
+    #[test]
+    fn test_create_store_with_metadata() {
+        let mut context = VMContextBuilder::new();
+        testing_env!(context.predecessor_account_id(accounts(1)).build());
+
+        let mut contract = Contract::new(accounts(0));
+        let store_id = contract.create_store_with_metadata(
+            accounts(2),
+            "Store".to_string(),
+            "A store".to_string(),
+            "http://logo.url".to_string(),
+            "http://store.url".to_string(),
+        );
+
+        assert_eq!(store_id, accounts(2));
+        assert_eq!(
+            contract.get_owners_by_store_id(accounts(2)),
+            vec![accounts(1)]
+        );
+        assert_eq!(
+            contract.get_store_metadata(accounts(2)).unwrap().name,
+            "Store"
+        );
+    }
+
+    #[test]
+    fn test_get_stores_with_metadata() {
+        let mut context = VMContextBuilder::new();
+        testing_env!(context.predecessor_account_id(accounts(1)).build());
+
+        let mut contract = Contract::new(accounts(0));
+        contract.create_store_with_metadata(
+            accounts(2),
+            "Store".to_string(),
+            "A store".to_string(),
+            "http://logo.url".to_string(),
+            "http://store.url".to_string(),
+        );
+        contract.create_store(accounts(3));
+
+        let stores = contract.get_stores_with_metadata(None, None);
+        assert_eq!(stores.len(), 2);
+        assert_eq!(stores[0].0, accounts(2));
+        assert_eq!(stores[0].1.as_ref().unwrap().name, "Store");
+        assert_eq!(stores[1], (accounts(3), None));
+
+        let page = contract.get_stores_with_metadata(Some(1), Some(1));
+        assert_eq!(page, vec![(accounts(3), None)]);
+    }
+
+    #[test]
+    fn test_item_approval() {
+        let mut context = VMContextBuilder::new();
+        testing_env!(context
+            .signer_account_id(accounts(0))
+            .predecessor_account_id(accounts(0))
+            .build());
+
+        let mut contract = Contract::new(accounts(0));
+        contract.set_require_item_approval(true);
+
+        testing_env!(context
+            .signer_account_id(accounts(1))
+            .predecessor_account_id(accounts(1))
+            .build());
+        contract.create_store(accounts(2));
+        contract.add_store_item(
+            "item1".to_string(),
+            accounts(2),
+            "Item 1".to_string(),
+            U128(1000),
+            "http://item1.url".to_string(),
+            None,
+            None,
+            None,
+        );
+        contract.add_store_item(
+            "item2".to_string(),
+            accounts(2),
+            "Item 2".to_string(),
+            U128(1000),
+            "http://item2.url".to_string(),
+            None,
+            None,
+            None,
+        );
+        assert!(!contract.is_item_listed("item1".to_string(), accounts(2)));
+        assert_eq!(contract.get_pending_approval_items(None, None).len(), 2);
+
+        testing_env!(context
+            .signer_account_id(accounts(0))
+            .predecessor_account_id(accounts(0))
+            .build());
+        contract.approve_item("item1".to_string(), accounts(2));
+        contract.reject_item("item2".to_string(), accounts(2));
+
+        assert!(contract.is_item_listed("item1".to_string(), accounts(2)));
+        assert!(contract.get_pending_approval_items(None, None).is_empty());
+        assert_eq!(contract.get_total_item_count(), 1);
+    }
+
+    #[test]
+    #[should_panic(expected = "StoreHub: item pending approval")]
+    fn test_buy_item_pending_approval() {
+        let mut context = VMContextBuilder::new();
+        testing_env!(context
+            .signer_account_id(accounts(0))
+            .predecessor_account_id(accounts(0))
+            .build());
+
+        let mut contract = Contract::new(accounts(0));
+        contract.set_require_item_approval(true);
+
+        testing_env!(context
+            .signer_account_id(accounts(1))
+            .predecessor_account_id(accounts(1))
+            .build());
+        contract.create_store(accounts(2));
+        contract.add_store_item(
+            "item1".to_string(),
+            accounts(2),
+            "Item 1".to_string(),
+            U128(1000),
+            "http://item1.url".to_string(),
+            None,
+            None,
+            None,
+        );
+
+        testing_env!(context
+            .signer_account_id(accounts(3))
+            .predecessor_account_id(accounts(3))
+            .attached_deposit(1000)
+            .build());
+        contract.buy("item1".to_string(), accounts(2));
+    }
+
+    #[test]
+    fn test_store_closed_for_listing() {
+        let mut context = VMContextBuilder::new();
+        let (mut contract, _) = setup_group_buy(&mut context);
+        contract.close_store_for_listing(accounts(2));
+        assert!(!contract.is_store_open_for_listing(accounts(2)));
+
+        // existing items remain purchasable
+        testing_env!(context
+            .signer_account_id(accounts(3))
+            .predecessor_account_id(accounts(3))
+            .attached_deposit(1000)
+            .build());
+        contract.buy("item1".to_string(), accounts(2));
+
+        testing_env!(context
+            .signer_account_id(accounts(1))
+            .predecessor_account_id(accounts(1))
+            .attached_deposit(0)
+            .build());
+        contract.open_store_for_listing(accounts(2));
+        contract.add_store_item(
+            "item2".to_string(),
+            accounts(2),
+            "Item 2".to_string(),
+            U128(1000),
+            "http://item2.url".to_string(),
+            None,
+            None,
+            None,
+        );
+        assert!(contract.is_store_open_for_listing(accounts(2)));
+    }
+
+    #[test]
+    #[should_panic(expected = "StoreHub: store not accepting new items")]
+    fn test_add_item_to_closed_store() {
+        let mut context = VMContextBuilder::new();
+        let (mut contract, _) = setup_group_buy(&mut context);
+        contract.close_store_for_listing(accounts(2));
+        contract.add_store_item(
+            "item2".to_string(),
+            accounts(2),
+            "Item 2".to_string(),
+            U128(1000),
+            "http://item2.url".to_string(),
+            None,
+            None,
+            None,
+        );
+    }
+
+    #[test]
+    fn test_get_item_effective_price() {
+        let mut context = VMContextBuilder::new();
+        testing_env!(context.predecessor_account_id(accounts(1)).build());
+
+        let mut contract = Contract::new(accounts(0));
+        contract.create_store(accounts(2));
+        contract.add_store_item(
+            "item1".to_string(),
+            accounts(2),
+            "item_name".to_string(),
+            U128(1000),
+            "http://image.url".to_string(),
+            None,
+            None,
+            None,
+        );
+        contract.set_item_shipping_info(
+            "item1".to_string(),
+            accounts(2),
+            ShippingInfo {
+                ships_from_region: "NG".to_string(),
+                estimated_days_min: 2,
+                estimated_days_max: 5,
+                flat_shipping_fee: U128(50),
+                free_shipping_threshold: None,
+            },
+        );
+        contract.set_store_sale_period(accounts(2), 2_000, 10, 20);
+        assert_eq!(
+            contract.get_item_effective_price("item1".to_string(), accounts(2), 1, None),
+            U128(1050)
+        );
+
+        testing_env!(context
+            .signer_account_id(accounts(3))
+            .attached_deposit(850)
+            .block_timestamp(10)
+            .build());
+        let price = contract.get_item_effective_price("item1".to_string(), accounts(2), 1, None);
+        assert_eq!(price, U128(850));
+        contract.buy("item1".to_string(), accounts(2));
+    }
+
+    #[test]
+    #[should_panic(expected = "StoreHub: items are single units")]
+    fn test_get_item_effective_price_for_quantity() {
+        let mut context = VMContextBuilder::new();
+        let (contract, _) = setup_group_buy(&mut context);
+        contract.get_item_effective_price("item1".to_string(), accounts(2), 2, None);
+    }
+
+    #[test]
+    fn test_import_store_from_json() {
+        let mut context = VMContextBuilder::new();
+        let (mut contract, _) = setup_group_buy(&mut context);
+
+        let payload = json!([
+            { "id": "item2", "name": "Item 2", "price": "1000", "img_url": "http://item2.url" },
+            { "id": "item1", "name": "Item 1", "price": "1000", "img_url": "http://item1.url" },
+            { "id": "item3", "name": "", "price": "1000", "img_url": "http://item3.url" },
+            { "id": "item4", "name": "Item 4", "price": 1000, "img_url": "http://item4.url" },
+        ]);
+        let result = contract.import_store_from_json(accounts(2), payload.to_string());
+
+        assert_eq!(result.imported, 1);
+        assert_eq!(result.failed, 3);
+        assert_eq!(result.errors[0], "item 1: item item1 already exists");
+        assert_eq!(result.errors[1], "item 2: empty name");
+        assert_eq!(
+            contract
+                .get_item_metadata("item2".to_string(), accounts(2))
+                .unwrap()
+                .price,
+            U128(1000)
+        );
+    }
+
+    #[test]
+    fn test_export_store_items() {
+        let mut context = VMContextBuilder::new();
+        let (mut contract, _) = setup_group_buy(&mut context);
+        contract.require_item_approval = true;
+        contract.add_store_item(
+            "item2".to_string(),
+            accounts(2),
+            "Item 2".to_string(),
+            U128(2000),
+            "http://item2.url".to_string(),
+            None,
+            None,
+            None,
+        );
+
+        let export: serde_json::Value =
+            serde_json::from_str(&contract.export_store_items(accounts(2), None, None)).unwrap();
+        assert_eq!(export["total_count"], 2);
+
+        // the export can be imported as-is
+        let items: Vec<CatalogItem> = serde_json::from_value(export["items"].clone()).unwrap();
+        assert!(items.contains(&CatalogItem {
+            id: "item2".to_string(),
+            name: "Item 2".to_string(),
+            price: U128(2000),
+            img_url: "http://item2.url".to_string(),
+        }));
+    }
+
+    #[test]
+    fn test_get_buyer_store_spending() {
+        let mut context = VMContextBuilder::new();
+        testing_env!(context.predecessor_account_id(accounts(1)).build());
+
+        let mut contract = Contract::new(accounts(0));
+        for (store, item, price) in [(2, "item1", 1000), (4, "item2", 3000), (4, "item3", 500)] {
+            contract.create_store(accounts(store));
+            contract.add_store_item(
+                item.to_string(),
+                accounts(store),
+                item.to_string(),
+                U128(price),
+                "http://image.url".to_string(),
+                None,
+                None,
+                None,
+            );
+        }
+
+        for (store, item, price) in [(2, "item1", 1000), (4, "item2", 3000), (4, "item3", 500)] {
+            testing_env!(context
+                .signer_account_id(accounts(3))
+                .attached_deposit(price)
+                .build());
+            contract.buy(item.to_string(), accounts(store));
+        }
+
+        assert_eq!(
+            contract.get_buyer_store_spending(accounts(3)),
+            vec![(accounts(4), U128(3500)), (accounts(2), U128(1000))]
+        );
+        assert!(contract.get_buyer_store_spending(accounts(5)).is_empty());
+    }
+
+    #[test]
+    fn test_buy_with_referral() {
+        let mut context = VMContextBuilder::new();
+        let (mut contract, _) = setup_group_buy(&mut context);
+        contract.create_referral_program(accounts(2), 1_000, 500);
+
+        testing_env!(context
+            .signer_account_id(accounts(3))
+            .predecessor_account_id(accounts(3))
+            .attached_deposit(950)
+            .build());
+        contract.buy_with_referral("item1".to_string(), accounts(2), accounts(4));
+
+        assert_eq!(contract.get_referral_earnings(accounts(4)), U128(95));
+        let receipts = get_created_receipts();
+        assert_eq!(receipts[0].receiver_id, accounts(4));
+        assert_eq!(
+            receipts[0].actions,
+            vec![VmAction::Transfer { deposit: 95 }]
+        );
+        assert_eq!(receipts[1].receiver_id, accounts(2));
+        assert_eq!(
+            receipts[1].actions,
+            vec![VmAction::Transfer { deposit: 855 }]
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "StoreHub: referral program is inactive")]
+    fn test_buy_with_ended_referral_program() {
+        let mut context = VMContextBuilder::new();
+        let (mut contract, _) = setup_group_buy(&mut context);
+        contract.create_referral_program(accounts(2), 1_000, 500);
+        contract.end_referral_program(accounts(2));
+
+        testing_env!(context
+            .signer_account_id(accounts(3))
+            .predecessor_account_id(accounts(3))
+            .attached_deposit(1000)
+            .build());
+        contract.buy_with_referral("item1".to_string(), accounts(2), accounts(4));
+    }
+
+    #[test]
+    fn test_item_purchase_cooldown() {
+        let mut context = VMContextBuilder::new();
+        let (mut contract, _) = setup_group_buy(&mut context);
+        contract.set_item_purchase_cooldown("item1".to_string(), accounts(2), 100);
+
+        for (buyer, timestamp) in [(3, 10), (4, 20)] {
+            testing_env!(context
+                .signer_account_id(accounts(buyer))
+                .predecessor_account_id(accounts(buyer))
+                .attached_deposit(1000)
+                .block_timestamp(timestamp)
+                .build());
+            contract.buy("item1".to_string(), accounts(2));
+        }
+        assert_eq!(
+            contract.get_item_cooldown_remaining(accounts(3), "item1".to_string(), accounts(2)),
+            90
+        );
+
+        testing_env!(context
+            .signer_account_id(accounts(3))
+            .predecessor_account_id(accounts(3))
+            .block_timestamp(110)
+            .build());
+        assert_eq!(
+            contract.get_item_cooldown_remaining(accounts(3), "item1".to_string(), accounts(2)),
+            0
+        );
+        contract.buy("item1".to_string(), accounts(2));
+    }
+
+    #[test]
+    #[should_panic(expected = "StoreHub: purchase cooldown active")]
+    fn test_buy_during_purchase_cooldown() {
+        let mut context = VMContextBuilder::new();
+        let (mut contract, _) = setup_group_buy(&mut context);
+        contract.set_item_purchase_cooldown("item1".to_string(), accounts(2), 100);
+
+        for buyer in [3, 4, 3] {
+            testing_env!(context
+                .signer_account_id(accounts(buyer))
+                .predecessor_account_id(accounts(buyer))
+                .attached_deposit(1000)
+                .build());
+            contract.buy("item1".to_string(), accounts(2));
+        }
+    }
+
+    #[test]
+    fn test_get_store_activity_feed() {
+        let mut context = VMContextBuilder::new();
+        testing_env!(context
+            .predecessor_account_id(accounts(1))
+            .block_timestamp(10)
+            .build());
+
+        let mut contract = Contract::new(accounts(0));
+        contract.create_store(accounts(2));
+        contract.add_store_item(
+            "item1".to_string(),
+            accounts(2),
+            "item_name".to_string(),
+            U128(1000),
+            "http://image.url".to_string(),
+            None,
+            None,
+            None,
+        );
+
+        testing_env!(context
+            .signer_account_id(accounts(3))
+            .attached_deposit(1000)
+            .block_timestamp(20)
+            .build());
+        contract.buy("item1".to_string(), accounts(2));
+
+        let feed = contract.get_store_activity_feed(accounts(2), None, None);
+        assert_eq!(feed.len(), 2);
+        match &feed[0] {
+            ActivityEvent::Sale {
+                item_id,
+                buyer,
+                price,
+                timestamp,
+            } => {
+                assert_eq!(item_id, "item1");
+                assert_eq!(*buyer, accounts(3));
+                assert_eq!(*price, U128(1000));
+                assert_eq!(*timestamp, 20);
+            }
+            _ => panic!("expected a sale"),
+        }
+        assert!(matches!(
+            &feed[1],
+            ActivityEvent::NewItem { item_id, timestamp: 10 } if item_id == "item1"
+        ));
+
+        let page = contract.get_store_activity_feed(accounts(2), Some(1), Some(1));
+        assert!(matches!(page[0], ActivityEvent::NewItem { .. }));
+    }
+
+    #[test]
+    fn test_buy_after_acknowledging_terms() {
+        let mut context = VMContextBuilder::new();
+        let (mut contract, _) = setup_group_buy(&mut context);
+        contract
+            .set_store_acceptance_terms(accounts(2), Some("https://store.url/terms".to_string()));
+
+        testing_env!(context
+            .signer_account_id(accounts(3))
+            .predecessor_account_id(accounts(3))
+            .attached_deposit(1000)
+            .build());
+        assert!(!contract.has_accepted_terms(accounts(2), accounts(3)));
+        contract.acknowledge_store_terms(accounts(2));
+        assert!(contract.has_accepted_terms(accounts(2), accounts(3)));
+        contract.buy("item1".to_string(), accounts(2));
+    }
+
+    #[test]
+    #[should_panic(expected = "StoreHub: must acknowledge terms first")]
+    fn test_buy_without_acknowledging_terms() {
+        let mut context = VMContextBuilder::new();
+        let (mut contract, _) = setup_group_buy(&mut context);
+        contract
+            .set_store_acceptance_terms(accounts(2), Some("https://store.url/terms".to_string()));
+
+        testing_env!(context
+            .signer_account_id(accounts(3))
+            .predecessor_account_id(accounts(3))
+            .attached_deposit(1000)
+            .build());
+        contract.buy("item1".to_string(), accounts(2));
+    }
+
+    #[test]
+    fn test_buy_age_restricted_item() {
+        let mut context = VMContextBuilder::new();
+        let (mut contract, _) = setup_group_buy(&mut context);
+        contract.set_item_age_restricted("item1".to_string(), accounts(2), true);
+
+        testing_env!(context
+            .signer_account_id(accounts(3))
+            .predecessor_account_id(accounts(3))
+            .attached_deposit(1000)
+            .build());
+        contract.attest_age(21);
+        assert!(contract.has_attested_age(accounts(3), 18));
+        assert!(!contract.has_attested_age(accounts(3), 25));
+        contract.buy("item1".to_string(), accounts(2));
+    }
+
+    #[test]
+    #[should_panic(expected = "StoreHub: age attestation required")]
+    fn test_buy_age_restricted_item_underage() {
+        let mut context = VMContextBuilder::new();
+        let (mut contract, _) = setup_group_buy(&mut context);
+        contract.set_item_age_restricted("item1".to_string(), accounts(2), true);
+
+        testing_env!(context
+            .signer_account_id(accounts(3))
+            .predecessor_account_id(accounts(3))
+            .attached_deposit(1000)
+            .build());
+        contract.attest_age(16);
+        contract.buy("item1".to_string(), accounts(2));
+    }
+
+    #[test]
+    fn test_buy_with_dynamic_pricing() {
+        let mut context = VMContextBuilder::new();
+        let (mut contract, _) = setup_group_buy(&mut context);
+        contract.set_dynamic_pricing("item1".to_string(), accounts(2), 1_000, Some(U128(1150)));
+
+        for (buyer, deposit, new_price) in [(3, 1000, 1100), (4, 1100, 1150)] {
+            testing_env!(context
+                .signer_account_id(accounts(buyer))
+                .predecessor_account_id(accounts(buyer))
+                .attached_deposit(deposit)
+                .build());
+            contract.buy("item1".to_string(), accounts(2));
+
+            let metadata = contract
+                .get_item_metadata("item1".to_string(), accounts(2))
+                .unwrap();
+            assert_eq!(metadata.price, U128(new_price));
+
+            let log = contract.audit_logs.iter().last().unwrap();
+            let extra: serde_json::Value = serde_json::from_str(&log.extra).unwrap();
+            assert_eq!(extra["new_price"], new_price.to_string());
+        }
+    }
+
+    #[test]
+    fn test_platform_announcements() {
+        let mut context = VMContextBuilder::new();
+        testing_env!(context.signer_account_id(accounts(0)).build());
+
+        let mut contract = Contract::new(accounts(0));
+        let id = contract.post_announcement(
+            "Maintenance".to_string(),
+            "Downtime at noon".to_string(),
+            Some(100),
+        );
+        testing_env!(context.block_timestamp(10).build());
+        contract.post_announcement("Policy".to_string(), "New fees".to_string(), None);
+        assert_eq!(contract.get_active_announcements().len(), 2);
+
+        testing_env!(context.block_timestamp(100).build());
+        let active = contract.get_active_announcements();
+        assert_eq!(active.len(), 1);
+        assert_eq!(active[0].title, "Policy");
+
+        contract.delete_announcement(id);
+        assert_eq!(contract.platform_announcements.len(), 1);
+    }
+
+    #[test]
+    #[should_panic(expected = "StoreHub: cannot have more than 10 announcements")]
+    fn test_post_too_many_announcements() {
+        let mut context = VMContextBuilder::new();
+        testing_env!(context.signer_account_id(accounts(0)).build());
+
+        let mut contract = Contract::new(accounts(0));
+        for timestamp in 0..=10 {
+            testing_env!(context.block_timestamp(timestamp).build());
+            contract.post_announcement("Notice".to_string(), "".to_string(), None);
+        }
+    }
+
+    #[test]
+    fn test_stake_for_store() {
+        let mut context = VMContextBuilder::new();
+        let (mut contract, _) = setup_group_buy(&mut context);
+
+        for (staker, deposit) in [(3, 500), (4, 300), (3, 200)] {
+            testing_env!(context
+                .predecessor_account_id(accounts(staker))
+                .attached_deposit(deposit)
+                .build());
+            contract.stake_for_store(accounts(2));
+        }
+        assert_eq!(contract.get_store_total_stake(accounts(2)), U128(1000));
+        assert_eq!(
+            contract.get_staker_stake(accounts(2), accounts(3)),
+            U128(700)
+        );
+
+        testing_env!(context
+            .predecessor_account_id(accounts(3))
+            .attached_deposit(0)
+            .build());
+        contract.unstake_from_store(accounts(2), U128(700));
+        assert_eq!(contract.get_store_total_stake(accounts(2)), U128(300));
+        assert_eq!(contract.get_staker_stake(accounts(2), accounts(3)), U128(0));
+
+        let receipts = get_created_receipts();
+        assert_eq!(receipts[0].receiver_id, accounts(3));
+        assert_eq!(
+            receipts[0].actions,
+            vec![VmAction::Transfer { deposit: 700 }]
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "StoreHub: insufficient stake")]
+    fn test_unstake_more_than_staked() {
+        let mut context = VMContextBuilder::new();
+        let (mut contract, _) = setup_group_buy(&mut context);
+
+        testing_env!(context
+            .predecessor_account_id(accounts(3))
+            .attached_deposit(500)
+            .build());
+        contract.stake_for_store(accounts(2));
+        contract.unstake_from_store(accounts(2), U128(501));
+    }
+
+    #[test]
+    fn test_item_waitlist() {
+        let mut context = VMContextBuilder::new();
+        let (mut contract, _) = setup_group_buy(&mut context);
+
+        testing_env!(context
+            .signer_account_id(accounts(3))
+            .attached_deposit(1000)
+            .build());
+        contract.buy("item1".to_string(), accounts(2));
+
+        for account in [4, 5] {
+            testing_env!(context
+                .signer_account_id(accounts(account))
+                .attached_deposit(0)
+                .build());
+            contract.join_item_waitlist("item1".to_string(), accounts(2));
+        }
+        assert_eq!(
+            contract.get_waitlist_position("item1".to_string(), accounts(2), accounts(5)),
+            Some(2)
+        );
+
+        testing_env!(context.signer_account_id(accounts(4)).build());
+        contract.leave_item_waitlist("item1".to_string(), accounts(2));
+        assert_eq!(
+            contract.get_waitlist_position("item1".to_string(), accounts(2), accounts(5)),
+            Some(1)
+        );
+
+        assert_eq!(
+            contract.get_item_waitlist("item1".to_string(), accounts(2)),
+            vec![accounts(5)]
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "StoreHub: item is in stock")]
+    fn test_join_waitlist_of_item_in_stock() {
+        let mut context = VMContextBuilder::new();
+        let (mut contract, _) = setup_group_buy(&mut context);
+
+        testing_env!(context.signer_account_id(accounts(3)).build());
+        contract.join_item_waitlist("item1".to_string(), accounts(2));
+    }
+
+    #[test]
+    fn test_item_certificates() {
+        let mut context = VMContextBuilder::new();
+        let (mut contract, _) = setup_group_buy(&mut context);
+
+        testing_env!(context.signer_account_id(accounts(0)).build());
+        contract.add_certified_issuer(accounts(3));
+
+        testing_env!(context.signer_account_id(accounts(3)).build());
+        contract.add_item_certificate(
+            "item1".to_string(),
+            accounts(2),
+            "authenticity".to_string(),
+            "cert-1".to_string(),
+            Some(100),
+            "http://cert.url".to_string(),
+        );
+        assert!(contract.is_item_certified("item1".to_string(), accounts(2)));
+
+        testing_env!(context.block_timestamp(100).build());
+        assert!(!contract.is_item_certified("item1".to_string(), accounts(2)));
+        assert_eq!(
+            contract
+                .get_item_certificates("item1".to_string(), accounts(2))
+                .len(),
+            1
+        );
+
+        contract.revoke_item_certificate("item1".to_string(), accounts(2), "cert-1".to_string());
+        assert!(contract
+            .get_item_certificates("item1".to_string(), accounts(2))
+            .is_empty());
+    }
+
+    #[test]
+    #[should_panic(expected = "StoreHub: not a certified issuer")]
+    fn test_add_item_certificate_by_unknown_issuer() {
+        let mut context = VMContextBuilder::new();
+        let (mut contract, _) = setup_group_buy(&mut context);
+
+        testing_env!(context.signer_account_id(accounts(3)).build());
+        contract.add_item_certificate(
+            "item1".to_string(),
+            accounts(2),
+            "authenticity".to_string(),
+            "cert-1".to_string(),
+            None,
+            "http://cert.url".to_string(),
+        );
+    }
+
+    #[test]
+    fn test_loyalty_tiers() {
+        let mut context = VMContextBuilder::new();
+        let (mut contract, _) = setup_group_buy(&mut context);
+
+        testing_env!(context.signer_account_id(accounts(0)).build());
+        contract.create_loyalty_tier("Gold".to_string(), U128(5000), "Free shipping".to_string());
+        contract.create_loyalty_tier("Bronze".to_string(), U128(500), "Early access".to_string());
+        assert_eq!(contract.loyalty_tiers[0].name, "Bronze");
+        assert_eq!(
+            contract.get_loyalty_tier_benefits("Gold".to_string()),
+            Some("Free shipping".to_string())
+        );
+        assert!(contract.get_loyalty_tier_for_account(accounts(3)).is_none());
+
+        testing_env!(context
+            .signer_account_id(accounts(3))
+            .attached_deposit(1000)
+            .build());
+        contract.buy("item1".to_string(), accounts(2));
+        assert_eq!(
+            contract
+                .get_loyalty_tier_for_account(accounts(3))
+                .unwrap()
+                .name,
+            "Bronze"
+        );
+    }
+
+    #[test]
+    fn test_buy_with_partner_discount() {
+        let mut context = VMContextBuilder::new();
+        let (mut contract, _) = setup_group_buy(&mut context);
+        contract.create_store(accounts(4));
+        contract.add_store_item(
+            "item2".to_string(),
+            accounts(4),
+            "item_name".to_string(),
+            U128(1000),
+            "http://image.url".to_string(),
+            None,
+            None,
+            None,
+        );
+        let partnership_id = contract.propose_partnership(accounts(2), accounts(4), 1_000);
+        contract.accept_partnership(partnership_id);
+        assert_eq!(contract.get_store_partnerships(accounts(4)).len(), 1);
+
+        for (item, store, deposit) in [("item1", 2, 1000), ("item2", 4, 900)] {
+            testing_env!(context
+                .signer_account_id(accounts(3))
+                .predecessor_account_id(accounts(3))
+                .attached_deposit(deposit)
+                .build());
+            contract.buy(item.to_string(), accounts(store));
+        }
+
+        let log = contract.audit_logs.iter().last().unwrap();
+        let extra: serde_json::Value = serde_json::from_str(&log.extra).unwrap();
+        assert_eq!(extra["partner_discount"], "100");
+    }
+
+    #[test]
+    fn test_dissolved_partnership_discount() {
+        let mut context = VMContextBuilder::new();
+        let (mut contract, _) = setup_group_buy(&mut context);
+        contract.create_store(accounts(4));
+
+        let partnership_id = contract.propose_partnership(accounts(2), accounts(4), 1_000);
+        contract.accept_partnership(partnership_id.clone());
+        assert_eq!(
+            contract.accepted_partnerships_by_store.get(&accounts(4)),
+            Some(vec![partnership_id.clone()])
+        );
+
+        contract.dissolve_partnership(partnership_id);
+        assert_eq!(
+            contract.accepted_partnerships_by_store.get(&accounts(2)),
+            Some(vec![])
+        );
+        assert_eq!(
+            contract.internal_partner_discount_bps(&accounts(3), &accounts(4)),
+            0
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "StoreHub: partnership doesn't exist")]
+    fn test_accept_dissolved_partnership() {
+        let mut context = VMContextBuilder::new();
+        let (mut contract, _) = setup_group_buy(&mut context);
+        contract.create_store(accounts(4));
+
+        let partnership_id = contract.propose_partnership(accounts(2), accounts(4), 1_000);
+        contract.dissolve_partnership(partnership_id.clone());
+        contract.accept_partnership(partnership_id);
+    }
+
+    #[test]
+    fn test_item_barcodes() {
+        let mut context = VMContextBuilder::new();
+        let (mut contract, _) = setup_group_buy(&mut context);
+
+        for (item_id, upc) in [("item2", "036000291452"), ("item3", "4006381333931")] {
+            contract.add_store_item(
+                item_id.to_string(),
+                accounts(2),
+                item_id.to_string(),
+                U128(1000),
+                "http://image.url".to_string(),
+                None,
+                Some(upc.to_string()),
+                None,
+            );
+        }
+        assert!(contract.does_barcode_exist("036000291452".to_string()));
+        assert_eq!(
+            contract
+                .get_item_by_barcode("4006381333931".to_string(), accounts(2))
+                .unwrap()
+                .upc,
+            Some("4006381333931".to_string())
+        );
+        assert!(contract
+            .get_item_by_barcode("4006381333931".to_string(), accounts(3))
+            .is_none());
+
+        contract.update_item_metadata(
+            "item2".to_string(),
+            accounts(2),
+            None,
+            None,
+            None,
+            Some("036000291469".to_string()),
+            None,
+        );
+        assert!(!contract.does_barcode_exist("036000291452".to_string()));
+        assert!(contract.does_barcode_exist("036000291469".to_string()));
+    }
+
+    #[test]
+    #[should_panic(expected = "StoreHub: invalid barcode")]
+    fn test_add_item_with_invalid_barcode() {
+        let mut context = VMContextBuilder::new();
+        let (mut contract, _) = setup_group_buy(&mut context);
+        contract.add_store_item(
+            "item2".to_string(),
+            accounts(2),
+            "item_name".to_string(),
+            U128(1000),
+            "http://image.url".to_string(),
+            None,
+            Some("03600029145A".to_string()),
+            None,
+        );
+    }
+
+    fn banner(store_id: AccountId, starts_at: u64, ends_at: u64) -> FeaturedBanner {
+        FeaturedBanner {
+            store_id,
+            image_url: "http://banner.url".to_string(),
+            cta_text: "Shop now".to_string(),
+            link_url: "http://store.url".to_string(),
+            starts_at,
+            ends_at,
+        }
+    }
+
+    #[test]
+    fn test_featured_banners() {
+        let mut context = VMContextBuilder::new();
+        let (mut contract, _) = setup_group_buy(&mut context);
+
+        testing_env!(context.signer_account_id(accounts(0)).build());
+        contract.set_banner_fee(U128(100));
+
+        testing_env!(context.attached_deposit(100).build());
+        contract.set_featured_banner(banner(accounts(2), 0, 10));
+        contract.set_featured_banner(banner(accounts(2), 10, 20));
+        assert_eq!(contract.get_treasury_balance(), U128(200));
+        assert_eq!(contract.get_active_featured_banners()[0].ends_at, 10);
+
+        testing_env!(context.block_timestamp(10).build());
+        assert_eq!(contract.get_active_featured_banners()[0].ends_at, 20);
+
+        contract.remove_featured_banner(accounts(2));
+        assert!(contract.get_active_featured_banners().is_empty());
+    }
+
+    #[test]
+    #[should_panic(expected = "StoreHub: cannot have more than 5 active banners")]
+    fn test_too_many_featured_banners() {
+        let mut context = VMContextBuilder::new();
+        let (mut contract, _) = setup_group_buy(&mut context);
+
+        testing_env!(context.signer_account_id(accounts(0)).build());
+        for _ in 0..=5 {
+            contract.set_featured_banner(banner(accounts(2), 0, 10));
+        }
+    }
+
+    #[test]
+    fn test_item_video() {
+        let mut context = VMContextBuilder::new();
+        let (mut contract, _) = setup_group_buy(&mut context);
+        contract.update_item_metadata(
+            "item1".to_string(),
+            accounts(2),
+            None,
+            None,
+            None,
+            None,
+            Some("https://video.url/item1.mp4".to_string()),
+        );
+        assert_eq!(contract.get_items_with_video(accounts(2)).len(), 1);
+
+        for timestamp in [0, VIDEO_VIEW_COOLDOWN - 1, VIDEO_VIEW_COOLDOWN] {
+            testing_env!(context
+                .signer_account_id(accounts(3))
+                .block_timestamp(timestamp)
+                .build());
+            contract.record_video_view("item1".to_string(), accounts(2));
+        }
+        assert_eq!(
+            contract.get_item_video_views("item1".to_string(), accounts(2)),
+            2
+        );
+    }
+
+    #[test]
+    fn test_https_url_validation() {
+        assert!(is_https_url("https://video.url/item1.mp4"));
+        assert!(!is_https_url(""));
+        assert!(!is_https_url("https://"));
+        assert!(!is_https_url("http://video.url/item1.mp4"));
+        assert!(!is_https_url("video.url/item1.mp4"));
+    }
+
+    #[test]
+    #[should_panic(expected = "StoreHub: invalid video url")]
+    fn test_add_item_with_invalid_video_url() {
+        let mut context = VMContextBuilder::new();
+        let (mut contract, _) = setup_group_buy(&mut context);
+        contract.add_store_item(
+            "item2".to_string(),
+            accounts(2),
+            "item_name".to_string(),
+            U128(1000),
+            "http://image.url".to_string(),
+            None,
+            None,
+            Some("ftp://video.url/item2.mp4".to_string()),
+        );
+    }
+
+    fn crosschain_link(item_id: &str) -> CrossChainLink {
+        CrossChainLink {
+            chain: "ethereum".to_string(),
+            contract: "0x00000000000000000000000000000000000000aa".to_string(),
+            item_id: item_id.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_crosschain_links() {
+        let mut context = VMContextBuilder::new();
+        let (mut contract, _) = setup_group_buy(&mut context);
+
+        testing_env!(context.signer_account_id(accounts(0)).build());
+        contract.approve_chain("ethereum".to_string());
+
+        testing_env!(context.signer_account_id(accounts(1)).build());
+        contract.add_crosschain_link("item1".to_string(), accounts(2), crosschain_link("1"));
+        contract.add_crosschain_link("item1".to_string(), accounts(2), crosschain_link("2"));
+        contract.remove_crosschain_link("item1".to_string(), accounts(2), crosschain_link("1"));
+        assert_eq!(
+            contract.get_crosschain_links("item1".to_string(), accounts(2)),
+            vec![crosschain_link("2")]
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "StoreHub: chain not approved")]
+    fn test_add_crosschain_link_to_unapproved_chain() {
+        let mut context = VMContextBuilder::new();
+        let (mut contract, _) = setup_group_buy(&mut context);
+        contract.add_crosschain_link("item1".to_string(), accounts(2), crosschain_link("1"));
+    }
+
+    #[test]
+    #[should_panic(expected = "StoreHub: cannot have more than 5 cross-chain links")]
+    fn test_too_many_crosschain_links() {
+        let mut context = VMContextBuilder::new();
+        let (mut contract, _) = setup_group_buy(&mut context);
+
+        testing_env!(context.signer_account_id(accounts(0)).build());
+        contract.approve_chain("ethereum".to_string());
+
+        testing_env!(context.signer_account_id(accounts(1)).build());
+        for item_id in 0..=5 {
+            contract.add_crosschain_link(
+                "item1".to_string(),
+                accounts(2),
+                crosschain_link(&item_id.to_string()),
+            );
+        }
+    }
+
+    #[test]
+    fn test_overseer_transfer_item_ownership() {
+        let mut context = VMContextBuilder::new();
+        let (mut contract, _) = setup_group_buy(&mut context);
+
+        testing_env!(context
+            .signer_account_id(accounts(3))
+            .attached_deposit(1000)
+            .build());
+        contract.buy("item1".to_string(), accounts(2));
+
+        testing_env!(context
+            .signer_account_id(accounts(0))
+            .predecessor_account_id(accounts(0))
+            .attached_deposit(0)
+            .build());
+        contract.overseer_transfer_item_ownership(
+            "item1".to_string(),
+            accounts(2),
+            accounts(4),
+            "account compromised".to_string(),
+        );
+
+        let metadata = contract
+            .get_item_metadata("item1".to_string(), accounts(2))
+            .unwrap();
+        assert_eq!(metadata.owner, accounts(4));
+
+        let log = contract.audit_logs.iter().last().unwrap();
+        assert_eq!(log.action, "admin_ownership_override");
+        let extra: serde_json::Value = serde_json::from_str(&log.extra).unwrap();
+        assert_eq!(extra["old_owner"], accounts(3).to_string());
+        assert_eq!(extra["new_owner"], accounts(4).to_string());
+        assert_eq!(extra["reason"], "account compromised");
+
+        let announcements = contract.get_active_announcements();
+        assert_eq!(announcements[0].body, "account compromised");
+    }
+
+    #[test]
+    fn test_calculate_checkout_total() {
+        let mut context = VMContextBuilder::new();
+        let (mut contract, _) = setup_group_buy(&mut context);
+        contract.create_store(accounts(4));
+        contract.add_store_item(
+            "item2".to_string(),
+            accounts(4),
+            "item_name".to_string(),
+            U128(2000),
+            "http://image.url".to_string(),
+            None,
+            None,
+            None,
+        );
+        contract.set_item_shipping_info(
+            "item2".to_string(),
+            accounts(4),
+            ShippingInfo {
+                ships_from_region: "NG".to_string(),
+                estimated_days_min: 2,
+                estimated_days_max: 5,
+                flat_shipping_fee: U128(50),
+                free_shipping_threshold: None,
+            },
+        );
+        contract.set_store_sale_period(accounts(2), 1_000, 0, 20);
+
+        testing_env!(context.signer_account_id(accounts(0)).build());
+        contract.set_protocol_fee(100);
+
+        let summary = contract.calculate_checkout_total(vec![
+            ("item1".to_string(), accounts(2), 1, None),
+            ("item2".to_string(), accounts(4), 1, None),
+        ]);
+        assert_eq!(summary.line_items[0].total, U128(900));
+        assert_eq!(summary.line_items[1].total, U128(2050));
+        assert_eq!(summary.subtotal, U128(3000));
+        assert_eq!(summary.total_discount, U128(100));
+        assert_eq!(summary.total_shipping, U128(50));
+        assert_eq!(summary.protocol_fee, U128(29));
+        assert_eq!(summary.grand_total, U128(2950));
+    }
+
+    #[test]
+    fn test_search_items_by_tags() {
+        let mut context = VMContextBuilder::new();
+        let (mut contract, _) = setup_group_buy(&mut context);
+        for item_id in ["item2", "item3"] {
+            contract.add_store_item(
+                item_id.to_string(),
+                accounts(2),
+                item_id.to_string(),
+                U128(1000),
+                "http://image.url".to_string(),
+                None,
+                None,
+                None,
+            );
+        }
+        let tags = |tags: &[&str]| tags.iter().map(|tag| tag.to_string()).collect::<Vec<_>>();
+        contract.set_item_tags(
+            "item1".to_string(),
+            accounts(2),
+            tags(&["Vintage", "shoes"]),
+        );
+        contract.set_item_tags(
+            "item2".to_string(),
+            accounts(2),
+            tags(&["shoes", "leather"]),
+        );
+        contract.set_item_tags(
+            "item3".to_string(),
+            accounts(2),
+            tags(&["vintage", "leather"]),
+        );
+
+        let search = |tags: Vec<String>, match_all: bool| -> Vec<StoreAndItemIds> {
+            contract
+                .search_items_by_tags(tags, match_all, None, None)
+                .into_iter()
+                .map(|(storeanditem_id, _)| storeanditem_id)
+                .collect()
+        };
+        let id = |item_id: &str| store_and_item_id(&accounts(2), item_id);
+        assert_eq!(search(tags(&["vintage", "shoes"]), true), vec![id("item1")]);
+        assert_eq!(
+            search(tags(&["vintage", "shoes"]), false),
+            vec![id("item1"), id("item2"), id("item3")]
+        );
+        assert!(search(tags(&["vintage", "unknown"]), true).is_empty());
+
+        contract.remove_store_item("item1".to_string(), accounts(2));
+        let matches = contract.search_items_by_tags(tags(&["vintage"]), false, None, None);
+        assert_eq!(matches.len(), 1);
+        assert_eq!(matches[0].0, id("item3"));
+    }
+
+    #[test]
+    fn test_get_item_price_in_near() {
+        let mut context = VMContextBuilder::new();
+        testing_env!(context
+            .signer_account_id(accounts(0))
+            .predecessor_account_id(accounts(0))
+            .build());
+
+        let mut contract = Contract::new(accounts(0));
+        contract.create_store(accounts(2));
+        contract.set_store_currency(accounts(2), Some(test_account()));
+        contract.add_store_item(
+            "item1".to_string(),
+            accounts(2),
+            "item_name".to_string(),
+            U128(1000),
+            "http://image.url".to_string(),
+            None,
+            None,
+            None,
+        );
+        contract.set_ft_exchange_rate(test_account(), U128(3));
+        assert_eq!(contract.get_ft_exchange_rate(test_account()), Some(U128(3)));
+        assert_eq!(
+            contract.get_item_price_in_near("item1".to_string(), accounts(2)),
+            U128(3000)
+        );
+
+        contract.create_store(accounts(3));
+        contract.add_store_item(
+            "item1".to_string(),
+            accounts(3),
+            "item_name".to_string(),
+            U128(1000),
+            "http://image.url".to_string(),
+            None,
+            None,
+            None,
+        );
+        assert_eq!(
+            contract.get_item_price_in_near("item1".to_string(), accounts(3)),
+            U128(1000)
+        );
+    }
+
+    #[test]
+    fn test_overseer_multisig() {
+        let mut context = VMContextBuilder::new();
+        testing_env!(context.signer_account_id(accounts(0)).build());
+
+        let mut contract = Contract::new(accounts(0));
+        contract.setup_overseer_multisig(vec![accounts(1), accounts(2), accounts(3)], 2);
+        assert_eq!(
+            contract.get_overseer_multisig(),
+            Some((vec![accounts(1), accounts(2), accounts(3)], 2))
+        );
+
+        testing_env!(context.signer_account_id(accounts(1)).build());
+        let action_id =
+            contract.propose_admin_action("set_protocol_fee".to_string(), "250".to_string());
+        assert_eq!(contract.get_protocol_fee(), 0);
+        assert_eq!(contract.get_pending_admin_actions().len(), 1);
+
+        testing_env!(context.signer_account_id(accounts(3)).build());
+        contract.approve_admin_action(action_id);
+        assert_eq!(contract.get_protocol_fee(), 250);
+        assert!(contract.get_pending_admin_actions().is_empty());
+    }
+
+    #[test]
+    #[should_panic(expected = "StoreHub: action requires multisig approval")]
+    fn test_set_protocol_fee_with_multisig() {
+        let mut context = VMContextBuilder::new();
+        testing_env!(context.signer_account_id(accounts(0)).build());
+
+        let mut contract = Contract::new(accounts(0));
+        contract.setup_overseer_multisig(vec![accounts(1), accounts(2)], 2);
+        contract.set_protocol_fee(250);
+    }
+
+    #[test]
+    fn test_withdraw_treasury_with_multisig() {
+        let mut context = VMContextBuilder::new();
+        testing_env!(context.signer_account_id(accounts(0)).build());
+
+        let mut contract = Contract::new(accounts(0));
+        contract.treasury_balance = 500;
+        contract.setup_overseer_multisig(vec![accounts(1), accounts(2)], 2);
+
+        testing_env!(context.signer_account_id(accounts(1)).build());
+        let action_id =
+            contract.propose_admin_action("withdraw_treasury".to_string(), "200".to_string());
+        assert_eq!(contract.treasury_balance, 500);
+
+        testing_env!(context.signer_account_id(accounts(2)).build());
+        contract.approve_admin_action(action_id);
+        assert_eq!(contract.treasury_balance, 300);
+        let receipts = get_created_receipts();
+        assert_eq!(receipts[0].receiver_id, accounts(0));
+        assert_eq!(
+            receipts[0].actions,
+            vec![VmAction::Transfer { deposit: 200 }]
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "StoreHub: action requires multisig approval")]
+    fn test_withdraw_treasury_bypassing_multisig() {
+        let mut context = VMContextBuilder::new();
+        testing_env!(context.signer_account_id(accounts(0)).build());
+
+        let mut contract = Contract::new(accounts(0));
+        contract.treasury_balance = 500;
+        contract.setup_overseer_multisig(vec![accounts(1), accounts(2)], 2);
+        contract.withdraw_treasury(U128(500));
+    }
+
+    #[test]
+    #[should_panic(expected = "StoreHub: already approved")]
+    fn test_approve_admin_action_twice() {
+        let mut context = VMContextBuilder::new();
+        testing_env!(context.signer_account_id(accounts(0)).build());
+
+        let mut contract = Contract::new(accounts(0));
+        contract.setup_overseer_multisig(vec![accounts(1), accounts(2)], 2);
+
+        testing_env!(context.signer_account_id(accounts(1)).build());
+        let action_id =
+            contract.propose_admin_action("add_ft".to_string(), "usdc.near".to_string());
+        contract.approve_admin_action(action_id);
+    }
+
+    #[test]
+    fn test_assign_store_to_dao() {
+        let mut context = VMContextBuilder::new();
+        let (mut contract, _) = setup_group_buy(&mut context);
+        let dao: AccountId = "dao.sputnik-dao.near".parse().unwrap();
+
+        contract.assign_store_to_dao(accounts(2), dao.clone());
+        assert!(contract.is_dao_controlled(accounts(2)));
+        assert!(!contract.is_dao_controlled(accounts(3)));
+        assert_eq!(
+            contract.get_owners_by_store_id(accounts(2)),
+            vec![dao.clone()]
+        );
+
+        testing_env!(context.predecessor_account_id(dao).build());
+        contract.update_item_metadata(
+            "item1".to_string(),
+            accounts(2),
+            Some("renamed".to_string()),
+            None,
+            None,
+            None,
+            None,
+        );
+        assert_eq!(
+            contract
+                .get_item_metadata("item1".to_string(), accounts(2))
+                .unwrap()
+                .name,
+            "renamed"
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "StoreHub: access denied")]
+    fn test_dao_controlled_store_rejects_managers() {
+        let mut context = VMContextBuilder::new();
+        let (mut contract, _) = setup_group_buy(&mut context);
+        contract.set_store_role(accounts(2), accounts(3), StoreRole::Manager);
+        contract.assign_store_to_dao(accounts(2), "dao.sputnik-dao.near".parse().unwrap());
+
+        testing_env!(context.predecessor_account_id(accounts(3)).build());
+        contract.update_item_metadata(
+            "item1".to_string(),
+            accounts(2),
+            Some("renamed".to_string()),
+            None,
+            None,
+            None,
+            None,
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "StoreHub: store has several owners")]
+    fn test_assign_co_owned_store_to_dao() {
+        let mut context = VMContextBuilder::new();
+        let (mut contract, _) = setup_group_buy(&mut context);
+        contract.add_store_owners(accounts(2), accounts(3));
+        contract.assign_store_to_dao(accounts(2), "dao.sputnik-dao.near".parse().unwrap());
+    }
+
+    #[test]
+    fn test_generate_store_report() {
+        let mut context = VMContextBuilder::new();
+        testing_env!(context
+            .signer_account_id(accounts(0))
+            .block_timestamp(100)
+            .build());
+
+        let mut contract = Contract::new(accounts(0));
+        contract.create_store(accounts(2));
+        contract.add_store_owners(accounts(2), accounts(1));
+        for (item_id, price) in [("item1", 1000), ("item2", 3000)] {
+            contract.add_store_item(
+                item_id.to_string(),
+                accounts(2),
+                item_id.to_string(),
+                U128(price),
+                "http://image.url".to_string(),
+                None,
+                None,
+                None,
+            );
+        }
+
+        testing_env!(context
+            .signer_account_id(accounts(3))
+            .attached_deposit(1000)
+            .block_timestamp(200)
+            .build());
+        contract.buy("item1".to_string(), accounts(2));
+        contract.rate_store(accounts(2), 4, "fine".to_string());
+
+        assert_eq!(
+            contract.generate_store_report(accounts(2)),
+            StoreReport {
+                store_id: accounts(2),
+                owner_count: 2,
+                item_count: 2,
+                total_sales: 1,
+                total_revenue: U128(1000),
+                average_item_price: U128(2000),
+                creation_timestamp: Some(100),
+                average_rating: Some(4),
+                latest_sale_timestamp: Some(200),
+            }
+        );
+    }
+
+    #[test]
+    fn test_get_stores_by_age() {
+        let mut context = VMContextBuilder::new();
+        testing_env!(context.block_timestamp(100).build());
+
+        let mut contract = Contract::new(accounts(0));
+        contract.create_store(accounts(2));
+        testing_env!(context.block_timestamp(200).build());
+        contract.create_store(accounts(3));
+        contract.create_store(accounts(2));
+
+        assert_eq!(contract.get_store_created_at(accounts(2)), Some(100));
+        assert_eq!(contract.get_store_created_at(accounts(4)), None);
+        assert_eq!(
+            contract.get_stores_by_age(None, None),
+            vec![(accounts(3), 200), (accounts(2), 100)]
+        );
+        assert_eq!(
+            contract.get_stores_by_age(Some(1), Some(1)),
+            vec![(accounts(2), 100)]
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "StoreHub: item supply exhausted")]
+    fn test_item_supply_cap() {
+        let mut context = VMContextBuilder::new();
+        let (mut contract, _) = setup_group_buy(&mut context);
+        contract.set_item_supply_cap("item1".to_string(), accounts(2), 1);
+        assert_eq!(
+            contract.get_item_remaining_supply("item1".to_string(), accounts(2)),
+            1
+        );
+
+        testing_env!(context
+            .signer_account_id(accounts(3))
+            .attached_deposit(1000)
+            .build());
+        contract.buy("item1".to_string(), accounts(2));
+        assert_eq!(
+            contract.get_item_remaining_supply("item1".to_string(), accounts(2)),
+            0
+        );
+
+        // a resale would be a second sale of the item
+        testing_env!(context.signer_account_id(accounts(4)).build());
+        contract.buy("item1".to_string(), accounts(2));
+    }
+
+    #[test]
+    fn test_relisted_item_drops_supply_cap() {
+        let mut context = VMContextBuilder::new();
+        let (mut contract, _) = setup_group_buy(&mut context);
+        contract.set_item_supply_cap("item1".to_string(), accounts(2), 10);
+
+        contract.remove_store_item("item1".to_string(), accounts(2));
+        contract.add_store_item(
+            "item1".to_string(),
+            accounts(2),
+            "item_name".to_string(),
+            U128(1000),
+            "http://image.url".to_string(),
+            None,
+            None,
+            None,
+        );
+        assert_eq!(
+            contract.get_item_supply_cap("item1".to_string(), accounts(2)),
+            None
+        );
+        assert_eq!(
+            contract.get_item_remaining_supply("item1".to_string(), accounts(2)),
+            u64::MAX
+        );
 
-        assert_eq!(contract.overseer_id, accounts(0).into());
+        contract.set_item_supply_cap("item1".to_string(), accounts(2), 20);
     }
 
     #[test]
-    fn test_create_store() {
+    #[should_panic(expected = "StoreHub: supply cap already set")]
+    fn test_set_item_supply_cap_twice() {
         let mut context = VMContextBuilder::new();
-        testing_env!(context.signer_account_id(accounts(1)).build());
-
-        let mut contract = Contract::new(accounts(0));
-        contract.create_store(accounts(2));
-
-        assert_eq!(contract.get_stores_by_account_id(), vec![accounts(2)]);
+        let (mut contract, _) = setup_group_buy(&mut context);
+        contract.set_item_supply_cap("item1".to_string(), accounts(2), 10);
+        contract.set_item_supply_cap("item1".to_string(), accounts(2), 20);
     }
 
     #[test]
-    fn test_add_store_owners() {
+    fn test_item_charity_split() {
         let mut context = VMContextBuilder::new();
-        testing_env!(context.signer_account_id(accounts(1)).build());
-
-        let mut contract = Contract::new(accounts(0));
-        contract.create_store(accounts(2));
-        contract.add_store_owners(accounts(2), accounts(3));
+        let (mut contract, _) = setup_group_buy(&mut context);
+        contract.set_item_charity_split("item1".to_string(), accounts(2), accounts(5), 1_000);
+        assert_eq!(
+            contract.get_item_charity_split("item1".to_string(), accounts(2)),
+            Some((accounts(5), 1_000))
+        );
 
-        let owners = contract.get_owners_by_store_id(accounts(2));
+        testing_env!(context
+            .signer_account_id(accounts(3))
+            .attached_deposit(1000)
+            .build());
+        contract.buy("item1".to_string(), accounts(2));
 
-        assert!(owners.contains(&accounts(3)));
+        let receipts = get_created_receipts();
+        assert_eq!(receipts[0].receiver_id, accounts(5));
+        assert_eq!(
+            receipts[0].actions,
+            vec![VmAction::Transfer { deposit: 100 }]
+        );
+        assert_eq!(receipts[1].receiver_id, accounts(2));
+        assert_eq!(
+            receipts[1].actions,
+            vec![VmAction::Transfer { deposit: 900 }]
+        );
+        assert_eq!(
+            contract.get_total_donated_by_item("item1".to_string(), accounts(2)),
+            U128(100)
+        );
     }
 
     #[test]
-    fn test_add_store_item() {
+    fn test_relisted_item_drops_charity_split() {
         let mut context = VMContextBuilder::new();
-        testing_env!(context.signer_account_id(accounts(1)).build());
-
-        let mut contract = Contract::new(accounts(0));
-        contract.create_store(accounts(2));
-
-        let item_id = "item1".to_string();
-        let item_name = "item_name".to_string();
-        let item_price = U128(1000);
-        let item_img_url = "http://image.url".to_string();
+        let (mut contract, _) = setup_group_buy(&mut context);
+        contract.set_item_charity_split("item1".to_string(), accounts(2), accounts(5), 1_000);
+        contract.set_item_rental_rate("item1".to_string(), accounts(2), Some(U128(3_600)));
 
+        contract.remove_store_item("item1".to_string(), accounts(2));
         contract.add_store_item(
-            item_id.clone(),
+            "item1".to_string(),
             accounts(2),
-            item_name.clone(),
-            item_price,
-            item_img_url.clone(),
+            "item_name".to_string(),
+            U128(1000),
+            "http://image.url".to_string(),
+            None,
+            None,
+            None,
         );
 
-        let item = contract.get_item_by_store_id(item_id.clone());
-        match item {
-            Some(metadata) => {
-                assert_eq!(metadata.name, item_name);
-                assert_eq!(metadata.price, item_price);
-                assert_eq!(metadata.img_url, item_img_url);
-                assert_eq!(metadata.owner, accounts(2));
-            }
-            None => {
-                assert!(false, "item doesn't exist");
-            }
-        }
+        assert_eq!(
+            contract.get_item_charity_split("item1".to_string(), accounts(2)),
+            None
+        );
+        assert_eq!(
+            contract.get_item_rental_rate("item1".to_string(), accounts(2)),
+            None
+        );
     }
 
     #[test]
-    #[should_panic(expected = "StoreHub: item doesn't exist")]
-    fn test_buy_nonexistent_item() {
+    #[should_panic(expected = "StoreHub: charity split too high")]
+    fn test_item_charity_split_too_high() {
+        let mut context = VMContextBuilder::new();
+        let (mut contract, _) = setup_group_buy(&mut context);
+        contract.set_item_charity_split("item1".to_string(), accounts(2), accounts(5), 5_001);
+    }
+
+    #[test]
+    fn test_rent_item() {
         let mut context = VMContextBuilder::new();
+        let (mut contract, _) = setup_group_buy(&mut context);
+        contract.set_item_rental_rate("item1".to_string(), accounts(2), Some(U128(3_600)));
+
         testing_env!(context
-            .signer_account_id(accounts(1))
-            .attached_deposit(1000)
+            .signer_account_id(accounts(3))
+            .attached_deposit(7_200)
+            .block_timestamp(0)
             .build());
+        let rental_id = contract.rent_item("item1".to_string(), accounts(2), 7_200);
+        assert!(contract.is_item_rented_by("item1".to_string(), accounts(2), accounts(3)));
+        assert!(!contract.is_item_rented_by("item1".to_string(), accounts(2), accounts(4)));
+        assert_eq!(
+            contract
+                .get_item_metadata("item1".to_string(), accounts(2))
+                .unwrap()
+                .owner,
+            accounts(2)
+        );
+        assert_eq!(contract.get_store_balance(accounts(2)), U128(7_200));
 
-        let mut contract = Contract::new(accounts(0));
-        contract.create_store(accounts(2));
+        testing_env!(context.block_timestamp(7_200 * 1_000_000_000).build());
+        assert!(!contract.is_item_rented_by("item1".to_string(), accounts(2), accounts(3)));
+        contract.expire_rental(rental_id.clone());
+        assert_eq!(contract.get_rental(rental_id), None);
+        assert!(contract
+            .rentals_by_item_and_renter
+            .get(&(store_and_item_id(&accounts(2), "item1"), accounts(3)))
+            .is_none());
+    }
 
-        contract.buy("item1".to_string(), accounts(2));
+    #[test]
+    #[should_panic(expected = "StoreHub: rental duration too long")]
+    fn test_rent_item_too_long() {
+        let mut context = VMContextBuilder::new();
+        let (mut contract, _) = setup_group_buy(&mut context);
+        contract.set_item_rental_rate("item1".to_string(), accounts(2), Some(U128(1)));
+
+        testing_env!(context
+            .signer_account_id(accounts(3))
+            .attached_deposit(1_000_000)
+            .build());
+        contract.rent_item("item1".to_string(), accounts(2), u64::MAX);
     }
 
     #[test]
-    #[should_panic(expected = "StoreHub: this item doesn't exist for this store")]
-    fn test_buy_existent_wrong_store_item() {
+    #[should_panic(expected = "StoreHub: rental has not expired")]
+    fn test_expire_active_rental() {
         let mut context = VMContextBuilder::new();
+        let (mut contract, _) = setup_group_buy(&mut context);
+        contract.set_item_rental_rate("item1".to_string(), accounts(2), Some(U128(3_600)));
+
         testing_env!(context
-            .signer_account_id(accounts(1))
-            .attached_deposit(1000)
+            .signer_account_id(accounts(3))
+            .attached_deposit(3_600)
             .build());
+        let rental_id = contract.rent_item("item1".to_string(), accounts(2), 3_600);
+        contract.expire_rental(rental_id);
+    }
 
-        let mut contract = Contract::new(accounts(0));
-        contract.create_store(accounts(2));
+    #[test]
+    fn test_get_item_by_name_and_store() {
+        let mut context = VMContextBuilder::new();
+        let (mut contract, _) = setup_group_buy(&mut context);
+        contract.add_store_item(
+            "item2".to_string(),
+            accounts(2),
+            "Cool  Sneakers".to_string(),
+            U128(1000),
+            "http://image.url".to_string(),
+            None,
+            None,
+            None,
+        );
+        assert_eq!(normalize_item_name("Cool  Sneakers"), "cool-sneakers");
+        assert_eq!(
+            contract.get_item_id_by_name(accounts(2), "cool-sneakers".to_string()),
+            Some("item2".to_string())
+        );
+        assert_eq!(
+            contract
+                .get_item_by_name_and_store(accounts(2), "cool-sneakers".to_string())
+                .unwrap()
+                .name,
+            "Cool  Sneakers"
+        );
 
-        let item_id = "item1".to_string();
-        let item_name = "item_name".to_string();
-        let item_price = U128(1000);
-        let item_img_url = "http://image.url".to_string();
+        contract.update_item_metadata(
+            "item2".to_string(),
+            accounts(2),
+            Some("Hot Sneakers".to_string()),
+            None,
+            None,
+            None,
+            None,
+        );
+        assert_eq!(
+            contract.get_item_id_by_name(accounts(2), "cool-sneakers".to_string()),
+            None
+        );
+        assert_eq!(
+            contract.get_item_id_by_name(accounts(2), "hot-sneakers".to_string()),
+            Some("item2".to_string())
+        );
+    }
 
+    #[test]
+    #[should_panic(expected = "StoreHub: item name already in use")]
+    fn test_add_item_with_duplicate_name() {
+        let mut context = VMContextBuilder::new();
+        let (mut contract, _) = setup_group_buy(&mut context);
         contract.add_store_item(
-            item_id.clone(),
+            "item2".to_string(),
             accounts(2),
-            item_name.clone(),
-            item_price,
-            item_img_url.clone(),
+            "ITEM_NAME".to_string(),
+            U128(1000),
+            "http://image.url".to_string(),
+            None,
+            None,
+            None,
         );
+    }
 
-        contract.buy("item1".to_string(), accounts(3));
+    #[test]
+    fn test_item_images() {
+        let mut context = VMContextBuilder::new();
+        let (mut contract, _) = setup_group_buy(&mut context);
+        for image in ["front", "back", "side"] {
+            contract.add_item_image(
+                "item1".to_string(),
+                accounts(2),
+                format!("https://image.url/{}.png", image),
+            );
+        }
+        contract.remove_item_image("item1".to_string(), accounts(2), 0);
+        assert_eq!(
+            contract.get_item_images("item1".to_string(), accounts(2)),
+            vec![
+                "https://image.url/back.png".to_string(),
+                "https://image.url/side.png".to_string()
+            ]
+        );
     }
 
     #[test]
-    #[should_panic(expected = "StoreHub: can't buy owned item")]
-    fn test_buy_owned_item() {
+    #[should_panic(expected = "StoreHub: invalid image url")]
+    fn test_add_item_image_over_http() {
         let mut context = VMContextBuilder::new();
-        testing_env!(context.signer_account_id(accounts(1)).build());
+        let (mut contract, _) = setup_group_buy(&mut context);
+        contract.add_item_image(
+            "item1".to_string(),
+            accounts(2),
+            "http://image.url/front.png".to_string(),
+        );
+    }
 
-        let mut contract = Contract::new(accounts(0));
-        contract.create_store(accounts(2));
+    #[test]
+    fn test_item_cost_basis() {
+        let mut context = VMContextBuilder::new();
+        let (mut contract, _) = setup_group_buy(&mut context);
+        contract.set_item_cost_basis("item1".to_string(), accounts(2), U128(800), 1_000);
+        assert_eq!(
+            contract.get_item_effective_margin_bps("item1".to_string(), accounts(2)),
+            Some(2_500)
+        );
+
+        let results = contract.update_item_price_batch(
+            accounts(2),
+            vec![("item1".to_string(), U128(870))],
+            false,
+        );
+        assert_eq!(results, vec![("item1".to_string(), false)]);
+
+        contract.update_item_price_batch(accounts(2), vec![("item1".to_string(), U128(880))], true);
+        assert_eq!(
+            contract.get_item_effective_margin_bps("item1".to_string(), accounts(2)),
+            Some(1_000)
+        );
+    }
+
+    #[test]
+    fn test_item_effective_margin_bounds() {
+        let mut context = VMContextBuilder::new();
+        let (mut contract, _) = setup_group_buy(&mut context);
+        contract.set_item_cost_basis("item1".to_string(), accounts(2), U128(2000), 0);
         contract.add_store_item(
-            "item1".to_string(),
+            "item2".to_string(),
             accounts(2),
-            "item_name".to_string(),
-            U128(1000),
+            "item2".to_string(),
+            U128(u128::MAX),
             "http://image.url".to_string(),
+            None,
+            None,
+            None,
         );
+        contract.set_item_cost_basis("item2".to_string(), accounts(2), U128(1), 0);
 
-        testing_env!(context
-            .signer_account_id(accounts(2))
-            .attached_deposit(2000)
-            .build());
+        testing_env!(context.predecessor_account_id(accounts(3)).build());
+        assert_eq!(
+            contract.get_item_effective_margin_bps("item1".to_string(), accounts(2)),
+            Some(-5_000)
+        );
+        assert_eq!(
+            contract.get_item_effective_margin_bps("item2".to_string(), accounts(2)),
+            Some(i64::MAX)
+        );
+    }
 
-        contract.buy("item1".to_string(), accounts(2));
+    #[test]
+    #[should_panic(expected = "StoreHub: price below cost floor")]
+    fn test_schedule_price_change_below_cost_floor() {
+        let mut context = VMContextBuilder::new();
+        let (mut contract, _) = setup_group_buy(&mut context);
+        contract.set_item_cost_basis("item1".to_string(), accounts(2), U128(800), 0);
+        contract.schedule_price_change("item1".to_string(), accounts(2), U128(799), 1);
+    }
+
+    fn review_media(url: &str, media_type: &str) -> ReviewMedia {
+        ReviewMedia {
+            url: url.to_string(),
+            media_type: media_type.to_string(),
+        }
     }
 
     #[test]
-    #[should_panic(expected = "StoreHub: deposit is below price")]
-    fn test_buy_with_insufficient_deposit() {
+    fn test_review_media_validation() {
+        assert!(is_valid_review_media(&review_media(
+            "https://media.url/1.png",
+            "image"
+        )));
+        assert!(is_valid_review_media(&review_media(
+            "https://media.url/1.mp4",
+            "video"
+        )));
+        assert!(!is_valid_review_media(&review_media(
+            "http://media.url/1.png",
+            "image"
+        )));
+        assert!(!is_valid_review_media(&review_media("https://", "image")));
+        assert!(!is_valid_review_media(&review_media(
+            "https://media.url/1.gif",
+            "gif"
+        )));
+    }
+
+    #[test]
+    fn test_review_with_media_moderation() {
         let mut context = VMContextBuilder::new();
+        let (mut contract, _) = setup_group_buy(&mut context);
+
         testing_env!(context
             .signer_account_id(accounts(3))
-            .attached_deposit(500)
+            .attached_deposit(1000)
             .build());
-
-        let mut contract = Contract::new(accounts(0));
-        contract.create_store(accounts(2));
-        contract.add_store_item(
+        contract.buy("item1".to_string(), accounts(2));
+        contract.rate_item(
             "item1".to_string(),
             accounts(2),
-            "item_name".to_string(),
-            U128(1000),
-            "http://image.url".to_string(),
+            5,
+            "great".to_string(),
+            Some(vec![review_media("https://media.url/1.png", "image")]),
         );
+        let reviews = contract.get_item_reviews_with_media("item1".to_string(), accounts(2));
+        assert_eq!(reviews.len(), 1);
+        assert_eq!(reviews[0].media[0].url, "https://media.url/1.png");
 
-        contract.buy("item1".to_string(), accounts(2));
+        let review_id = format!(
+            "{}.{}",
+            store_and_item_id(&accounts(2), "item1"),
+            accounts(3)
+        );
+        testing_env!(context.signer_account_id(accounts(4)).build());
+        contract.report_review(review_id.clone(), "spam".to_string());
+        assert_eq!(
+            contract.get_reported_reviews(None, None),
+            vec![(review_id.clone(), vec![(accounts(4), "spam".to_string())])]
+        );
+
+        testing_env!(context.signer_account_id(accounts(0)).build());
+        contract.remove_review(review_id);
+        assert!(contract
+            .get_item_ratings("item1".to_string(), accounts(2), None, None)
+            .is_empty());
+        assert!(contract.get_reported_reviews(None, None).is_empty());
     }
 
     #[test]
-    fn test_buy_item() {
+    #[should_panic(expected = "StoreHub: review removed by moderation")]
+    fn test_rate_item_after_review_removed() {
         let mut context = VMContextBuilder::new();
+        let (mut contract, _) = setup_group_buy(&mut context);
+
         testing_env!(context
             .signer_account_id(accounts(3))
-            .attached_deposit(2000)
+            .attached_deposit(1000)
             .build());
-
-        let mut contract = Contract::new(accounts(0));
-        contract.create_store(accounts(2));
-        contract.add_store_item(
+        contract.buy("item1".to_string(), accounts(2));
+        contract.rate_item(
             "item1".to_string(),
             accounts(2),
-            "item_name".to_string(),
-            U128(1000),
-            "http://image.url".to_string(),
+            1,
+            "spam".to_string(),
+            None,
         );
 
-        let response = contract.buy("item1".to_string(), accounts(2));
-        let response: serde_json::Value = serde_json::from_str(&response).unwrap();
+        testing_env!(context.signer_account_id(accounts(0)).build());
+        contract.remove_review(format!(
+            "{}.{}",
+            store_and_item_id(&accounts(2), "item1"),
+            accounts(3)
+        ));
 
-        assert_eq!(response["message"], "your purchase is ready");
-        assert!(response["transaction_id"].is_string());
+        testing_env!(context.signer_account_id(accounts(3)).build());
+        contract.rate_item(
+            "item1".to_string(),
+            accounts(2),
+            1,
+            "spam".to_string(),
+            None,
+        );
     }
 
     #[test]
-    fn test_add_transaction() {
+    #[should_panic(expected = "StoreHub: cannot attach more than 5 media")]
+    fn test_rate_item_with_too_much_media() {
         let mut context = VMContextBuilder::new();
-        testing_env!(context.signer_account_id(accounts(1)).build());
+        let (mut contract, _) = setup_group_buy(&mut context);
 
-        let mut contract = Contract::new(accounts(0));
-        let log_id = contract.add_log(
-            "action".to_string(),
-            "actor".to_string(),
-            "entity".to_string(),
-            "extra".to_string(),
+        testing_env!(context
+            .signer_account_id(accounts(3))
+            .attached_deposit(1000)
+            .build());
+        contract.buy("item1".to_string(), accounts(2));
+        contract.rate_item(
+            "item1".to_string(),
+            accounts(2),
+            5,
+            "great".to_string(),
+            Some(vec![review_media("https://media.url/1.png", "image"); 6]),
         );
+    }
 
-        let log = contract.get_log(log_id.clone());
+    #[test]
+    fn test_store_webhook_event() {
+        let mut context = VMContextBuilder::new();
+        let (mut contract, _) = setup_group_buy(&mut context);
+        contract.set_store_webhook_url(
+            accounts(2),
+            Some("https://hooks.store.url/sales".to_string()),
+        );
 
-        assert_eq!(log.id, log_id);
-        assert_eq!(log.action, "action");
-        assert_eq!(log.actor, "actor");
-        assert_eq!(log.entity, "entity");
-        assert_eq!(log.extra, "extra");
+        testing_env!(context
+            .signer_account_id(accounts(3))
+            .predecessor_account_id(accounts(3))
+            .attached_deposit(1000)
+            .build());
+        contract.buy("item1".to_string(), accounts(2));
+        assert_eq!(
+            contract.get_store_webhook_url(accounts(2)),
+            Some("https://hooks.store.url/sales".to_string())
+        );
+
+        let logs = get_logs();
+        assert!(logs[0].starts_with("EVENT_JSON:"));
+        assert!(logs[0].contains("\"event\":\"store_sale\""));
+        assert!(logs[0].contains("\"webhook_url\":\"https://hooks.store.url/sales\""));
     }
 
     #[test]
-    #[should_panic(expected = "StoreHub: access denied")]
-    fn test_add_ft_denied() {
+    fn test_get_item_price_history() {
         let mut context = VMContextBuilder::new();
-        testing_env!(context.signer_account_id(accounts(1)).build());
+        let (mut contract, _) = setup_group_buy(&mut context);
 
-        let mut contract = Contract::new(accounts(0));
-        contract.add_ft(accounts(2));
+        testing_env!(context.block_timestamp(10).build());
+        contract.update_item_price_batch(
+            accounts(2),
+            vec![("item1".to_string(), U128(1500))],
+            true,
+        );
+        contract.set_dynamic_pricing("item1".to_string(), accounts(2), 1_000, None);
+
+        testing_env!(context
+            .signer_account_id(accounts(3))
+            .attached_deposit(1500)
+            .block_timestamp(20)
+            .build());
+        contract.buy("item1".to_string(), accounts(2));
+
+        testing_env!(context
+            .signer_account_id(accounts(0))
+            .attached_deposit(0)
+            .block_timestamp(30)
+            .build());
+        contract.overseer_override_item_price("item1".to_string(), accounts(2), U128(900));
+
+        assert_eq!(
+            contract.get_item_price_history("item1".to_string(), accounts(2), None, None),
+            vec![(U128(1000), 10), (U128(1500), 20), (U128(1650), 30)]
+        );
+        assert_eq!(
+            contract.get_item_price_history("item1".to_string(), accounts(2), Some(2), Some(5)),
+            vec![(U128(1650), 30)]
+        );
     }
 
     #[test]
-    fn test_add_ft_approved() {
+    fn test_items_by_age() {
         let mut context = VMContextBuilder::new();
-        testing_env!(context.signer_account_id(accounts(0)).build());
-
-        let mut contract = Contract::new(accounts(0));
-        contract.add_ft(accounts(2));
+        testing_env!(context.block_timestamp(0).build());
+        let (mut contract, _) = setup_group_buy(&mut context);
+        for (item_id, added_at) in [("item3", 30), ("item2", 20), ("item4", 30)] {
+            testing_env!(context.block_timestamp(added_at).build());
+            contract.add_store_item(
+                item_id.to_string(),
+                accounts(2),
+                item_id.to_string(),
+                U128(1000),
+                "http://image.url".to_string(),
+                None,
+                None,
+                None,
+            );
+        }
 
-        assert!(contract.is_ft_approved(accounts(2)));
+        let item_ids = |items: Vec<(ItemId, ItemMetadata)>| -> Vec<ItemId> {
+            items.into_iter().map(|(item_id, _)| item_id).collect()
+        };
+        assert_eq!(
+            item_ids(contract.get_items_added_since(accounts(2), 20, None, None)),
+            vec!["item3", "item4", "item2"]
+        );
+        assert_eq!(
+            item_ids(contract.get_items_added_since(accounts(2), 20, Some(1), Some(1))),
+            vec!["item4"]
+        );
+        assert_eq!(
+            item_ids(contract.get_newest_items_by_store(accounts(2), 10)),
+            vec!["item3", "item4", "item2", "item1"]
+        );
     }
 }